@@ -0,0 +1,208 @@
+use super::event::{BTreeMapDiff, BTreeMapEvent};
+use crate::structural_signal::pull_source::{
+    PullSourceHost, PullSourceStructuralSignal, StructrualSignalPullSource,
+};
+use im::OrdMap;
+use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::hash::Hash;
+use std::iter::Iterator;
+use std::sync::Arc;
+
+/// The internal state of a MutableBTreeMap or MutableBTreeMapReader. All
+/// clones (and readonly clones) will share this same instance.
+///
+/// Keys must be `Hash` as well as `Ord`, because pending diffs are coalesced by key
+/// in a HashMap, exactly like they are for a MutableHashMap.
+#[derive(Debug)]
+pub struct MutableBTreeMapState<K: Clone + Ord + Hash, V: Clone> {
+    btree_map: OrdMap<K, V>,
+    pull_source: StructrualSignalPullSource<BTreeMapDiff<K>>,
+}
+
+impl<K: Clone + Ord + Hash, V: Clone> PullSourceHost for MutableBTreeMapState<K, V> {
+    type DiffType = BTreeMapDiff<K>;
+    type EventType = BTreeMapEvent<K, V>;
+
+    fn get_pull_source(&mut self) -> &mut StructrualSignalPullSource<Self::DiffType> {
+        &mut self.pull_source
+    }
+
+    fn make_event(&self, diffs: Vec<Self::DiffType>) -> Self::EventType {
+        BTreeMapEvent {
+            snapshot: self.btree_map.clone(),
+            diffs,
+        }
+    }
+
+    fn make_granular_diffs(&self) -> Vec<Self::DiffType> {
+        self.btree_map
+            .keys()
+            .map(|key| BTreeMapDiff::Insert { key: key.clone() })
+            .collect()
+    }
+}
+
+/// A BTreeMap that can be observed as it changes over time. Unlike MutableHashMap,
+/// its entries are always kept sorted by key.
+///
+/// This structure is backed by `im.OrdMap` and so requires that keys and values
+/// are clonable. The backing structure is optimized to clone only when necessary.
+pub struct MutableBTreeMap<K: Clone + Ord + Hash, V: Clone>(
+    Arc<RwLock<MutableBTreeMapState<K, V>>>,
+);
+
+impl<K: Clone + Ord + Hash, V: Clone> Clone for MutableBTreeMap<K, V> {
+    fn clone(&self) -> Self {
+        MutableBTreeMap(Arc::new(RwLock::new(MutableBTreeMapState {
+            btree_map: self.0.read().btree_map.clone(),
+            pull_source: StructrualSignalPullSource::new(),
+        })))
+    }
+}
+
+impl<K: Clone + Ord + Hash, V: Clone> Default for MutableBTreeMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Clone + Ord + Hash, V: Clone> MutableBTreeMap<K, V> {
+    /// Returns a readonly view into the underlying state that can be used
+    /// to read values from the BTreeMap.
+    #[inline]
+    pub fn read(&self) -> RwLockReadGuard<'_, MutableBTreeMapState<K, V>> {
+        self.0.read()
+    }
+
+    /// Returns a writer into the underlying state that can be used to
+    /// modify the BTreeMap.
+    #[inline]
+    pub fn write(&self) -> RwLockWriteGuard<'_, MutableBTreeMapState<K, V>> {
+        self.0.write()
+    }
+
+    pub fn new() -> Self {
+        MutableBTreeMap(Arc::new(RwLock::new(MutableBTreeMapState {
+            btree_map: OrdMap::new(),
+            pull_source: StructrualSignalPullSource::new(),
+        })))
+    }
+
+    /// Creates a read-only view into this data structure. This Reader object
+    /// can lookup items in this map at any time, but cannot modify it.
+    /// Readers can be cloned. Note that Readers can hold a ReadLock which can
+    /// block MutableBTreeMap::write() calls, so use them with care.
+    #[inline]
+    pub fn reader(&self) -> MutableBTreeMapReader<K, V> {
+        MutableBTreeMapReader(self.0.clone())
+    }
+
+    /// Creates a signal that tracks the value of this BTreeMap. Signals can be directly
+    /// used for UI, or can be transformed with SignalBTreeMapExt.
+    #[inline]
+    pub fn as_signal(&self) -> PullSourceStructuralSignal<MutableBTreeMapState<K, V>> {
+        PullSourceStructuralSignal::new(self.0.clone())
+    }
+}
+
+/// A read-only view into a MutableBTreeMap.
+pub struct MutableBTreeMapReader<K: Clone + Ord + Hash, V: Clone>(
+    Arc<RwLock<MutableBTreeMapState<K, V>>>,
+);
+
+impl<K: Clone + Ord + Hash, V: Clone> Clone for MutableBTreeMapReader<K, V> {
+    #[inline]
+    fn clone(&self) -> Self {
+        MutableBTreeMapReader(self.0.clone())
+    }
+}
+
+impl<K: Clone + Ord + Hash, V: Clone> MutableBTreeMapReader<K, V> {
+    /// Returns a readonly view into the underlying state that can be used
+    /// to read values from the BTreeMap.
+    #[inline]
+    pub fn read(&self) -> RwLockReadGuard<'_, MutableBTreeMapState<K, V>> {
+        self.0.read()
+    }
+
+    /// Creates a signal that tracks the value of this BTreeMap. Signals can be directly
+    /// used for UI, or can be transformed with SignalBTreeMapExt.
+    #[inline]
+    pub fn as_signal(&self) -> PullSourceStructuralSignal<MutableBTreeMapState<K, V>> {
+        PullSourceStructuralSignal::new(self.0.clone())
+    }
+}
+
+impl<K: Clone + Ord + Hash, V: Clone> MutableBTreeMapState<K, V> {
+    #[inline]
+    fn add_diff(&mut self, diff: BTreeMapDiff<K>) {
+        self.pull_source.add_diff(diff);
+    }
+
+    /// Gets the current value of a key, if it exists.
+    #[inline]
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.btree_map.get(key)
+    }
+
+    /// Returns true if the map currently containes a value for the given key.
+    #[inline]
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.btree_map.contains_key(key)
+    }
+
+    /// Creates an immutable snapshot of the current state of this BTreeMap. This
+    /// operation is fairly cheap thanks to the backing Immutable data structure.
+    /// Future changes to this MutableBTreeMap will not alter the snapshot.
+    #[inline]
+    pub fn snapshot(&self) -> OrdMap<K, V> {
+        self.btree_map.clone()
+    }
+
+    /// Replaces the entire contents of this BTreeMap with new entries. All existing
+    /// data will be cleared. Like `clear`, replacing an empty BTreeMap with no entries
+    /// does nothing.
+    pub fn replace<E>(&mut self, entries: E)
+    where
+        E: Iterator<Item = (K, V)>,
+    {
+        let was_empty = self.btree_map.is_empty();
+        self.btree_map = entries.collect();
+        if was_empty && self.btree_map.is_empty() {
+            return;
+        }
+        self.add_diff(BTreeMapDiff::Replace {});
+    }
+
+    /// Inserts a new value into this BTreeMap at a given key.
+    pub fn insert(&mut self, k: K, v: V) -> Option<V> {
+        let remember_k = k.clone();
+        let result = self.btree_map.insert(k, v);
+
+        if result.is_none() {
+            self.add_diff(BTreeMapDiff::Insert { key: remember_k });
+        } else {
+            self.add_diff(BTreeMapDiff::Update { key: remember_k });
+        }
+        result
+    }
+
+    /// Removes and returns the value at a given key, if it exists.
+    pub fn remove(&mut self, k: &K) -> Option<V> {
+        let result = self.btree_map.remove(k);
+        if result.is_some() {
+            self.add_diff(BTreeMapDiff::Remove { key: k.clone() });
+        }
+        result
+    }
+
+    /// Removes every value in this BTreeMap.
+    pub fn clear(&mut self) {
+        if self.btree_map.is_empty() {
+            return;
+        }
+
+        self.btree_map.clear();
+        self.add_diff(BTreeMapDiff::Clear {})
+    }
+}
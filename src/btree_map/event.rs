@@ -0,0 +1,81 @@
+use crate::hash_map::MapDiff;
+use crate::structural_signal::pull_source::coalesce_diffs;
+use crate::structural_signal::structural_signal_ext::{
+    MergeableEvent, SnapshottableEvent, SplittableEvent,
+};
+use core::hash::Hash;
+use im::OrdMap;
+
+/// The diffs of a BTreeMap. These are the same as the diffs of a HashMap, since both
+/// are keyed structures that merge diffs on the same key in the same way.
+pub type BTreeMapDiff<K> = MapDiff<K>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BTreeMapEvent<K, V>
+where
+    K: Clone + Ord + Hash,
+    V: Clone,
+{
+    pub snapshot: OrdMap<K, V>,
+    pub diffs: Vec<BTreeMapDiff<K>>,
+}
+
+impl<K, V> BTreeMapEvent<K, V>
+where
+    K: Clone + Ord + Hash,
+    V: Clone,
+{
+    /// Creates an event directly from a snapshot and a list of diffs. This is mostly
+    /// useful for feeding hand-crafted events into a transformer during testing.
+    pub fn new(snapshot: OrdMap<K, V>, diffs: Vec<BTreeMapDiff<K>>) -> BTreeMapEvent<K, V> {
+        BTreeMapEvent { snapshot, diffs }
+    }
+}
+
+impl<K, V> SnapshottableEvent for BTreeMapEvent<K, V>
+where
+    K: Clone + Ord + Hash,
+    V: Clone,
+{
+    type SnapshotType = OrdMap<K, V>;
+
+    fn snapshot(&self) -> Self::SnapshotType {
+        self.snapshot.clone()
+    }
+}
+
+impl<K, V> MergeableEvent for BTreeMapEvent<K, V>
+where
+    K: Clone + Ord + Hash,
+    V: Clone,
+{
+    fn merge(self, next: BTreeMapEvent<K, V>) -> BTreeMapEvent<K, V> {
+        let mut diffs = self.diffs;
+        diffs.extend(next.diffs);
+        BTreeMapEvent {
+            snapshot: next.snapshot,
+            diffs: coalesce_diffs(diffs),
+        }
+    }
+}
+
+impl<K, V> SplittableEvent for BTreeMapEvent<K, V>
+where
+    K: Clone + Ord + Hash,
+    V: Clone,
+{
+    fn split(self) -> Vec<BTreeMapEvent<K, V>> {
+        if self.diffs.len() <= 1 {
+            return vec![self];
+        }
+        let snapshot = self.snapshot;
+        self.diffs
+            .into_iter()
+            .map(|diff| BTreeMapEvent {
+                snapshot: snapshot.clone(),
+                diffs: vec![diff],
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,94 @@
+use super::event::{BTreeMapDiff, BTreeMapEvent};
+use crate::structural_signal::pull_source::PullSourceStructuralSignal;
+use crate::structural_signal::transformer::StructuralSignalTransformer;
+use crate::vector::{MutableVector, MutableVectorState};
+use core::hash::Hash;
+use im::Vector;
+
+// ** ENTRIES ** //
+
+pub struct EntriesBTreeMapTransformer<K, V>
+where
+    K: Ord + Hash + Clone,
+    V: Clone,
+{
+    vector: MutableVector<(K, V)>,
+}
+
+impl<K, V> EntriesBTreeMapTransformer<K, V>
+where
+    K: Ord + Hash + Clone,
+    V: Clone,
+{
+    pub(crate) fn new() -> EntriesBTreeMapTransformer<K, V> {
+        EntriesBTreeMapTransformer {
+            vector: MutableVector::new(),
+        }
+    }
+}
+
+#[inline]
+fn search_entries<K: Ord + Clone, V: Clone>(
+    entries: &Vector<(K, V)>,
+    key: &K,
+) -> Result<usize, usize> {
+    entries.binary_search_by(|(entry_key, _)| entry_key.cmp(key))
+}
+
+impl<K, V> StructuralSignalTransformer for EntriesBTreeMapTransformer<K, V>
+where
+    K: Ord + Hash + Clone,
+    V: Clone,
+{
+    type InputEvent = BTreeMapEvent<K, V>;
+    type OutputSignal = PullSourceStructuralSignal<MutableVectorState<(K, V)>>;
+
+    fn apply_event(&mut self, map_event: BTreeMapEvent<K, V>) {
+        let mut writer = self.vector.write();
+        for diff in map_event.diffs {
+            match diff {
+                BTreeMapDiff::Replace {} => {
+                    // OrdMap already iterates in key order.
+                    writer.replace(map_event.snapshot.clone().into_iter());
+                }
+                BTreeMapDiff::Insert { key } => {
+                    let val = map_event.snapshot.get(&key).unwrap().clone();
+                    match search_entries(&writer, &key) {
+                        Result::Ok(_) => {
+                            panic!(
+                                "Found existing value for newly-inserted key in BTreeMap.entries()"
+                            );
+                        }
+                        Result::Err(index) => {
+                            writer.insert(index, (key, val));
+                        }
+                    }
+                }
+                BTreeMapDiff::Update { key } => {
+                    let val = map_event.snapshot.get(&key).unwrap().clone();
+                    match search_entries(&writer, &key) {
+                        Result::Ok(index) => {
+                            writer.set(index, (key, val));
+                        }
+                        Result::Err(_) => {
+                            panic!("Found no existing value for updated key in BTreeMap.entries()");
+                        }
+                    }
+                }
+                BTreeMapDiff::Remove { key } => {
+                    if let Result::Ok(index) = search_entries(&writer, &key) {
+                        writer.remove(index);
+                    }
+                }
+                BTreeMapDiff::Clear {} => {
+                    writer.clear();
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn get_signal(&self) -> Self::OutputSignal {
+        self.vector.as_signal()
+    }
+}
@@ -0,0 +1,9 @@
+mod btree_map;
+mod event;
+mod map_transforms;
+mod signal_ext;
+
+pub use btree_map::{MutableBTreeMap, MutableBTreeMapReader};
+pub use event::{BTreeMapDiff, BTreeMapEvent};
+pub use map_transforms::EntriesBTreeMapTransformer;
+pub use signal_ext::SignalBTreeMapExt;
@@ -0,0 +1,61 @@
+use super::event::BTreeMapEvent;
+use super::map_transforms::EntriesBTreeMapTransformer;
+use crate::structural_signal::transformer::TransformedStructuralSignal;
+use crate::StructuralSignal;
+use core::hash::Hash;
+
+pub trait SignalBTreeMapExt: StructuralSignal
+where
+    Self: Sized,
+{
+    type Key: Clone + Ord + Hash;
+    type Value: Clone;
+    type SelfType: StructuralSignal<Item = BTreeMapEvent<Self::Key, Self::Value>>;
+
+    /// Returns a vector signal of every (key, value) pair in this map, sorted by key.
+    /// Inserting a key emits a single Insert diff at its sorted position.
+    ///
+    /// ```
+    /// use signals_im::btree_map::{MutableBTreeMap, SignalBTreeMapExt};
+    /// use signals_im::StructuralSignalExt;
+    /// use im::vector;
+    ///
+    /// let input_map = MutableBTreeMap::<u8, u8>::new();
+    /// input_map.write().insert(3, 30);
+    /// input_map.write().insert(1, 10);
+    ///
+    /// let entries_signal = input_map.as_signal().entries();
+    /// input_map.write().insert(2, 20);
+    ///
+    /// let entries = entries_signal.snapshot().unwrap();
+    /// assert_eq!(entries, vector![(1, 10), (2, 20), (3, 30)]);
+    /// ```
+    fn entries(
+        self,
+    ) -> TransformedStructuralSignal<
+        Self::SelfType,
+        <Self::SelfType as StructuralSignal>::Item,
+        EntriesBTreeMapTransformer<Self::Key, Self::Value>,
+    >;
+}
+
+impl<K, V, I> SignalBTreeMapExt for I
+where
+    I: StructuralSignal<Item = BTreeMapEvent<K, V>>,
+    K: Clone + Ord + Hash,
+    V: Clone,
+{
+    type Key = K;
+    type Value = V;
+    type SelfType = I;
+
+    fn entries(
+        self,
+    ) -> TransformedStructuralSignal<
+        Self,
+        Self::Item,
+        EntriesBTreeMapTransformer<Self::Key, Self::Value>,
+    > {
+        TransformedStructuralSignal::new(self, EntriesBTreeMapTransformer::new())
+    }
+}
@@ -0,0 +1,116 @@
+use super::event::HashMapEvent;
+use super::hash_map::{MutableHashMap, MutableHashMapState};
+use crate::structural_signal::pull_source::PullSourceStructuralSignal;
+use crate::StructuralSignal;
+use core::hash::Hash;
+use futures_signals::signal_map::{MapDiff as SignalMapDiff, SignalMap};
+use pin_project::pin_project;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Adapts a `futures_signals` SignalMap into a hash map StructuralSignal. Created by
+/// `signal_map_to_structural`.
+#[pin_project(project = SignalMapStructuralSignalProj)]
+#[must_use = "StructuralSignals do nothing unless polled"]
+pub struct SignalMapStructuralSignal<S, K, V>
+where
+    K: Clone + Eq + Hash,
+    V: Clone,
+    S: SignalMap<Key = K, Value = V>,
+{
+    #[pin]
+    inner: S,
+    hash_map: MutableHashMap<K, V>,
+    #[pin]
+    output: PullSourceStructuralSignal<MutableHashMapState<K, V>>,
+    is_closed: bool,
+}
+
+/// Brings a `futures_signals` SignalMap, such as the signal of a `MutableBTreeMap`,
+/// into this crate. Every MapDiff is applied to an internal MutableHashMap, and the
+/// returned StructuralSignal emits that MutableHashMap's events, coalesced like any
+/// other.
+///
+/// ```
+/// use futures_signals::signal_map::MutableBTreeMap;
+/// use signals_im::hash_map::signal_map_to_structural;
+/// use signals_im::StructuralSignalExt;
+/// use im::hashmap;
+///
+/// let input_map = MutableBTreeMap::<u8, u8>::new();
+/// input_map.lock_mut().insert(1, 1);
+/// let signal = signal_map_to_structural(input_map.signal_map());
+/// assert_eq!(signal.snapshot().unwrap(), hashmap!{1 => 1});
+/// ```
+pub fn signal_map_to_structural<S, K, V>(signal_map: S) -> SignalMapStructuralSignal<S, K, V>
+where
+    K: Clone + Eq + Hash,
+    V: Clone,
+    S: SignalMap<Key = K, Value = V>,
+{
+    let hash_map = MutableHashMap::new();
+    let output = hash_map.as_signal();
+    SignalMapStructuralSignal {
+        inner: signal_map,
+        hash_map,
+        output,
+        is_closed: false,
+    }
+}
+
+impl<S, K, V> SignalMapStructuralSignal<S, K, V>
+where
+    K: Clone + Eq + Hash,
+    V: Clone,
+    S: SignalMap<Key = K, Value = V>,
+{
+    fn apply_map_diff(writer: &mut MutableHashMapState<K, V>, diff: SignalMapDiff<K, V>) {
+        match diff {
+            SignalMapDiff::Replace { entries } => writer.replace(entries.into_iter()),
+            SignalMapDiff::Insert { key, value } | SignalMapDiff::Update { key, value } => {
+                writer.insert(key, value);
+            }
+            SignalMapDiff::Remove { key } => {
+                writer.remove(&key);
+            }
+            SignalMapDiff::Clear {} => writer.clear(),
+        }
+    }
+}
+
+impl<S, K, V> StructuralSignal for SignalMapStructuralSignal<S, K, V>
+where
+    K: Clone + Eq + Hash,
+    V: Clone,
+    S: SignalMap<Key = K, Value = V>,
+{
+    type Item = HashMapEvent<K, V>;
+
+    #[inline]
+    fn poll_change(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<HashMapEvent<K, V>>> {
+        let SignalMapStructuralSignalProj {
+            mut inner,
+            hash_map,
+            output,
+            is_closed,
+        } = self.project();
+
+        while !*is_closed {
+            match inner.as_mut().poll_map_change(cx) {
+                Poll::Ready(Some(diff)) => {
+                    let mut writer = hash_map.write();
+                    SignalMapStructuralSignal::<S, K, V>::apply_map_diff(&mut writer, diff);
+                }
+                Poll::Ready(None) => *is_closed = true,
+                Poll::Pending => break,
+            }
+        }
+
+        let result = output.poll_change(cx);
+        if *is_closed && !result.is_ready() {
+            Poll::Ready(None)
+        } else {
+            result
+        }
+    }
+}
@@ -1,10 +1,13 @@
 use crate::structural_signal::pull_source::PullableDiff;
-use crate::structural_signal::pull_source::DiffMergeResult;
-use crate::structural_signal::structural_signal_ext::SnapshottableEvent;
+use crate::structural_signal::pull_source::{coalesce_diffs, DiffMergeResult};
+use crate::structural_signal::structural_signal_ext::{
+    MergeableEvent, SnapshottableEvent, SplittableEvent,
+};
 use core::hash::Hash;
 use im::HashMap;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MapDiff<K> {
     Replace {},
 
@@ -70,15 +73,62 @@ where
                 panic!("Found two inserts on the same key. The second should be an update.")
             }
         }
+
+        // Remove then Insert => Update, since the key still exists downstream
+        if let MapDiff::Remove {key} = previous {
+            if let MapDiff::Insert {key: _} = self {
+                return DiffMergeResult::merge(MapDiff::Update {key: key.clone()});
+            }
+        }
+
+        // Update then Remove => Remove, along with everything else
         return DiffMergeResult::replace();
     }
 
     fn full_replace() -> MapDiff<K> {
         MapDiff::Replace {}
     }
+
+    fn is_clear(&self) -> bool {
+        matches!(self, MapDiff::Clear {})
+    }
+}
+
+/// Computes a minimal list of diffs that turns `old` into `new`: a Remove for every key
+/// only in `old`, an Insert for every key only in `new`, and an Update for every key
+/// whose value changed. Emptying the map is a single Clear. The diffs read their values
+/// from `new`, which acts as their snapshot.
+///
+/// ```
+/// use im::hashmap;
+/// use signals_im::hash_map::{diff_maps, MapDiff};
+///
+/// let diffs = diff_maps(&hashmap!{1 => 1, 2 => 2}, &hashmap!{1 => 10});
+/// assert_eq!(diffs, vec![MapDiff::Remove { key: 2 }, MapDiff::Update { key: 1 }]);
+/// ```
+pub fn diff_maps<K, V>(old: &HashMap<K, V>, new: &HashMap<K, V>) -> Vec<MapDiff<K>>
+where
+    K: Clone + Eq + Hash,
+    V: Clone + PartialEq,
+{
+    if new.is_empty() && !old.is_empty() {
+        return vec![MapDiff::Clear {}];
+    }
+
+    let removed = old
+        .keys()
+        .filter(|key| !new.contains_key(key))
+        .map(|key| MapDiff::Remove { key: key.clone() });
+    let inserted_or_updated = new.iter().filter_map(|(key, value)| match old.get(key) {
+        None => Some(MapDiff::Insert { key: key.clone() }),
+        Some(old_value) if old_value != value => Some(MapDiff::Update { key: key.clone() }),
+        Some(_) => None,
+    });
+    removed.chain(inserted_or_updated).collect()
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HashMapEvent<K, V>
 where
     K: Clone + Eq + Hash,
@@ -88,6 +138,27 @@ where
     pub diffs: Vec<MapDiff<K>>,
 }
 
+impl<K, V> HashMapEvent<K, V>
+where
+    K: Clone + Eq + Hash,
+    V: Clone,
+{
+    /// Creates an event directly from a snapshot and a list of diffs. This is mostly
+    /// useful for feeding hand-crafted events into a transformer during testing.
+    pub fn new(snapshot: HashMap<K, V>, diffs: Vec<MapDiff<K>>) -> HashMapEvent<K, V> {
+        HashMapEvent { snapshot, diffs }
+    }
+
+    /// Merges and drops diffs on the same key, like a pull source would have done had
+    /// the diffs all happened between two polls.
+    pub fn coalesced(self) -> HashMapEvent<K, V> {
+        HashMapEvent {
+            snapshot: self.snapshot,
+            diffs: coalesce_diffs(self.diffs),
+        }
+    }
+}
+
 impl<K, V> SnapshottableEvent for HashMapEvent<K, V>
 where
     K: Clone + Eq + Hash,
@@ -99,3 +170,39 @@ where
         self.snapshot.clone()
     }
 }
+
+impl<K, V> MergeableEvent for HashMapEvent<K, V>
+where
+    K: Clone + Eq + Hash,
+    V: Clone,
+{
+    fn merge(self, next: HashMapEvent<K, V>) -> HashMapEvent<K, V> {
+        // Combine diffs on the same key exactly as if they had happened between two polls.
+        let mut diffs = self.diffs;
+        diffs.extend(next.diffs);
+        HashMapEvent {
+            snapshot: next.snapshot,
+            diffs: coalesce_diffs(diffs),
+        }
+    }
+}
+
+impl<K, V> SplittableEvent for HashMapEvent<K, V>
+where
+    K: Clone + Eq + Hash,
+    V: Clone,
+{
+    fn split(self) -> Vec<HashMapEvent<K, V>> {
+        if self.diffs.len() <= 1 {
+            return vec![self];
+        }
+        let snapshot = self.snapshot;
+        self.diffs
+            .into_iter()
+            .map(|diff| HashMapEvent {
+                snapshot: snapshot.clone(),
+                diffs: vec![diff],
+            })
+            .collect()
+    }
+}
@@ -1,11 +1,14 @@
 use super::event::{HashMapEvent, MapDiff};
+use crate::structural_signal::adapters::DirtySignal;
 use crate::structural_signal::pull_source::{
     PullSourceHost, PullSourceStructuralSignal, StructrualSignalPullSource,
 };
 use im::HashMap;
 use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::fmt::{self, Debug, Formatter};
 use std::hash::Hash;
-use std::iter::Iterator;
+use std::iter::{FromIterator, Iterator};
+use std::panic::{catch_unwind, resume_unwind, AssertUnwindSafe};
 use std::sync::Arc;
 
 /// The internal state of a MutableHashMap or MutableHashMapReader. All
@@ -34,6 +37,13 @@ impl<K: Clone + Eq + Hash, V: Clone> PullSourceHost for MutableHashMapState<K, V
             diffs: diffs,
         }
     }
+
+    fn make_granular_diffs(&self) -> Vec<Self::DiffType> {
+        self.hash_map
+            .keys()
+            .map(|key| MapDiff::Insert { key: key.clone() })
+            .collect()
+    }
 }
 
 /// A HashMap that can be observed as it changes over time.
@@ -53,6 +63,51 @@ impl<K: Clone + Eq + Hash, V: Clone> Clone for MutableHashMap<K, V> {
     }
 }
 
+/// Compares the current contents of two HashMaps, taking a read lock on each in turn.
+/// This says nothing about whether the two share state or signals.
+impl<K: Clone + Eq + Hash, V: Clone + PartialEq> PartialEq for MutableHashMap<K, V> {
+    fn eq(&self, other: &MutableHashMap<K, V>) -> bool {
+        if Arc::ptr_eq(&self.0, &other.0) {
+            return true;
+        }
+        // Snapshot one side first so that both locks are never held at once.
+        let snapshot = self.0.read().hash_map.clone();
+        snapshot == other.0.read().hash_map
+    }
+}
+
+/// Compares the current contents of this HashMap with a plain `im::HashMap`.
+impl<K, V> PartialEq<HashMap<K, V>> for MutableHashMap<K, V>
+where
+    K: Clone + Eq + Hash,
+    V: Clone + PartialEq,
+{
+    fn eq(&self, other: &HashMap<K, V>) -> bool {
+        self.0.read().hash_map == *other
+    }
+}
+
+impl<K: Clone + Eq + Hash + Debug, V: Clone + Debug> Debug for MutableHashMap<K, V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("MutableHashMap")
+            .field(&self.0.read().hash_map)
+            .finish()
+    }
+}
+
+impl<K: Clone + Eq + Hash, V: Clone> FromIterator<(K, V)> for MutableHashMap<K, V> {
+    /// Creates a MutableHashMap that starts out holding the given entries. No diffs
+    /// are emitted, since nothing can be listening yet.
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        MutableHashMap {
+            0: Arc::new(RwLock::new(MutableHashMapState {
+                hash_map: iter.into_iter().collect(),
+                pull_source: StructrualSignalPullSource::new(),
+            })),
+        }
+    }
+}
+
 impl<K: Clone + Eq + Hash, V: Clone> MutableHashMap<K, V> {
     /// Returns a readonly view into the underlying state that can be used
     /// to read values from the HashMap.
@@ -68,6 +123,44 @@ impl<K: Clone + Eq + Hash, V: Clone> MutableHashMap<K, V> {
         self.0.write()
     }
 
+    /// Runs a function that makes several changes to this HashMap as one atomic batch.
+    /// The function can call `write` as many times as it needs to. Signals cannot pull
+    /// any of the changes until it returns, so they never see the HashMap half-way
+    /// through, and they receive all of the changes in a single event. Batches can be
+    /// nested, and the changes are only released when the outermost batch ends.
+    ///
+    /// ```
+    /// use futures::task::noop_waker_ref;
+    /// use signals_im::hash_map::MutableHashMap;
+    /// use signals_im::StructuralSignal;
+    /// use std::pin::Pin;
+    /// use std::task::Context;
+    ///
+    /// let input_map = MutableHashMap::<u8, u8>::new();
+    /// let mut signal = input_map.as_signal();
+    /// let mut cx = Context::from_waker(noop_waker_ref());
+    /// assert!(Pin::new(&mut signal).poll_change(&mut cx).is_ready());
+    ///
+    /// input_map.batch(|| {
+    ///     input_map.write().insert(1, 1);
+    ///     assert!(Pin::new(&mut signal).poll_change(&mut cx).is_pending());
+    ///     input_map.write().insert(2, 2);
+    /// });
+    /// assert!(Pin::new(&mut signal).poll_change(&mut cx).is_ready());
+    /// ```
+    pub fn batch<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        self.write().pull_source.begin_batch();
+        let result = catch_unwind(AssertUnwindSafe(f));
+        self.write().pull_source.end_batch();
+        match result {
+            Ok(value) => value,
+            Err(panic) => resume_unwind(panic),
+        }
+    }
+
     pub fn new() -> Self {
         MutableHashMap {
             0: Arc::new(RwLock::new(MutableHashMapState {
@@ -77,6 +170,33 @@ impl<K: Clone + Eq + Hash, V: Clone> MutableHashMap<K, V> {
         }
     }
 
+    /// Consumes this MutableHashMap and returns its backing HashMap without cloning it.
+    /// This only succeeds if no other readers or signals share the underlying
+    /// state, otherwise the original MutableHashMap is returned unchanged.
+    pub fn try_into_inner(self) -> Result<HashMap<K, V>, Self> {
+        Arc::try_unwrap(self.0)
+            .map(|state| state.into_inner().hash_map)
+            .map_err(MutableHashMap)
+    }
+
+    /// Diagnostic: the number of diffs retained since the last global diff, such as a
+    /// Replace or Clear. Diffs are kept even after every signal has pulled them, and
+    /// are only tracked once at least one signal is listening.
+    pub fn pending_diff_count(&self) -> usize {
+        self.0.read().pull_source.pending_diff_count()
+    }
+
+    /// Diagnostic: the number of signals that have polled this HashMap at least once.
+    pub fn listening_signal_count(&self) -> usize {
+        self.0.read().pull_source.listening_signal_count()
+    }
+
+    /// Diagnostic: the number of keys with a diff that can still be coalesced with
+    /// later changes to the same key.
+    pub fn keyed_diff_count(&self) -> usize {
+        self.0.read().pull_source.keyed_diff_count()
+    }
+
     /// Creates a read-only view into this data structure. This Reader object
     /// can lookup items in this map at any time, but cannot modify it.
     /// Readers can be cloned. Note that Readers can hold a ReadLock which can
@@ -92,6 +212,23 @@ impl<K: Clone + Eq + Hash, V: Clone> MutableHashMap<K, V> {
     pub fn as_signal(&self) -> PullSourceStructuralSignal<MutableHashMapState<K, V>> {
         PullSourceStructuralSignal::new(self.0.clone())
     }
+
+    /// Like `as_signal`, but the first event describes the current contents of this
+    /// HashMap as one Insert diff per key rather than as a single Replace diff.
+    /// If it is empty, the first event is still a single Replace diff.
+    #[inline]
+    pub fn as_signal_granular(&self) -> PullSourceStructuralSignal<MutableHashMapState<K, V>> {
+        PullSourceStructuralSignal::new_granular(self.0.clone())
+    }
+
+    /// Creates a Signal that fires once for each batch of changes made to this HashMap
+    /// between polls, as well as once initially. It carries no data, which makes it
+    /// the cheapest way to find out that something changed. Pair it with `read` to
+    /// get the current contents.
+    #[inline]
+    pub fn dirty_signal(&self) -> DirtySignal<PullSourceStructuralSignal<MutableHashMapState<K, V>>> {
+        DirtySignal::new(self.as_signal())
+    }
 }
 
 /// A read-only view into a MutableHashMap.
@@ -120,6 +257,14 @@ impl<K: Clone + Eq + Hash, V: Clone> MutableHashMapReader<K, V> {
     pub fn as_signal(&self) -> PullSourceStructuralSignal<MutableHashMapState<K, V>> {
         PullSourceStructuralSignal::new(self.0.clone())
     }
+
+    /// Like `as_signal`, but the first event describes the current contents of this
+    /// HashMap as one Insert diff per key rather than as a single Replace diff.
+    /// If it is empty, the first event is still a single Replace diff.
+    #[inline]
+    pub fn as_signal_granular(&self) -> PullSourceStructuralSignal<MutableHashMapState<K, V>> {
+        PullSourceStructuralSignal::new_granular(self.0.clone())
+    }
 }
 
 impl<K: Clone + Eq + Hash, V: Clone> MutableHashMapState<K, V> {
@@ -148,19 +293,45 @@ impl<K: Clone + Eq + Hash, V: Clone> MutableHashMapState<K, V> {
         self.hash_map.clone()
     }
 
+    /// Returns false if this HashMap has not been modified since `prev` was taken from
+    /// it with `snapshot`. This compares the backing maps by pointer, so it runs in
+    /// constant time, but it may return true for a map whose contents happen to be equal
+    /// to `prev` again.
+    #[inline]
+    pub fn snapshot_changed_since(&self, prev: &HashMap<K, V>) -> bool {
+        !self.hash_map.ptr_eq(prev)
+    }
+
     /// Replaces the entire contents of this HashMap with new entries. All existing
-    /// data will be cleared.
+    /// data will be cleared. Like `clear`, replacing an empty HashMap with no entries
+    /// does nothing.
     pub fn replace<E>(&mut self, entries: E)
     where
         E: Iterator<Item = (K, V)>,
     {
+        let was_empty = self.hash_map.is_empty();
         self.hash_map.clear();
         for (key, value) in entries {
             self.hash_map.insert(key, value);
         }
+        if was_empty && self.hash_map.is_empty() {
+            return;
+        }
         self.add_diff(MapDiff::Replace {});
     }
 
+    /// Like `replace`, but returns the previous contents of this HashMap.
+    pub fn replace_returning<E>(&mut self, entries: E) -> HashMap<K, V>
+    where
+        E: Iterator<Item = (K, V)>,
+    {
+        let previous = std::mem::replace(&mut self.hash_map, entries.collect());
+        if !previous.is_empty() || !self.hash_map.is_empty() {
+            self.add_diff(MapDiff::Replace {});
+        }
+        previous
+    }
+
     /// Inserts a new value into this HashMap at a given key.
     pub fn insert(&mut self, k: K, v: V) -> Option<V> {
         let remember_k = k.clone();
@@ -176,6 +347,66 @@ impl<K: Clone + Eq + Hash, V: Clone> MutableHashMapState<K, V> {
         return result;
     }
 
+    /// Inserts a new value at a given key only if the key does not have a value yet.
+    /// Returns true if the value was inserted. Existing values are left untouched and
+    /// produce no diff.
+    pub fn insert_if_absent(&mut self, k: K, v: V) -> bool {
+        if self.hash_map.contains_key(&k) {
+            return false;
+        }
+
+        self.hash_map.insert(k.clone(), v);
+        self.add_diff(MapDiff::Insert { key: k });
+        true
+    }
+
+    /// Like `insert_if_absent`, but for many entries at once. Returns the number of
+    /// entries that were inserted.
+    pub fn insert_many_if_absent<E>(&mut self, entries: E) -> usize
+    where
+        E: Iterator<Item = (K, V)>,
+    {
+        let mut inserted_count = 0;
+        for (key, value) in entries {
+            if self.insert_if_absent(key, value) {
+                inserted_count += 1;
+            }
+        }
+        inserted_count
+    }
+
+    /// Merges the entries of another map into this one. Keys that are missing here are
+    /// inserted, and keys that exist in both maps are set to `combine(existing, other)`.
+    pub fn merge_in<F>(&mut self, other: &HashMap<K, V>, combine: F)
+    where
+        F: Fn(&V, &V) -> V,
+    {
+        for (key, value) in other.iter() {
+            let merged = match self.hash_map.get(key) {
+                Some(existing) => combine(existing, value),
+                None => value.clone(),
+            };
+            self.insert(key.clone(), merged);
+        }
+    }
+
+    /// Runs a function that modifies the value at a given key in place, inserting
+    /// the default value first if the key does not exist yet.
+    pub fn modify_or_default<F>(&mut self, key: K, modify: F)
+    where
+        V: Default,
+        F: FnOnce(&mut V),
+    {
+        let is_new = !self.hash_map.contains_key(&key);
+        modify(self.hash_map.entry(key.clone()).or_default());
+
+        if is_new {
+            self.add_diff(MapDiff::Insert { key });
+        } else {
+            self.add_diff(MapDiff::Update { key });
+        }
+    }
+
     /// Removes and returns the value at a given key, if it exists.
     pub fn remove(&mut self, k: &K) -> Option<V> {
         let result = self.hash_map.remove(k);
@@ -187,6 +418,43 @@ impl<K: Clone + Eq + Hash, V: Clone> MutableHashMapState<K, V> {
         return result;
     }
 
+    /// Moves the value at `old` to `new` in a single operation, overwriting any value
+    /// already at `new`. Returns false, without changing anything, if `old` has no
+    /// value. Maps have no move diff, so downstream signals see this as a Remove of
+    /// `old` followed by an Insert (or Update) of `new`. Renaming a key to itself
+    /// changes nothing.
+    pub fn rename_key(&mut self, old: &K, new: K) -> bool {
+        if old == &new {
+            return self.hash_map.contains_key(old);
+        }
+
+        let value = match self.remove(old) {
+            Some(value) => value,
+            None => return false,
+        };
+
+        self.insert(new, value);
+        true
+    }
+
+    /// Removes every entry that fails a predicate, emitting a Remove diff for each one
+    /// so that signals watching the surviving keys are not disturbed. Like `clear`,
+    /// this does nothing if no entry is removed.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&K, &V) -> bool,
+    {
+        let removed_keys: Vec<K> = self
+            .hash_map
+            .iter()
+            .filter(|(k, v)| !f(k, v))
+            .map(|(k, _v)| k.clone())
+            .collect();
+        for key in removed_keys {
+            self.remove(&key);
+        }
+    }
+
     /// Removes every value in this HashMap.
     pub fn clear(&mut self) {
         if self.hash_map.is_empty() {
@@ -196,4 +464,15 @@ impl<K: Clone + Eq + Hash, V: Clone> MutableHashMapState<K, V> {
         self.hash_map.clear();
         self.add_diff(MapDiff::Clear {})
     }
+
+    /// Removes every value in this HashMap, returning the previous contents.
+    pub fn take(&mut self) -> HashMap<K, V> {
+        if self.hash_map.is_empty() {
+            return HashMap::new();
+        }
+
+        let previous = std::mem::take(&mut self.hash_map);
+        self.add_diff(MapDiff::Clear {});
+        previous
+    }
 }
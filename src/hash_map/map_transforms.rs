@@ -1,37 +1,56 @@
 use super::event::{HashMapEvent, MapDiff};
 use super::hash_map::{MutableHashMap, MutableHashMapState};
 use crate::structural_signal::pull_source::PullSourceStructuralSignal;
-use crate::structural_signal::transformer::StructuralSignalTransformer;
+use crate::structural_signal::transformer::{
+    FallibleStructuralSignalTransformer, StructuralSignalTransformer,
+};
 use crate::vector::{MutableVector, MutableVectorState};
 use core::hash::Hash;
+use im::Vector;
+use std::cmp::Ordering;
 use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
 use std::hash::Hasher;
 use std::marker::PhantomData;
 
 // ** MAP_VALUES ** //
 
+// A MapDiff along with the mapped values it needs.
+enum MappedMapDiff<K, V> {
+    Replace(Vec<(K, V)>),
+    Insert(K, V),
+    Remove(K),
+    Clear,
+}
+
+// The map function may be stateful. It is called once per mapped value, in the order
+// the diffs of each event arrive in; a Replace maps the snapshot in iteration order.
 pub struct MapHashMapTransformer<K, F, IV, OV>
 where
     K: Hash + Eq + Clone,
     OV: Clone,
-    F: Fn(&IV) -> OV,
+    F: FnMut(&IV) -> OV,
 {
     hash_map: MutableHashMap<K, OV>,
     map_fn: F,
     input_type: PhantomData<IV>,
+    // Set while an event is being mapped. If it is still set when the next event
+    // arrives, the map function panicked and the previous event was never applied.
+    interrupted: bool,
 }
 
 impl<K, F, IV, OV> MapHashMapTransformer<K, F, IV, OV>
 where
     K: Hash + Eq + Clone,
     OV: Clone,
-    F: Fn(&IV) -> OV,
+    F: FnMut(&IV) -> OV,
 {
-    pub(crate) fn new(map_fn: F) -> MapHashMapTransformer<K, F, IV, OV> {
+    pub fn new(map_fn: F) -> MapHashMapTransformer<K, F, IV, OV> {
         MapHashMapTransformer {
             hash_map: MutableHashMap::new(),
             map_fn: map_fn,
             input_type: PhantomData,
+            interrupted: false,
         }
     }
 }
@@ -41,27 +60,304 @@ where
     K: Hash + Eq + Clone,
     IV: Clone,
     OV: Clone,
+    F: FnMut(&IV) -> OV,
+{
+    type InputEvent = HashMapEvent<K, IV>;
+    type OutputSignal = PullSourceStructuralSignal<MutableHashMapState<K, OV>>;
+
+    fn apply_event(&mut self, map_event: HashMapEvent<K, IV>) {
+        // Every value is mapped before the output is touched, so if the map function
+        // panics part way through an event, none of that event is applied. The output
+        // then lags behind the input, so the next event is applied as a full Replace
+        // of its snapshot to catch up.
+        let HashMapEvent { snapshot, diffs } = map_event;
+        let diffs = if self.interrupted {
+            vec![MapDiff::Replace {}]
+        } else {
+            diffs
+        };
+        self.interrupted = true;
+        let mapped_diffs: Vec<MappedMapDiff<K, OV>> = diffs
+            .into_iter()
+            .map(|diff| match diff {
+                MapDiff::Replace {} => MappedMapDiff::Replace(
+                    snapshot
+                        .iter()
+                        .map(|(k, iv)| (k.clone(), (self.map_fn)(iv)))
+                        .collect(),
+                ),
+                MapDiff::Insert { key } | MapDiff::Update { key } => {
+                    let mapped_val = (self.map_fn)(snapshot.get(&key).unwrap());
+                    MappedMapDiff::Insert(key, mapped_val)
+                }
+                MapDiff::Remove { key } => MappedMapDiff::Remove(key),
+                MapDiff::Clear {} => MappedMapDiff::Clear,
+            })
+            .collect();
+
+        let mut writer = self.hash_map.write();
+        for mapped_diff in mapped_diffs {
+            match mapped_diff {
+                MappedMapDiff::Replace(entries) => {
+                    writer.replace(entries.into_iter());
+                }
+                MappedMapDiff::Insert(key, mapped_val) => {
+                    writer.insert(key, mapped_val);
+                }
+                MappedMapDiff::Remove(key) => {
+                    writer.remove(&key);
+                }
+                MappedMapDiff::Clear => {
+                    writer.clear();
+                }
+            }
+        }
+        self.interrupted = false;
+    }
+
+    #[inline]
+    fn get_signal(&self) -> Self::OutputSignal {
+        self.hash_map.as_signal()
+    }
+}
+
+// ** MAP_VALUES_WITH ** //
+
+/// Options for `SignalHashMapExt::map_values_with`. The default leaves every option off,
+/// which behaves exactly like `map_values`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MapValuesOptions {
+    /// Skips output diffs for keys whose mapped value did not change, at the cost of
+    /// comparing every mapped value against the current output. A coarse Replace is
+    /// skipped entirely if it would not change the output.
+    pub dedupe: bool,
+    /// Caches the output for each input value, so that a Replace only runs the map
+    /// function for input values it has not seen. The cache is trimmed to the current
+    /// input values on every Replace and emptied on Clear, so between those it grows
+    /// with the number of distinct input values mapped.
+    pub memoize: bool,
+    /// Forwards a Replace as Insert, Update and Remove diffs against the current output
+    /// instead of a coarse Replace, so downstream consumers only redo changed keys.
+    /// This costs a pass over both the old and the new contents.
+    pub granular_replace: bool,
+}
+
+pub struct MapWithOptionsHashMapTransformer<K, F, IV, OV>
+where
+    K: Hash + Eq + Clone,
+    IV: Hash + Eq + Clone,
+    OV: Clone + PartialEq,
+    F: Fn(&IV) -> OV,
+{
+    hash_map: MutableHashMap<K, OV>,
+    map_fn: F,
+    options: MapValuesOptions,
+    memo: HashMap<IV, OV>,
+    // See MapHashMapTransformer.
+    interrupted: bool,
+}
+
+impl<K, F, IV, OV> MapWithOptionsHashMapTransformer<K, F, IV, OV>
+where
+    K: Hash + Eq + Clone,
+    IV: Hash + Eq + Clone,
+    OV: Clone + PartialEq,
+    F: Fn(&IV) -> OV,
+{
+    pub fn new(
+        options: MapValuesOptions,
+        map_fn: F,
+    ) -> MapWithOptionsHashMapTransformer<K, F, IV, OV> {
+        MapWithOptionsHashMapTransformer {
+            hash_map: MutableHashMap::new(),
+            map_fn,
+            options,
+            memo: HashMap::new(),
+            interrupted: false,
+        }
+    }
+
+    fn map_value(&mut self, value: &IV) -> OV {
+        if !self.options.memoize {
+            return (self.map_fn)(value);
+        }
+        if let Some(mapped_val) = self.memo.get(value) {
+            return mapped_val.clone();
+        }
+        let mapped_val = (self.map_fn)(value);
+        self.memo.insert(value.clone(), mapped_val.clone());
+        mapped_val
+    }
+}
+
+impl<K, F, IV, OV> StructuralSignalTransformer for MapWithOptionsHashMapTransformer<K, F, IV, OV>
+where
+    K: Hash + Eq + Clone,
+    IV: Hash + Eq + Clone,
+    OV: Clone + PartialEq,
     F: Fn(&IV) -> OV,
 {
     type InputEvent = HashMapEvent<K, IV>;
     type OutputSignal = PullSourceStructuralSignal<MutableHashMapState<K, OV>>;
 
     fn apply_event(&mut self, map_event: HashMapEvent<K, IV>) {
+        // Like map_values, every value is mapped before the output is touched, and an
+        // event that follows a panic is applied as a full Replace.
+        let HashMapEvent { snapshot, diffs } = map_event;
+        let diffs = if self.interrupted {
+            vec![MapDiff::Replace {}]
+        } else {
+            diffs
+        };
+        self.interrupted = true;
+        let mut mapped_diffs = Vec::with_capacity(diffs.len());
+        for diff in diffs {
+            mapped_diffs.push(match diff {
+                MapDiff::Replace {} => {
+                    if self.options.memoize {
+                        let current_values: HashSet<&IV> = snapshot.values().collect();
+                        self.memo.retain(|iv, _ov| current_values.contains(iv));
+                    }
+                    let entries = snapshot
+                        .iter()
+                        .map(|(k, iv)| (k.clone(), self.map_value(iv)))
+                        .collect();
+                    MappedMapDiff::Replace(entries)
+                }
+                MapDiff::Insert { key } | MapDiff::Update { key } => {
+                    let mapped_val = self.map_value(snapshot.get(&key).unwrap());
+                    MappedMapDiff::Insert(key, mapped_val)
+                }
+                MapDiff::Remove { key } => MappedMapDiff::Remove(key),
+                MapDiff::Clear {} => {
+                    self.memo.clear();
+                    MappedMapDiff::Clear
+                }
+            });
+        }
+
+        let options = self.options;
         let mut writer = self.hash_map.write();
+        for mapped_diff in mapped_diffs {
+            match mapped_diff {
+                MappedMapDiff::Replace(entries) => {
+                    if options.granular_replace {
+                        let new_keys: HashSet<&K> = entries.iter().map(|(key, _)| key).collect();
+                        for key in writer.snapshot().keys() {
+                            if !new_keys.contains(key) {
+                                writer.remove(key);
+                            }
+                        }
+                        for (key, mapped_val) in entries {
+                            if !options.dedupe || writer.get(&key) != Some(&mapped_val) {
+                                writer.insert(key, mapped_val);
+                            }
+                        }
+                        continue;
+                    }
+
+                    let current = writer.snapshot();
+                    let is_unchanged = options.dedupe
+                        && entries.len() == current.len()
+                        && entries
+                            .iter()
+                            .all(|(key, mapped_val)| current.get(key) == Some(mapped_val));
+                    if !is_unchanged {
+                        writer.replace(entries.into_iter());
+                    }
+                }
+                MappedMapDiff::Insert(key, mapped_val) => {
+                    if !options.dedupe || writer.get(&key) != Some(&mapped_val) {
+                        writer.insert(key, mapped_val);
+                    }
+                }
+                MappedMapDiff::Remove(key) => {
+                    writer.remove(&key);
+                }
+                MappedMapDiff::Clear => {
+                    writer.clear();
+                }
+            }
+        }
+        self.interrupted = false;
+    }
+
+    #[inline]
+    fn get_signal(&self) -> Self::OutputSignal {
+        self.hash_map.as_signal()
+    }
+}
+
+// ** TRY_MAP_VALUES ** //
+
+pub struct TryMapHashMapTransformer<K, F, IV, OV, E>
+where
+    K: Hash + Eq + Clone,
+    OV: Clone,
+    F: Fn(&IV) -> Result<OV, E>,
+{
+    hash_map: MutableHashMap<K, OV>,
+    map_fn: F,
+    input_type: PhantomData<IV>,
+}
+
+impl<K, F, IV, OV, E> TryMapHashMapTransformer<K, F, IV, OV, E>
+where
+    K: Hash + Eq + Clone,
+    OV: Clone,
+    F: Fn(&IV) -> Result<OV, E>,
+{
+    pub fn new(map_fn: F) -> TryMapHashMapTransformer<K, F, IV, OV, E> {
+        TryMapHashMapTransformer {
+            hash_map: MutableHashMap::new(),
+            map_fn,
+            input_type: PhantomData,
+        }
+    }
+}
+
+impl<K, F, IV, OV, E> FallibleStructuralSignalTransformer for TryMapHashMapTransformer<K, F, IV, OV, E>
+where
+    K: Hash + Eq + Clone,
+    IV: Clone,
+    OV: Clone,
+    E: Clone,
+    F: Fn(&IV) -> Result<OV, E>,
+{
+    type InputEvent = HashMapEvent<K, IV>;
+    type OutputSignal = PullSourceStructuralSignal<MutableHashMapState<K, OV>>;
+    type Error = E;
+
+    fn apply_event(&mut self, map_event: HashMapEvent<K, IV>) -> Result<(), E> {
+        // Map every affected value before touching the output, so that a failure
+        // leaves the output exactly as it was.
+        let mut mapped_diffs = Vec::with_capacity(map_event.diffs.len());
         for diff in map_event.diffs {
+            let mapped_entries = match &diff {
+                MapDiff::Replace {} => map_event
+                    .snapshot
+                    .iter()
+                    .map(|(k, v)| (self.map_fn)(v).map(|ov| (k.clone(), ov)))
+                    .collect::<Result<Vec<(K, OV)>, E>>()?,
+                MapDiff::Insert { key } | MapDiff::Update { key } => {
+                    let mapped_val = (self.map_fn)(map_event.snapshot.get(key).unwrap())?;
+                    vec![(key.clone(), mapped_val)]
+                }
+                MapDiff::Remove { key: _ } | MapDiff::Clear {} => vec![],
+            };
+            mapped_diffs.push((diff, mapped_entries));
+        }
+
+        let mut writer = self.hash_map.write();
+        for (diff, mapped_entries) in mapped_diffs {
             match diff {
                 MapDiff::Replace {} => {
-                    writer.replace(
-                        map_event
-                            .snapshot
-                            .clone()
-                            .into_iter()
-                            .map(|(k, ov)| (k, (self.map_fn)(&ov))),
-                    );
+                    writer.replace(mapped_entries.into_iter());
                 }
-                MapDiff::Insert { key } | MapDiff::Update { key } => {
-                    let mapped_val = (self.map_fn)(map_event.snapshot.get(&key).unwrap());
-                    writer.insert(key, mapped_val);
+                MapDiff::Insert { key: _ } | MapDiff::Update { key: _ } => {
+                    for (key, mapped_val) in mapped_entries {
+                        writer.insert(key, mapped_val);
+                    }
                 }
                 MapDiff::Remove { key } => {
                     writer.remove(&key);
@@ -71,6 +367,7 @@ where
                 }
             }
         }
+        Ok(())
     }
 
     #[inline]
@@ -85,7 +382,7 @@ pub struct FilterHashMapTransformer<K, V, F>
 where
     K: Hash + Eq + Clone,
     V: Clone,
-    F: Fn(&V) -> bool,
+    F: FnMut(&V) -> bool,
 {
     hash_map: MutableHashMap<K, V>,
     predicate: F,
@@ -95,9 +392,9 @@ impl<K, V, F> FilterHashMapTransformer<K, V, F>
 where
     K: Hash + Eq + Clone,
     V: Clone,
-    F: Fn(&V) -> bool,
+    F: FnMut(&V) -> bool,
 {
-    pub(crate) fn new(predicate: F) -> FilterHashMapTransformer<K, V, F> {
+    pub fn new(predicate: F) -> FilterHashMapTransformer<K, V, F> {
         FilterHashMapTransformer {
             hash_map: MutableHashMap::new(),
             predicate: predicate,
@@ -109,12 +406,13 @@ impl<K, V, F> StructuralSignalTransformer for FilterHashMapTransformer<K, V, F>
 where
     K: Hash + Eq + Clone,
     V: Clone,
-    F: Fn(&V) -> bool,
+    F: FnMut(&V) -> bool,
 {
     type InputEvent = HashMapEvent<K, V>;
     type OutputSignal = PullSourceStructuralSignal<MutableHashMapState<K, V>>;
 
     fn apply_event(&mut self, map_event: HashMapEvent<K, V>) {
+        let predicate = &mut self.predicate;
         let mut writer = self.hash_map.write();
         for diff in map_event.diffs {
             match diff {
@@ -124,18 +422,18 @@ where
                             .snapshot
                             .clone()
                             .into_iter()
-                            .filter(|(_k, v)| (self.predicate)(v)),
+                            .filter(|(_k, v)| predicate(v)),
                     );
                 }
                 MapDiff::Insert { key } | MapDiff::Update { key } => {
                     let val = map_event.snapshot.get(&key).unwrap();
-                    let passes_predicate = (self.predicate)(val);
+                    let passes_predicate = predicate(val);
                     if passes_predicate {
                         writer.insert(key, val.clone());
-                        return;
+                        continue;
                     }
 
-                    let currently_exists = self.hash_map.read().contains_key(&key);
+                    let currently_exists = writer.contains_key(&key);
                     if currently_exists {
                         writer.remove(&key);
                     }
@@ -156,6 +454,187 @@ where
     }
 }
 
+// ** FILTER_MAP ** //
+
+pub struct FilterMapHashMapTransformer<K, IV, OV, F>
+where
+    K: Hash + Eq + Clone,
+    IV: Clone,
+    OV: Clone,
+    F: Fn(&IV) -> Option<OV>,
+{
+    hash_map: MutableHashMap<K, OV>,
+    filter_map_fn: F,
+    input_type: PhantomData<IV>,
+}
+
+impl<K, IV, OV, F> FilterMapHashMapTransformer<K, IV, OV, F>
+where
+    K: Hash + Eq + Clone,
+    IV: Clone,
+    OV: Clone,
+    F: Fn(&IV) -> Option<OV>,
+{
+    pub(crate) fn new(filter_map_fn: F) -> FilterMapHashMapTransformer<K, IV, OV, F> {
+        FilterMapHashMapTransformer {
+            hash_map: MutableHashMap::new(),
+            filter_map_fn,
+            input_type: PhantomData,
+        }
+    }
+}
+
+impl<K, IV, OV, F> StructuralSignalTransformer for FilterMapHashMapTransformer<K, IV, OV, F>
+where
+    K: Hash + Eq + Clone,
+    IV: Clone,
+    OV: Clone,
+    F: Fn(&IV) -> Option<OV>,
+{
+    type InputEvent = HashMapEvent<K, IV>;
+    type OutputSignal = PullSourceStructuralSignal<MutableHashMapState<K, OV>>;
+
+    fn apply_event(&mut self, map_event: HashMapEvent<K, IV>) {
+        let mut writer = self.hash_map.write();
+        for diff in map_event.diffs {
+            match diff {
+                MapDiff::Replace {} => {
+                    let filter_map_fn = &self.filter_map_fn;
+                    writer.replace(
+                        map_event
+                            .snapshot
+                            .iter()
+                            .filter_map(|(k, v)| filter_map_fn(v).map(|ov| (k.clone(), ov))),
+                    );
+                }
+                MapDiff::Insert { key } | MapDiff::Update { key } => {
+                    let val = map_event.snapshot.get(&key).unwrap();
+                    match (self.filter_map_fn)(val) {
+                        Some(mapped) => {
+                            writer.insert(key, mapped);
+                        }
+                        None => {
+                            writer.remove(&key);
+                        }
+                    }
+                }
+                MapDiff::Remove { key } => {
+                    writer.remove(&key);
+                }
+                MapDiff::Clear {} => {
+                    writer.clear();
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn get_signal(&self) -> Self::OutputSignal {
+        self.hash_map.as_signal()
+    }
+}
+
+// ** MAP_KEYS ** //
+
+pub struct MapKeysHashMapTransformer<IK, OK, V, F>
+where
+    IK: Hash + Eq + Clone,
+    OK: Hash + Eq + Clone,
+    V: Clone,
+    F: Fn(&IK) -> OK,
+{
+    hash_map: MutableHashMap<OK, V>,
+    // The input keys that map to each output key, with the most recently written last.
+    // When input keys collide, the output holds the value of the last one written.
+    writers: HashMap<OK, Vec<IK>>,
+    map_fn: F,
+}
+
+impl<IK, OK, V, F> MapKeysHashMapTransformer<IK, OK, V, F>
+where
+    IK: Hash + Eq + Clone,
+    OK: Hash + Eq + Clone,
+    V: Clone,
+    F: Fn(&IK) -> OK,
+{
+    pub(crate) fn new(map_fn: F) -> MapKeysHashMapTransformer<IK, OK, V, F> {
+        MapKeysHashMapTransformer {
+            hash_map: MutableHashMap::new(),
+            writers: HashMap::new(),
+            map_fn,
+        }
+    }
+}
+
+impl<IK, OK, V, F> StructuralSignalTransformer for MapKeysHashMapTransformer<IK, OK, V, F>
+where
+    IK: Hash + Eq + Clone,
+    OK: Hash + Eq + Clone,
+    V: Clone,
+    F: Fn(&IK) -> OK,
+{
+    type InputEvent = HashMapEvent<IK, V>;
+    type OutputSignal = PullSourceStructuralSignal<MutableHashMapState<OK, V>>;
+
+    fn apply_event(&mut self, map_event: HashMapEvent<IK, V>) {
+        let mut writer = self.hash_map.write();
+        for diff in map_event.diffs {
+            match diff {
+                MapDiff::Replace {} => {
+                    self.writers.clear();
+                    let mut entries = vec![];
+                    for (key, value) in map_event.snapshot.iter() {
+                        let output_key = (self.map_fn)(key);
+                        self.writers
+                            .entry(output_key.clone())
+                            .or_default()
+                            .push(key.clone());
+                        entries.push((output_key, value.clone()));
+                    }
+                    writer.replace(entries.into_iter());
+                }
+                MapDiff::Insert { key } | MapDiff::Update { key } => {
+                    let output_key = (self.map_fn)(&key);
+                    let value = map_event.snapshot.get(&key).unwrap().clone();
+                    let key_writers = self.writers.entry(output_key.clone()).or_default();
+                    key_writers.retain(|k| *k != key);
+                    key_writers.push(key);
+                    writer.insert(output_key, value);
+                }
+                MapDiff::Remove { key } => {
+                    let output_key = (self.map_fn)(&key);
+                    let key_writers = self.writers.entry(output_key.clone()).or_default();
+                    let was_latest = key_writers.last() == Some(&key);
+                    key_writers.retain(|k| *k != key);
+                    match key_writers.last().cloned() {
+                        None => {
+                            self.writers.remove(&output_key);
+                            writer.remove(&output_key);
+                        }
+                        Some(latest) => {
+                            // Fall back to the value of the next most recently written key.
+                            if was_latest {
+                                if let Some(value) = map_event.snapshot.get(&latest) {
+                                    writer.insert(output_key, value.clone());
+                                }
+                            }
+                        }
+                    }
+                }
+                MapDiff::Clear {} => {
+                    self.writers.clear();
+                    writer.clear();
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn get_signal(&self) -> Self::OutputSignal {
+        self.hash_map.as_signal()
+    }
+}
+
 // ** ENTRIES ** //
 
 pub struct EntriesHashMapTransformer<K, V>
@@ -178,9 +657,60 @@ where
     }
 }
 
+// Entries are ordered by the hash of their key. Keys with colliding hashes fall back
+// to their own ordering, so that every key has exactly one position.
 #[inline]
-fn hashed_key_sort<'r, K: Hash, V>(entry: &'r (K, V)) -> u64 {
-    hash_key(&entry.0)
+fn hashed_key_sort<K: Hash, V>(entry: &(K, V)) -> (u64, &K) {
+    (hash_key(&entry.0), &entry.0)
+}
+
+#[inline]
+fn search_entries<K, V>(entries: &Vector<(K, V)>, key: &K) -> Result<usize, usize>
+where
+    K: Hash + Ord + Clone,
+    V: Clone,
+{
+    let key_sort = (hash_key(key), key);
+    entries.binary_search_by(|entry| hashed_key_sort(entry).cmp(&key_sort))
+}
+
+// Turns the current entries into `new_entries`, both sorted by `hashed_key_sort`, with
+// one Insert or Remove per key that was added or removed and one Update per key that
+// was kept. This way a Replace of the input map does not make downstream consumers
+// rebuild the whole list.
+fn replace_entries_incrementally<K, V>(
+    writer: &mut MutableVectorState<(K, V)>,
+    new_entries: Vec<(K, V)>,
+) where
+    K: Hash + Ord + Clone,
+    V: Clone,
+{
+    let mut index = 0;
+    for entry in new_entries {
+        loop {
+            let ordering = match writer.get(index) {
+                Some(existing) => hashed_key_sort(existing).cmp(&hashed_key_sort(&entry)),
+                None => Ordering::Greater,
+            };
+            match ordering {
+                Ordering::Less => {
+                    writer.remove(index);
+                }
+                Ordering::Equal => {
+                    writer.set(index, entry);
+                    break;
+                }
+                Ordering::Greater => {
+                    writer.insert(index, entry);
+                    break;
+                }
+            }
+        }
+        index += 1;
+    }
+    while writer.len() > index {
+        writer.remove(index);
+    }
 }
 
 #[inline]
@@ -192,7 +722,7 @@ fn hash_key<K: Hash>(key: &K) -> u64 {
 
 impl<K, V> StructuralSignalTransformer for EntriesHashMapTransformer<K, V>
 where
-    K: Hash + Eq + Clone,
+    K: Hash + Ord + Clone,
     V: Clone,
 {
     type InputEvent = HashMapEvent<K, V>;
@@ -208,12 +738,15 @@ where
                         .clone()
                         .into_iter()
                         .collect::<Vec<(K, V)>>();
-                    snapshot_vec.sort_by_key(hashed_key_sort);
-                    writer.replace(snapshot_vec.into_iter());
+                    snapshot_vec.sort_by(|a, b| hashed_key_sort(a).cmp(&hashed_key_sort(b)));
+                    if writer.is_empty() || snapshot_vec.is_empty() {
+                        writer.replace(snapshot_vec.into_iter());
+                    } else {
+                        replace_entries_incrementally(&mut writer, snapshot_vec);
+                    }
                 }
                 MapDiff::Insert { key } => {
-                    let key_hash = hash_key(&key);
-                    let insert_at_index = writer.binary_search_by_key(&key_hash, hashed_key_sort);
+                    let insert_at_index = search_entries(&writer, &key);
                     let val = map_event.snapshot.get(&key).unwrap().clone();
                     match insert_at_index {
                         Result::Ok(_) => {
@@ -225,8 +758,7 @@ where
                     }
                 }
                 MapDiff::Update { key } => {
-                    let key_hash = hash_key(&key);
-                    let insert_at_index = writer.binary_search_by_key(&key_hash, hashed_key_sort);
+                    let insert_at_index = search_entries(&writer, &key);
                     let val = map_event.snapshot.get(&key).unwrap().clone();
                     match insert_at_index {
                         Result::Ok(index) => {
@@ -238,8 +770,7 @@ where
                     }
                 }
                 MapDiff::Remove { key } => {
-                    let key_hash = hash_key(&key);
-                    let remove_at_index = writer.binary_search_by_key(&key_hash, hashed_key_sort);
+                    let remove_at_index = search_entries(&writer, &key);
                     if let Result::Ok(index) = remove_at_index {
                         writer.remove(index);
                     }
@@ -256,3 +787,251 @@ where
         self.vector.as_signal()
     }
 }
+
+// ** KEYS ** //
+
+pub struct KeysHashMapTransformer<K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    vector: MutableVector<K>,
+    value_type: PhantomData<V>,
+}
+
+impl<K, V> KeysHashMapTransformer<K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    pub(crate) fn new() -> KeysHashMapTransformer<K, V> {
+        KeysHashMapTransformer {
+            vector: MutableVector::new(),
+            value_type: PhantomData,
+        }
+    }
+}
+
+// Keys use the same ordering as entries(), so the two line up.
+#[inline]
+fn search_keys<K>(keys: &Vector<K>, key: &K) -> Result<usize, usize>
+where
+    K: Hash + Ord + Clone,
+{
+    let key_sort = (hash_key(key), key);
+    keys.binary_search_by(|k| (hash_key(k), k).cmp(&key_sort))
+}
+
+impl<K, V> StructuralSignalTransformer for KeysHashMapTransformer<K, V>
+where
+    K: Hash + Ord + Clone,
+    V: Clone,
+{
+    type InputEvent = HashMapEvent<K, V>;
+    type OutputSignal = PullSourceStructuralSignal<MutableVectorState<K>>;
+
+    fn apply_event(&mut self, map_event: HashMapEvent<K, V>) {
+        let mut writer = self.vector.write();
+        for diff in map_event.diffs {
+            match diff {
+                MapDiff::Replace {} => {
+                    let mut keys = map_event.snapshot.keys().cloned().collect::<Vec<K>>();
+                    keys.sort_by(|a, b| (hash_key(a), a).cmp(&(hash_key(b), b)));
+                    writer.replace(keys.into_iter());
+                }
+                MapDiff::Insert { key } => {
+                    if let Result::Err(index) = search_keys(&writer, &key) {
+                        writer.insert(index, key);
+                    }
+                }
+                MapDiff::Update { key: _ } => {
+                    // The set of keys does not change.
+                }
+                MapDiff::Remove { key } => {
+                    if let Result::Ok(index) = search_keys(&writer, &key) {
+                        writer.remove(index);
+                    }
+                }
+                MapDiff::Clear {} => {
+                    writer.clear();
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn get_signal(&self) -> Self::OutputSignal {
+        self.vector.as_signal()
+    }
+}
+
+// ** VALUES ** //
+
+pub struct ValuesHashMapTransformer<K, V>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+{
+    vector: MutableVector<V>,
+    // The key of each value, in the same order, which is used to find where values live.
+    keys: Vector<K>,
+}
+
+impl<K, V> ValuesHashMapTransformer<K, V>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+{
+    pub(crate) fn new() -> ValuesHashMapTransformer<K, V> {
+        ValuesHashMapTransformer {
+            vector: MutableVector::new(),
+            keys: Vector::new(),
+        }
+    }
+}
+
+impl<K, V> StructuralSignalTransformer for ValuesHashMapTransformer<K, V>
+where
+    K: Hash + Ord + Clone,
+    V: Clone,
+{
+    type InputEvent = HashMapEvent<K, V>;
+    type OutputSignal = PullSourceStructuralSignal<MutableVectorState<V>>;
+
+    fn apply_event(&mut self, map_event: HashMapEvent<K, V>) {
+        let mut writer = self.vector.write();
+        for diff in map_event.diffs {
+            match diff {
+                MapDiff::Replace {} => {
+                    let mut snapshot_vec = map_event
+                        .snapshot
+                        .clone()
+                        .into_iter()
+                        .collect::<Vec<(K, V)>>();
+                    snapshot_vec.sort_by(|a, b| hashed_key_sort(a).cmp(&hashed_key_sort(b)));
+                    self.keys = snapshot_vec.iter().map(|(k, _)| k.clone()).collect();
+                    writer.replace(snapshot_vec.into_iter().map(|(_, v)| v));
+                }
+                MapDiff::Insert { key } => {
+                    let val = map_event.snapshot.get(&key).unwrap().clone();
+                    match search_keys(&self.keys, &key) {
+                        Result::Ok(index) => {
+                            writer.set(index, val);
+                        }
+                        Result::Err(index) => {
+                            self.keys.insert(index, key);
+                            writer.insert(index, val);
+                        }
+                    }
+                }
+                MapDiff::Update { key } => {
+                    // Values are updated in place, since their key keeps its position.
+                    let val = map_event.snapshot.get(&key).unwrap().clone();
+                    if let Result::Ok(index) = search_keys(&self.keys, &key) {
+                        writer.set(index, val);
+                    }
+                }
+                MapDiff::Remove { key } => {
+                    if let Result::Ok(index) = search_keys(&self.keys, &key) {
+                        self.keys.remove(index);
+                        writer.remove(index);
+                    }
+                }
+                MapDiff::Clear {} => {
+                    self.keys.clear();
+                    writer.clear();
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn get_signal(&self) -> Self::OutputSignal {
+        self.vector.as_signal()
+    }
+}
+
+// ** SORTED ** //
+
+pub struct SortedHashMapTransformer<K, V>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+{
+    vector: MutableVector<(K, V)>,
+}
+
+impl<K, V> SortedHashMapTransformer<K, V>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+{
+    pub(crate) fn new() -> SortedHashMapTransformer<K, V> {
+        SortedHashMapTransformer {
+            vector: MutableVector::new(),
+        }
+    }
+}
+
+#[inline]
+fn search_sorted_entries<K, V>(entries: &Vector<(K, V)>, key: &K) -> Result<usize, usize>
+where
+    K: Ord + Clone,
+    V: Clone,
+{
+    entries.binary_search_by(|entry| entry.0.cmp(key))
+}
+
+impl<K, V> StructuralSignalTransformer for SortedHashMapTransformer<K, V>
+where
+    K: Hash + Ord + Clone,
+    V: Clone,
+{
+    type InputEvent = HashMapEvent<K, V>;
+    type OutputSignal = PullSourceStructuralSignal<MutableVectorState<(K, V)>>;
+
+    fn apply_event(&mut self, map_event: HashMapEvent<K, V>) {
+        let mut writer = self.vector.write();
+        for diff in map_event.diffs {
+            match diff {
+                MapDiff::Replace {} => {
+                    let mut snapshot_vec = map_event
+                        .snapshot
+                        .clone()
+                        .into_iter()
+                        .collect::<Vec<(K, V)>>();
+                    snapshot_vec.sort_by(|a, b| a.0.cmp(&b.0));
+                    writer.replace(snapshot_vec.into_iter());
+                }
+                MapDiff::Insert { key } => {
+                    let val = map_event.snapshot.get(&key).unwrap().clone();
+                    match search_sorted_entries(&writer, &key) {
+                        Result::Ok(index) => {
+                            writer.set(index, (key, val));
+                        }
+                        Result::Err(index) => {
+                            writer.insert(index, (key, val));
+                        }
+                    }
+                }
+                MapDiff::Update { key } => {
+                    let val = map_event.snapshot.get(&key).unwrap().clone();
+                    if let Result::Ok(index) = search_sorted_entries(&writer, &key) {
+                        writer.set(index, (key, val));
+                    }
+                }
+                MapDiff::Remove { key } => {
+                    if let Result::Ok(index) = search_sorted_entries(&writer, &key) {
+                        writer.remove(index);
+                    }
+                }
+                MapDiff::Clear {} => {
+                    writer.clear();
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn get_signal(&self) -> Self::OutputSignal {
+        self.vector.as_signal()
+    }
+}
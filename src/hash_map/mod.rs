@@ -1,8 +1,18 @@
+pub mod compat;
 mod event;
 mod hash_map;
 mod signal_ext;
 mod map_transforms;
 
-pub use event::{HashMapEvent, MapDiff};
+pub use compat::{signal_map_to_structural, SignalMapStructuralSignal};
+pub use event::{diff_maps, HashMapEvent, MapDiff};
 pub use hash_map::{MutableHashMap, MutableHashMapReader};
-pub use signal_ext::{SignalHashMapExt, SignalHashMapKeyWatcher};
\ No newline at end of file
+pub use map_transforms::{
+    EntriesHashMapTransformer, FilterHashMapTransformer, FilterMapHashMapTransformer,
+    KeysHashMapTransformer, MapHashMapTransformer, MapKeysHashMapTransformer, MapValuesOptions,
+    MapWithOptionsHashMapTransformer, SortedHashMapTransformer, TryMapHashMapTransformer,
+    ValuesHashMapTransformer,
+};
+pub use signal_ext::{
+    EntrySignals, EntryValueSignal, MergeHashMapSignal, SignalHashMapExt, SignalHashMapKeyWatcher,
+};
\ No newline at end of file
@@ -1,15 +1,26 @@
-use super::event::HashMapEvent;
+use super::event::{HashMapEvent, MapDiff};
+use super::hash_map::{MutableHashMap, MutableHashMapState};
 use super::map_transforms::{
-    EntriesHashMapTransformer, FilterHashMapTransformer, MapHashMapTransformer,
+    EntriesHashMapTransformer, FilterHashMapTransformer, FilterMapHashMapTransformer,
+    KeysHashMapTransformer, MapHashMapTransformer, MapKeysHashMapTransformer, MapValuesOptions,
+    MapWithOptionsHashMapTransformer, SortedHashMapTransformer, TryMapHashMapTransformer,
+    ValuesHashMapTransformer,
+};
+use crate::structural_signal::adapters::LenSignal;
+use crate::structural_signal::pull_source::{PullSourceStructuralSignal, PullableDiff};
+use crate::structural_signal::transformer::{
+    FallibleTransformedStructuralSignal, TransformedStructuralSignal,
 };
-use crate::structural_signal::pull_source::PullableDiff;
-use crate::structural_signal::transformer::TransformedStructuralSignal;
 use crate::StructuralSignal;
 use core::hash::Hash;
 use futures_signals::signal::Signal;
+use im::HashMap;
+use parking_lot::RwLock;
 use pin_project::pin_project;
+use std::collections::HashSet;
 use std::pin::Pin;
-use std::task::{Context, Poll};
+use std::sync::Arc;
+use std::task::{Context, Poll, Waker};
 
 #[pin_project(project = SignalHashMapKeyWatcherProj)]
 pub struct SignalHashMapKeyWatcher<K, V, S>
@@ -75,6 +86,377 @@ where
     }
 }
 
+pub struct EntrySignalsState<K, V, S>
+where
+    K: Clone + Eq + Hash,
+    V: Clone,
+    S: StructuralSignal<Item = HashMapEvent<K, V>>,
+    S: Unpin,
+{
+    input: S,
+    snapshot: HashMap<K, V>,
+    // For every key currently in the map, the version at which it was inserted and
+    // the version at which it was last changed.
+    versions: HashMap<K, (usize, usize)>,
+    next_version: usize,
+    // Entry signals waiting for a change. Only the most recent poller is registered
+    // with the input, so everyone else has to be woken when it pulls in changes.
+    wakers: Vec<Waker>,
+    is_closed: bool,
+}
+
+impl<K, V, S> EntrySignalsState<K, V, S>
+where
+    K: Clone + Eq + Hash,
+    V: Clone,
+    S: StructuralSignal<Item = HashMapEvent<K, V>>,
+    S: Unpin,
+{
+    fn touch_key(&mut self, key: K) {
+        let version = self.next_version;
+        self.next_version += 1;
+        self.versions
+            .entry(key)
+            .and_modify(|(_, updated_at)| *updated_at = version)
+            .or_insert((version, version));
+    }
+
+    fn apply_event(&mut self, event: HashMapEvent<K, V>) {
+        for diff in event.diffs.iter() {
+            match diff {
+                MapDiff::Replace {} => {
+                    let snapshot = &event.snapshot;
+                    self.versions.retain(|key, _| snapshot.contains_key(key));
+                    for key in event.snapshot.keys() {
+                        self.touch_key(key.clone());
+                    }
+                }
+                MapDiff::Insert { key } | MapDiff::Update { key } => {
+                    self.touch_key(key.clone());
+                }
+                MapDiff::Remove { key } => {
+                    self.versions.remove(key);
+                }
+                MapDiff::Clear {} => {
+                    self.versions.clear();
+                }
+            }
+        }
+        self.snapshot = event.snapshot;
+    }
+
+    fn pull_in_new_changes(&mut self, cx: &mut Context) {
+        let mut has_new_changes = false;
+        while !self.is_closed {
+            match Pin::new(&mut self.input).poll_change(cx) {
+                Poll::Ready(Some(event)) => {
+                    self.apply_event(event);
+                    has_new_changes = true;
+                }
+                Poll::Ready(None) => {
+                    self.is_closed = true;
+                    has_new_changes = true;
+                }
+                Poll::Pending => break,
+            }
+        }
+
+        if has_new_changes {
+            for waker in self.wakers.drain(..) {
+                waker.wake();
+            }
+        }
+    }
+}
+
+/// Hands out a Signal for the value of each key in a map signal. Created by
+/// `SignalHashMapExt::entries_signal_map`.
+///
+/// A version record is kept for each key currently in the map, and dropped as soon as
+/// the key is removed, so memory use is bounded by the current size of the map rather
+/// than by every key it has ever held.
+pub struct EntrySignals<K, V, S>(Arc<RwLock<EntrySignalsState<K, V, S>>>)
+where
+    K: Clone + Eq + Hash,
+    V: Clone,
+    S: StructuralSignal<Item = HashMapEvent<K, V>>,
+    S: Unpin;
+
+impl<K, V, S> EntrySignals<K, V, S>
+where
+    K: Clone + Eq + Hash,
+    V: Clone,
+    S: StructuralSignal<Item = HashMapEvent<K, V>>,
+    S: Unpin,
+{
+    pub(crate) fn new(input: S) -> EntrySignals<K, V, S> {
+        EntrySignals(Arc::new(RwLock::new(EntrySignalsState {
+            input,
+            snapshot: HashMap::new(),
+            versions: HashMap::new(),
+            next_version: 0,
+            wakers: vec![],
+            is_closed: false,
+        })))
+    }
+
+    /// Returns a Signal of the value at the given key. The Signal completes as soon as
+    /// the key is removed, so it only lives as long as the entry does. If the key is
+    /// not in the map when the Signal is first polled, it completes right away.
+    /// Removing a key and inserting it again still completes the Signal, so request a
+    /// new one for the new entry.
+    pub fn signal_for_key(&self, key: K) -> EntryValueSignal<K, V, S> {
+        EntryValueSignal {
+            key,
+            parent: self.0.clone(),
+            inserted_at: None,
+            updated_at: None,
+            is_done: false,
+        }
+    }
+
+    /// Diagnostic: the number of keys with a version record, as of the last time one
+    /// of the entry Signals was polled.
+    pub fn tracked_key_count(&self) -> usize {
+        self.0.read().versions.len()
+    }
+}
+
+/// A Signal of the value of one entry, created by `EntrySignals::signal_for_key`.
+#[pin_project(project = EntryValueSignalProj)]
+pub struct EntryValueSignal<K, V, S>
+where
+    K: Clone + Eq + Hash,
+    V: Clone,
+    S: StructuralSignal<Item = HashMapEvent<K, V>>,
+    S: Unpin,
+{
+    key: K,
+    parent: Arc<RwLock<EntrySignalsState<K, V, S>>>,
+    inserted_at: Option<usize>,
+    updated_at: Option<usize>,
+    is_done: bool,
+}
+
+impl<K, V, S> Signal for EntryValueSignal<K, V, S>
+where
+    K: Clone + Eq + Hash,
+    V: Clone,
+    S: StructuralSignal<Item = HashMapEvent<K, V>>,
+    S: Unpin,
+{
+    type Item = V;
+
+    fn poll_change(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<V>> {
+        let EntryValueSignalProj {
+            key,
+            parent,
+            inserted_at,
+            updated_at,
+            is_done,
+        } = self.project();
+        if *is_done {
+            return Poll::Ready(None);
+        }
+
+        let mut state = parent.write();
+        state.pull_in_new_changes(cx);
+        let (key_inserted_at, key_updated_at) = match state.versions.get(&*key) {
+            Some(&versions) => versions,
+            None => {
+                *is_done = true;
+                return Poll::Ready(None);
+            }
+        };
+
+        // A different insert version means the entry was removed and re-added.
+        if inserted_at.map_or(false, |version| version != key_inserted_at) {
+            *is_done = true;
+            return Poll::Ready(None);
+        }
+        *inserted_at = Some(key_inserted_at);
+
+        if *updated_at == Some(key_updated_at) {
+            if state.is_closed {
+                *is_done = true;
+                return Poll::Ready(None);
+            }
+            state.wakers.push(cx.waker().clone());
+            return Poll::Pending;
+        }
+        *updated_at = Some(key_updated_at);
+        Poll::Ready(state.snapshot.get(&*key).cloned())
+    }
+}
+
+/// A StructuralSignal whose map holds every entry of two map signals. When both maps
+/// have a value for the same key, the merged value is decided by a resolver function.
+#[pin_project(project = MergeHashMapSignalProj)]
+pub struct MergeHashMapSignal<LS, RS, K, V, F>
+where
+    LS: StructuralSignal<Item = HashMapEvent<K, V>>,
+    RS: StructuralSignal<Item = HashMapEvent<K, V>>,
+    K: Clone + Eq + Hash,
+    V: Clone + PartialEq,
+    F: Fn(&V, &V) -> V,
+{
+    #[pin]
+    left_signal: LS,
+    #[pin]
+    right_signal: RS,
+    #[pin]
+    merged_signal: PullSourceStructuralSignal<MutableHashMapState<K, V>>,
+
+    // The latest snapshot of each input, so that a key can fall back to the other
+    // input's value when it is removed from one of them.
+    left: HashMap<K, V>,
+    right: HashMap<K, V>,
+    hash_map: MutableHashMap<K, V>,
+    resolve: F,
+
+    left_closed: bool,
+    right_closed: bool,
+}
+
+impl<LS, RS, K, V, F> MergeHashMapSignal<LS, RS, K, V, F>
+where
+    LS: StructuralSignal<Item = HashMapEvent<K, V>>,
+    RS: StructuralSignal<Item = HashMapEvent<K, V>>,
+    K: Clone + Eq + Hash,
+    V: Clone + PartialEq,
+    F: Fn(&V, &V) -> V,
+{
+    pub(crate) fn new(
+        left_signal: LS,
+        right_signal: RS,
+        resolve: F,
+    ) -> MergeHashMapSignal<LS, RS, K, V, F> {
+        let hash_map = MutableHashMap::new();
+        MergeHashMapSignal {
+            left_signal,
+            right_signal,
+            merged_signal: hash_map.as_signal(),
+            left: HashMap::new(),
+            right: HashMap::new(),
+            hash_map,
+            resolve,
+            left_closed: false,
+            right_closed: false,
+        }
+    }
+}
+
+// Finds the keys whose merged value may have changed because of an event on one of
+// the inputs. Diffs without a key can change any key in the old or new snapshot.
+fn keys_affected_by<K, V>(event: &HashMapEvent<K, V>, previous: &HashMap<K, V>) -> HashSet<K>
+where
+    K: Clone + Eq + Hash,
+    V: Clone,
+{
+    let mut keys = HashSet::new();
+    for diff in event.diffs.iter() {
+        match diff.get_key() {
+            Some(key) => {
+                keys.insert(key.clone());
+            }
+            None => {
+                keys.extend(previous.keys().cloned());
+                keys.extend(event.snapshot.keys().cloned());
+            }
+        }
+    }
+    keys
+}
+
+fn remerge_key<K, V, F>(
+    writer: &mut MutableHashMapState<K, V>,
+    left: &HashMap<K, V>,
+    right: &HashMap<K, V>,
+    resolve: &F,
+    key: K,
+) where
+    K: Clone + Eq + Hash,
+    V: Clone + PartialEq,
+    F: Fn(&V, &V) -> V,
+{
+    let merged = match (left.get(&key), right.get(&key)) {
+        (Some(l), Some(r)) => Some(resolve(l, r)),
+        (Some(value), None) | (None, Some(value)) => Some(value.clone()),
+        (None, None) => None,
+    };
+    match merged {
+        Some(value) => {
+            if writer.get(&key) != Some(&value) {
+                writer.insert(key, value);
+            }
+        }
+        None => {
+            writer.remove(&key);
+        }
+    }
+}
+
+impl<LS, RS, K, V, F> StructuralSignal for MergeHashMapSignal<LS, RS, K, V, F>
+where
+    LS: StructuralSignal<Item = HashMapEvent<K, V>>,
+    RS: StructuralSignal<Item = HashMapEvent<K, V>>,
+    K: Clone + Eq + Hash,
+    V: Clone + PartialEq,
+    F: Fn(&V, &V) -> V,
+{
+    type Item = HashMapEvent<K, V>;
+
+    fn poll_change(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<HashMapEvent<K, V>>> {
+        let MergeHashMapSignalProj {
+            mut left_signal,
+            mut right_signal,
+            merged_signal,
+            left,
+            right,
+            hash_map,
+            resolve,
+            left_closed,
+            right_closed,
+        } = self.project();
+
+        let mut writer = hash_map.write();
+        while !*left_closed {
+            match left_signal.as_mut().poll_change(cx) {
+                Poll::Ready(Some(event)) => {
+                    let keys = keys_affected_by(&event, left);
+                    *left = event.snapshot;
+                    for key in keys {
+                        remerge_key(&mut writer, left, right, resolve, key);
+                    }
+                }
+                Poll::Ready(None) => *left_closed = true,
+                Poll::Pending => break,
+            }
+        }
+        while !*right_closed {
+            match right_signal.as_mut().poll_change(cx) {
+                Poll::Ready(Some(event)) => {
+                    let keys = keys_affected_by(&event, right);
+                    *right = event.snapshot;
+                    for key in keys {
+                        remerge_key(&mut writer, left, right, resolve, key);
+                    }
+                }
+                Poll::Ready(None) => *right_closed = true,
+                Poll::Pending => break,
+            }
+        }
+        drop(writer);
+
+        let result = merged_signal.poll_change(cx);
+        if *left_closed && *right_closed && !result.is_ready() {
+            Poll::Ready(None)
+        } else {
+            result
+        }
+    }
+}
+
 pub trait SignalHashMapExt: StructuralSignal
 where
     Self: Sized,
@@ -119,6 +501,134 @@ where
         Self::Value: Clone,
         F: Fn(&Self::Value) -> OV;
 
+    /// Like `map_values`, but accepts a stateful `FnMut` transformer function. It is
+    /// called once for each inserted or updated value, in the order the diffs of each
+    /// event arrive in, so any state it keeps follows the order of changes rather than
+    /// the order of keys. The values of a `Replace` are mapped in iteration order.
+    ///
+    /// ```
+    /// use signals_im::hash_map::{MutableHashMap, SignalHashMapExt};
+    /// use signals_im::StructuralSignalExt;
+    /// use im::hashmap;
+    ///
+    /// let input_map = MutableHashMap::<u8, u8>::new();
+    /// input_map.write().insert(1, 1);
+    ///
+    /// let mut calls = 0;
+    /// let numbered = input_map.as_signal().map_values_mut(move |v| {
+    ///     calls += 1;
+    ///     (calls, *v)
+    /// });
+    ///
+    /// let numbered_map = numbered.snapshot().unwrap();
+    /// assert_eq!(numbered_map, hashmap!{1 => (1, 1)});
+    /// ```
+    fn map_values_mut<OV, F>(
+        self,
+        map_fn: F,
+    ) -> TransformedStructuralSignal<
+        Self::SelfType,
+        <Self::SelfType as StructuralSignal>::Item,
+        MapHashMapTransformer<Self::Key, F, Self::Value, OV>,
+    >
+    where
+        OV: Clone,
+        Self::Value: Clone,
+        F: FnMut(&Self::Value) -> OV;
+
+    /// Like `map_values`, but with options that trade extra work or memory for fewer
+    /// map function calls or output diffs. See `MapValuesOptions` for what each option
+    /// does. Since the options are chosen at runtime, the bounds they need apply even
+    /// when they are off: values must be `Eq + Hash` so they can key the memo, and
+    /// mapped values must be `PartialEq` so they can be deduplicated.
+    ///
+    /// ```
+    /// use signals_im::hash_map::{MapValuesOptions, MutableHashMap, SignalHashMapExt};
+    /// use signals_im::StructuralSignalExt;
+    /// use im::hashmap;
+    ///
+    /// let input_map = MutableHashMap::<u8, u8>::new();
+    /// input_map.write().insert(1, 1);
+    ///
+    /// let options = MapValuesOptions { dedupe: true, ..Default::default() };
+    /// let parity = input_map.as_signal().map_values_with(options, |v| v % 2);
+    /// input_map.write().insert(1, 3);
+    ///
+    /// let parity_map = parity.snapshot().unwrap();
+    /// assert_eq!(parity_map, hashmap!{1 => 1});
+    /// ```
+    fn map_values_with<OV, F>(
+        self,
+        options: MapValuesOptions,
+        map_fn: F,
+    ) -> TransformedStructuralSignal<
+        Self::SelfType,
+        <Self::SelfType as StructuralSignal>::Item,
+        MapWithOptionsHashMapTransformer<Self::Key, F, Self::Value, OV>,
+    >
+    where
+        OV: Clone + PartialEq,
+        Self::Value: Clone + Eq + Hash,
+        F: Fn(&Self::Value) -> OV;
+
+    /// Like `map_values`, but the transformer function can fail. Events are emitted as
+    /// `Ok` until the first failure, which is emitted as an `Err` event and ends the
+    /// signal. The event that caused the failure is not applied at all.
+    ///
+    /// ```
+    /// use signals_im::hash_map::{MutableHashMap, SignalHashMapExt};
+    /// use signals_im::StructuralSignalExt;
+    ///
+    /// let input_map = MutableHashMap::<u8, u8>::new();
+    /// input_map.write().insert(1, 0);
+    ///
+    /// let divided = input_map.as_signal().try_map_values(|v| 10u8.checked_div(*v).ok_or(*v));
+    /// assert_eq!(divided.take_ready_events(3).last(), Some(&Err(0)));
+    /// ```
+    fn try_map_values<OV, E, F>(
+        self,
+        map_fn: F,
+    ) -> FallibleTransformedStructuralSignal<
+        Self::SelfType,
+        <Self::SelfType as StructuralSignal>::Item,
+        TryMapHashMapTransformer<Self::Key, F, Self::Value, OV, E>,
+    >
+    where
+        OV: Clone,
+        E: Clone,
+        F: Fn(&Self::Value) -> Result<OV, E>;
+
+    /// Returns a version of this signal where every key in the map has been run
+    /// through a transformer function. If several keys map to the same output key,
+    /// the output holds the value of whichever of them was written most recently;
+    /// removing that key falls back to the next most recently written one.
+    ///
+    /// ```
+    /// use signals_im::hash_map::{MutableHashMap, SignalHashMapExt};
+    /// use signals_im::StructuralSignalExt;
+    /// use im::hashmap;
+    ///
+    /// let input_map = MutableHashMap::<u8, u8>::new();
+    /// input_map.write().insert(1, 1);
+    /// input_map.write().insert(12, 2);
+    ///
+    /// let by_last_digit = input_map.as_signal().map_keys(|k| k % 10);
+    ///
+    /// let by_last_digit_map = by_last_digit.snapshot().unwrap();
+    /// assert_eq!(by_last_digit_map, hashmap!{1 => 1, 2 => 2});
+    /// ```
+    fn map_keys<OK, F>(
+        self,
+        map_fn: F,
+    ) -> TransformedStructuralSignal<
+        Self::SelfType,
+        <Self::SelfType as StructuralSignal>::Item,
+        MapKeysHashMapTransformer<Self::Key, OK, Self::Value, F>,
+    >
+    where
+        OK: Clone + Eq + Hash,
+        F: Fn(&Self::Key) -> OK;
+
     /// Returns a version of this signal that includes only map entries that pass a predicate test.
     ///
     /// ```
@@ -149,7 +659,76 @@ where
         Self::Value: Clone,
         F: Fn(&Self::Value) -> bool;
 
-    /// Returns a version of this signal that includes only map entries that pass a predicate test.
+    /// Like `filter`, but accepts a stateful `FnMut` predicate. It is called once for
+    /// each inserted or updated value, in the order the diffs of each event arrive in.
+    /// The values of a `Replace` are tested in iteration order.
+    ///
+    /// ```
+    /// use signals_im::hash_map::{MutableHashMap, SignalHashMapExt};
+    /// use signals_im::StructuralSignalExt;
+    /// use im::hashmap;
+    ///
+    /// let input_map = MutableHashMap::<u8, u8>::new();
+    /// input_map.write().insert(1, 1);
+    ///
+    /// let mut budget = 1;
+    /// let limited = input_map.as_signal().filter_mut(move |_v| {
+    ///     budget -= 1;
+    ///     budget >= 0
+    /// });
+    ///
+    /// let limited_map = limited.snapshot().unwrap();
+    /// assert_eq!(limited_map, hashmap!{1 => 1});
+    /// ```
+    fn filter_mut<F>(
+        self,
+        predicate: F,
+    ) -> TransformedStructuralSignal<
+        Self::SelfType,
+        <Self::SelfType as StructuralSignal>::Item,
+        FilterHashMapTransformer<Self::Key, Self::Value, F>,
+    >
+    where
+        Self::Value: Clone,
+        F: FnMut(&Self::Value) -> bool;
+
+    /// Filters and maps the values of this signal in a single pass. Entries for which
+    /// the function returns `None` are left out of the output map, and removed from it
+    /// if an update makes them stop passing.
+    ///
+    /// ```
+    /// use signals_im::hash_map::{MutableHashMap, SignalHashMapExt};
+    /// use signals_im::StructuralSignalExt;
+    /// use im::hashmap;
+    ///
+    /// let input_map = MutableHashMap::<u8, &str>::new();
+    /// input_map.write().insert(1, "10");
+    /// input_map.write().insert(2, "ten");
+    ///
+    /// let parsed = input_map.as_signal().filter_map(|v| v.parse::<u8>().ok());
+    ///
+    /// let parsed_map = parsed.snapshot().unwrap();
+    /// assert_eq!(parsed_map, hashmap!{1 => 10});
+    /// ```
+    fn filter_map<OV, F>(
+        self,
+        filter_map_fn: F,
+    ) -> TransformedStructuralSignal<
+        Self::SelfType,
+        <Self::SelfType as StructuralSignal>::Item,
+        FilterMapHashMapTransformer<Self::Key, Self::Value, OV, F>,
+    >
+    where
+        OV: Clone,
+        F: Fn(&Self::Value) -> Option<OV>;
+
+    /// Returns a vector signal of the (key, value) entries in this map. Entries are
+    /// ordered by the hash of their key, falling back to the key's own ordering when
+    /// two keys have the same hash, so the order is deterministic within a run.
+    ///
+    /// Replacing the map emits an Insert or Remove for each key that was added or
+    /// removed and an Update for each key that was kept, rather than rebuilding the
+    /// whole vector.
     ///
     /// ```
     /// use signals_im::hash_map::{MutableHashMap, SignalHashMapExt};
@@ -175,7 +754,159 @@ where
         EntriesHashMapTransformer<Self::Key, Self::Value>,
     >
     where
+        Self::Key: Ord,
         Self::Value: Clone;
+
+    /// Returns a vector signal of the keys in this map, in the same order as `entries`.
+    /// Updating the value at a key does not change the keys, so it emits nothing.
+    ///
+    /// ```
+    /// use signals_im::hash_map::{MutableHashMap, SignalHashMapExt};
+    /// use signals_im::StructuralSignalExt;
+    ///
+    /// let input_map = MutableHashMap::<u8, u8>::new();
+    /// input_map.write().insert(1, 1);
+    /// input_map.write().insert(2, 1);
+    ///
+    /// let mut keys: Vec<u8> = input_map.as_signal().keys().snapshot().unwrap().into_iter().collect();
+    /// keys.sort();
+    /// assert_eq!(keys, vec![1, 2]);
+    /// ```
+    fn keys(
+        self,
+    ) -> TransformedStructuralSignal<
+        Self::SelfType,
+        <Self::SelfType as StructuralSignal>::Item,
+        KeysHashMapTransformer<Self::Key, Self::Value>,
+    >
+    where
+        Self::Key: Ord,
+        Self::Value: Clone;
+
+    /// Returns a vector signal of the values in this map, in the same order as `entries`.
+    /// Updating the value at a key updates it in place in the output.
+    ///
+    /// ```
+    /// use signals_im::hash_map::{MutableHashMap, SignalHashMapExt};
+    /// use signals_im::StructuralSignalExt;
+    ///
+    /// let input_map = MutableHashMap::<u8, u8>::new();
+    /// input_map.write().insert(1, 10);
+    /// input_map.write().insert(2, 20);
+    ///
+    /// let mut values: Vec<u8> = input_map.as_signal().values().snapshot().unwrap().into_iter().collect();
+    /// values.sort();
+    /// assert_eq!(values, vec![10, 20]);
+    /// ```
+    fn values(
+        self,
+    ) -> TransformedStructuralSignal<
+        Self::SelfType,
+        <Self::SelfType as StructuralSignal>::Item,
+        ValuesHashMapTransformer<Self::Key, Self::Value>,
+    >
+    where
+        Self::Key: Ord,
+        Self::Value: Clone;
+
+    /// Returns a vector signal of the (key, value) entries in this map, sorted by key.
+    /// Unlike `entries`, the order is meaningful and does not depend on hashing.
+    ///
+    /// ```
+    /// use signals_im::hash_map::{MutableHashMap, SignalHashMapExt};
+    /// use signals_im::StructuralSignalExt;
+    /// use im::vector;
+    ///
+    /// let input_map = MutableHashMap::<u8, u8>::new();
+    /// input_map.write().insert(3, 2);
+    /// input_map.write().insert(1, 1);
+    /// input_map.write().insert(2, 1);
+    ///
+    /// let sorted = input_map.as_signal().to_sorted();
+    /// assert_eq!(sorted.snapshot().unwrap(), vector![(1, 1), (2, 1), (3, 2)]);
+    /// ```
+    fn to_sorted(
+        self,
+    ) -> TransformedStructuralSignal<
+        Self::SelfType,
+        <Self::SelfType as StructuralSignal>::Item,
+        SortedHashMapTransformer<Self::Key, Self::Value>,
+    >
+    where
+        Self::Key: Ord,
+        Self::Value: Clone;
+
+    /// Returns a Signal of the number of entries in this map. It fires with the initial
+    /// count, and then only when the count changes, so updating the value at a key, or
+    /// removing one key and inserting another between polls, does not fire it.
+    ///
+    /// ```
+    /// use futures_signals::signal::SignalExt;
+    /// use signals_im::hash_map::{MutableHashMap, SignalHashMapExt};
+    ///
+    /// let input_map = MutableHashMap::<u8, u8>::new();
+    /// let lengths = input_map.as_signal().len_signal().to_stream();
+    /// input_map.write().insert(1, 1);
+    ///
+    /// let lengths: Vec<usize> = futures_executor::block_on_stream(lengths).take(1).collect();
+    /// assert_eq!(lengths, vec![1]);
+    /// ```
+    fn len_signal(self) -> LenSignal<Self::SelfType>;
+
+    /// Returns a structure that hands out a Signal for the value at each key. Each of
+    /// those Signals completes when its key is removed, which makes this useful for
+    /// fine-grained UIs that give every entry its own reactive view. The per-key
+    /// Signals share one subscription to this map, and it is dropped along with the
+    /// last of them.
+    ///
+    /// ```
+    /// use futures_signals::signal::SignalExt;
+    /// use signals_im::hash_map::{MutableHashMap, SignalHashMapExt};
+    ///
+    /// let input_map = MutableHashMap::<u8, u8>::new();
+    /// input_map.write().insert(1, 1);
+    ///
+    /// let entry_signals = input_map.as_signal().entries_signal_map();
+    /// let values = entry_signals.signal_for_key(1).to_stream();
+    /// input_map.write().remove(&1);
+    ///
+    /// let values: Vec<u8> = futures_executor::block_on_stream(values).collect();
+    /// assert!(values.is_empty());
+    /// ```
+    fn entries_signal_map(self) -> EntrySignals<Self::Key, Self::Value, Self::SelfType>
+    where
+        Self::SelfType: Unpin;
+
+    /// Merges this map with another into a single map that holds the entries of both.
+    /// When both maps have a value for the same key, `resolve` is called with this
+    /// map's value and then the other map's value to decide the merged value. Removing
+    /// a key from one map falls back to the other map's value, if it has one.
+    ///
+    /// ```
+    /// use signals_im::hash_map::{MutableHashMap, SignalHashMapExt};
+    /// use signals_im::StructuralSignalExt;
+    /// use im::hashmap;
+    ///
+    /// let defaults = MutableHashMap::<&str, u8>::new();
+    /// defaults.write().insert("volume", 5);
+    /// defaults.write().insert("brightness", 5);
+    /// let overrides = MutableHashMap::<&str, u8>::new();
+    /// overrides.write().insert("volume", 9);
+    ///
+    /// let settings = defaults
+    ///     .as_signal()
+    ///     .merge(overrides.as_signal(), |_default, value| *value);
+    /// assert_eq!(settings.snapshot().unwrap(), hashmap!{"volume" => 9, "brightness" => 5});
+    /// ```
+    fn merge<S2, F>(
+        self,
+        other: S2,
+        resolve: F,
+    ) -> MergeHashMapSignal<Self::SelfType, S2, Self::Key, Self::Value, F>
+    where
+        S2: StructuralSignal<Item = HashMapEvent<Self::Key, Self::Value>>,
+        Self::Value: PartialEq,
+        F: Fn(&Self::Value, &Self::Value) -> Self::Value;
 }
 
 impl<K, V, I> SignalHashMapExt for I
@@ -215,6 +946,73 @@ where
         TransformedStructuralSignal::new(self, MapHashMapTransformer::new(map_fn))
     }
 
+    fn map_values_mut<OV, F>(
+        self,
+        map_fn: F,
+    ) -> TransformedStructuralSignal<
+        Self,
+        Self::Item,
+        MapHashMapTransformer<Self::Key, F, Self::Value, OV>,
+    >
+    where
+        OV: Clone,
+        Self::Value: Clone,
+        F: FnMut(&Self::Value) -> OV,
+    {
+        TransformedStructuralSignal::new(self, MapHashMapTransformer::new(map_fn))
+    }
+
+    fn map_values_with<OV, F>(
+        self,
+        options: MapValuesOptions,
+        map_fn: F,
+    ) -> TransformedStructuralSignal<
+        Self,
+        Self::Item,
+        MapWithOptionsHashMapTransformer<Self::Key, F, Self::Value, OV>,
+    >
+    where
+        OV: Clone + PartialEq,
+        Self::Value: Clone + Eq + Hash,
+        F: Fn(&Self::Value) -> OV,
+    {
+        TransformedStructuralSignal::new(
+            self,
+            MapWithOptionsHashMapTransformer::new(options, map_fn),
+        )
+    }
+
+    fn try_map_values<OV, E, F>(
+        self,
+        map_fn: F,
+    ) -> FallibleTransformedStructuralSignal<
+        Self,
+        Self::Item,
+        TryMapHashMapTransformer<Self::Key, F, Self::Value, OV, E>,
+    >
+    where
+        OV: Clone,
+        E: Clone,
+        F: Fn(&Self::Value) -> Result<OV, E>,
+    {
+        FallibleTransformedStructuralSignal::new(self, TryMapHashMapTransformer::new(map_fn))
+    }
+
+    fn map_keys<OK, F>(
+        self,
+        map_fn: F,
+    ) -> TransformedStructuralSignal<
+        Self,
+        Self::Item,
+        MapKeysHashMapTransformer<Self::Key, OK, Self::Value, F>,
+    >
+    where
+        OK: Clone + Eq + Hash,
+        F: Fn(&Self::Key) -> OK,
+    {
+        TransformedStructuralSignal::new(self, MapKeysHashMapTransformer::new(map_fn))
+    }
+
     fn filter<F>(
         self,
         predicate: F,
@@ -230,6 +1028,36 @@ where
         TransformedStructuralSignal::new(self, FilterHashMapTransformer::new(predicate))
     }
 
+    fn filter_mut<F>(
+        self,
+        predicate: F,
+    ) -> TransformedStructuralSignal<
+        Self,
+        Self::Item,
+        FilterHashMapTransformer<Self::Key, Self::Value, F>,
+    >
+    where
+        Self::Value: Clone,
+        F: FnMut(&Self::Value) -> bool,
+    {
+        TransformedStructuralSignal::new(self, FilterHashMapTransformer::new(predicate))
+    }
+
+    fn filter_map<OV, F>(
+        self,
+        filter_map_fn: F,
+    ) -> TransformedStructuralSignal<
+        Self,
+        Self::Item,
+        FilterMapHashMapTransformer<Self::Key, Self::Value, OV, F>,
+    >
+    where
+        OV: Clone,
+        F: Fn(&Self::Value) -> Option<OV>,
+    {
+        TransformedStructuralSignal::new(self, FilterMapHashMapTransformer::new(filter_map_fn))
+    }
+
     fn entries(
         self,
     ) -> TransformedStructuralSignal<
@@ -238,8 +1066,71 @@ where
         EntriesHashMapTransformer<Self::Key, Self::Value>,
     >
     where
+        Self::Key: Ord,
         Self::Value: Clone,
     {
         TransformedStructuralSignal::new(self, EntriesHashMapTransformer::new())
     }
+
+    fn keys(
+        self,
+    ) -> TransformedStructuralSignal<
+        Self::SelfType,
+        <Self::SelfType as StructuralSignal>::Item,
+        KeysHashMapTransformer<Self::Key, Self::Value>,
+    >
+    where
+        Self::Key: Ord,
+        Self::Value: Clone,
+    {
+        TransformedStructuralSignal::new(self, KeysHashMapTransformer::new())
+    }
+
+    fn values(
+        self,
+    ) -> TransformedStructuralSignal<
+        Self::SelfType,
+        <Self::SelfType as StructuralSignal>::Item,
+        ValuesHashMapTransformer<Self::Key, Self::Value>,
+    >
+    where
+        Self::Key: Ord,
+        Self::Value: Clone,
+    {
+        TransformedStructuralSignal::new(self, ValuesHashMapTransformer::new())
+    }
+
+    fn to_sorted(
+        self,
+    ) -> TransformedStructuralSignal<
+        Self::SelfType,
+        <Self::SelfType as StructuralSignal>::Item,
+        SortedHashMapTransformer<Self::Key, Self::Value>,
+    >
+    where
+        Self::Key: Ord,
+        Self::Value: Clone,
+    {
+        TransformedStructuralSignal::new(self, SortedHashMapTransformer::new())
+    }
+
+    fn len_signal(self) -> LenSignal<Self> {
+        LenSignal::new(self, |event| event.snapshot.len())
+    }
+
+    fn entries_signal_map(self) -> EntrySignals<Self::Key, Self::Value, Self>
+    where
+        Self: Unpin,
+    {
+        EntrySignals::new(self)
+    }
+
+    fn merge<S2, F>(self, other: S2, resolve: F) -> MergeHashMapSignal<Self, S2, K, V, F>
+    where
+        S2: StructuralSignal<Item = HashMapEvent<K, V>>,
+        V: PartialEq,
+        F: Fn(&V, &V) -> V,
+    {
+        MergeHashMapSignal::new(self, other, resolve)
+    }
 }
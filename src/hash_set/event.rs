@@ -0,0 +1,140 @@
+use crate::structural_signal::pull_source::PullableDiff;
+use crate::structural_signal::pull_source::{coalesce_diffs, DiffMergeResult};
+use crate::structural_signal::structural_signal_ext::{
+    MergeableEvent, SnapshottableEvent, SplittableEvent,
+};
+use core::hash::Hash;
+use im::HashSet;
+
+/// A change to a HashSet. Sets have no values apart from their elements, so each diff
+/// is keyed on the element itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SetDiff<T> {
+    Insert(T),
+
+    Remove(T),
+
+    Clear,
+
+    Replace,
+}
+
+impl<T> PullableDiff for SetDiff<T>
+where
+    T: Clone + Eq + Hash,
+{
+    type KeyType = T;
+
+    fn get_key(&self) -> Option<&T> {
+        match self {
+            SetDiff::Insert(element) | SetDiff::Remove(element) => Some(element),
+            SetDiff::Replace | SetDiff::Clear => None,
+        }
+    }
+
+    fn get_snapshot_key(&self) -> Option<&T> {
+        // Set events do not need snapshot keys.
+        self.get_key()
+    }
+
+    fn set_key(&mut self, new_key: T) {
+        match self {
+            SetDiff::Insert(element) | SetDiff::Remove(element) => {
+                *element = new_key;
+            }
+            SetDiff::Replace | SetDiff::Clear => {
+                panic!("Cannot set key on non-keyed SetDiff");
+            }
+        }
+    }
+
+    fn set_snapshot_key(&mut self, _new_key: T) {
+        // No op. Set events do not need snapshot keys.
+    }
+
+    fn merge_with_previous(&self, previous: &SetDiff<T>) -> DiffMergeResult<SetDiff<T>> {
+        match (previous, self) {
+            // Insert then Remove, or Remove then Insert, leaves the set as it was.
+            (SetDiff::Insert(_), SetDiff::Remove(_)) | (SetDiff::Remove(_), SetDiff::Insert(_)) => {
+                DiffMergeResult::discard_both()
+            }
+            // Two inserts or two removes of the same element should never happen, since
+            // the second one would not change the set.
+            _ => DiffMergeResult::replace(),
+        }
+    }
+
+    fn full_replace() -> SetDiff<T> {
+        SetDiff::Replace
+    }
+
+    fn is_clear(&self) -> bool {
+        matches!(self, SetDiff::Clear)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HashSetEvent<T>
+where
+    T: Clone + Eq + Hash,
+{
+    pub snapshot: HashSet<T>,
+    pub diffs: Vec<SetDiff<T>>,
+}
+
+impl<T> HashSetEvent<T>
+where
+    T: Clone + Eq + Hash,
+{
+    /// Creates an event directly from a snapshot and a list of diffs. This is mostly
+    /// useful for feeding hand-crafted events into a transformer during testing.
+    pub fn new(snapshot: HashSet<T>, diffs: Vec<SetDiff<T>>) -> HashSetEvent<T> {
+        HashSetEvent { snapshot, diffs }
+    }
+}
+
+impl<T> SnapshottableEvent for HashSetEvent<T>
+where
+    T: Clone + Eq + Hash,
+{
+    type SnapshotType = HashSet<T>;
+
+    fn snapshot(&self) -> Self::SnapshotType {
+        self.snapshot.clone()
+    }
+}
+
+impl<T> MergeableEvent for HashSetEvent<T>
+where
+    T: Clone + Eq + Hash,
+{
+    fn merge(self, next: HashSetEvent<T>) -> HashSetEvent<T> {
+        let mut diffs = self.diffs;
+        diffs.extend(next.diffs);
+        HashSetEvent {
+            snapshot: next.snapshot,
+            diffs: coalesce_diffs(diffs),
+        }
+    }
+}
+
+impl<T> SplittableEvent for HashSetEvent<T>
+where
+    T: Clone + Eq + Hash,
+{
+    fn split(self) -> Vec<HashSetEvent<T>> {
+        if self.diffs.len() <= 1 {
+            return vec![self];
+        }
+        let snapshot = self.snapshot;
+        self.diffs
+            .into_iter()
+            .map(|diff| HashSetEvent {
+                snapshot: snapshot.clone(),
+                diffs: vec![diff],
+            })
+            .collect()
+    }
+}
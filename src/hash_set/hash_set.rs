@@ -0,0 +1,193 @@
+use super::event::{HashSetEvent, SetDiff};
+use crate::structural_signal::pull_source::{
+    PullSourceHost, PullSourceStructuralSignal, StructrualSignalPullSource,
+};
+use im::HashSet;
+use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::hash::Hash;
+use std::iter::Iterator;
+use std::sync::Arc;
+
+/// The internal state of a MutableHashSet or MutableHashSetReader. All
+/// clones (and readonly clones) will share this same instance.
+#[derive(Debug)]
+pub struct MutableHashSetState<T: Clone + Eq + Hash> {
+    hash_set: HashSet<T>,
+    pull_source: StructrualSignalPullSource<SetDiff<T>>,
+}
+
+impl<T: Clone + Eq + Hash> PullSourceHost for MutableHashSetState<T> {
+    type DiffType = SetDiff<T>;
+    type EventType = HashSetEvent<T>;
+
+    fn get_pull_source<'a>(&'a mut self) -> &'a mut StructrualSignalPullSource<Self::DiffType> {
+        &mut self.pull_source
+    }
+
+    fn make_event(&self, diffs: Vec<Self::DiffType>) -> Self::EventType {
+        HashSetEvent {
+            snapshot: self.hash_set.clone(),
+            diffs,
+        }
+    }
+
+    fn make_granular_diffs(&self) -> Vec<Self::DiffType> {
+        self.hash_set
+            .iter()
+            .map(|element| SetDiff::Insert(element.clone()))
+            .collect()
+    }
+}
+
+/// A HashSet that can be observed as it changes over time.
+///
+/// This structure is backed by `im.HashSet` and so requires that elements are
+/// clonable. The backing structure is optimized to clone only when necessary.
+pub struct MutableHashSet<T: Clone + Eq + Hash>(Arc<RwLock<MutableHashSetState<T>>>);
+
+impl<T: Clone + Eq + Hash> Clone for MutableHashSet<T> {
+    fn clone(&self) -> Self {
+        MutableHashSet {
+            0: Arc::new(RwLock::new(MutableHashSetState {
+                hash_set: self.0.read().hash_set.clone(),
+                pull_source: StructrualSignalPullSource::new(),
+            })),
+        }
+    }
+}
+
+impl<T: Clone + Eq + Hash> MutableHashSet<T> {
+    /// Returns a readonly view into the underlying state that can be used
+    /// to read values from the HashSet.
+    #[inline]
+    pub fn read(&self) -> RwLockReadGuard<MutableHashSetState<T>> {
+        self.0.read()
+    }
+
+    /// Returns a writer into the underlying state that can be used to
+    /// modify the HashSet.
+    #[inline]
+    pub fn write(&self) -> RwLockWriteGuard<MutableHashSetState<T>> {
+        self.0.write()
+    }
+
+    pub fn new() -> Self {
+        MutableHashSet {
+            0: Arc::new(RwLock::new(MutableHashSetState {
+                hash_set: HashSet::new(),
+                pull_source: StructrualSignalPullSource::new(),
+            })),
+        }
+    }
+
+    /// Creates a read-only view into this data structure. This Reader object
+    /// can check membership in this set at any time, but cannot modify it.
+    /// Readers can be cloned. Note that Readers can hold a ReadLock which can
+    /// block MutableHashSet::write() calls, so use them with care.
+    #[inline]
+    pub fn reader(&self) -> MutableHashSetReader<T> {
+        MutableHashSetReader { 0: self.0.clone() }
+    }
+
+    /// Creates a signal that tracks the value of this HashSet. Signals can be directly
+    /// used for UI, or can be transformed with SignalHashSetExt.
+    #[inline]
+    pub fn as_signal(&self) -> PullSourceStructuralSignal<MutableHashSetState<T>> {
+        PullSourceStructuralSignal::new(self.0.clone())
+    }
+}
+
+/// A read-only view into a MutableHashSet.
+pub struct MutableHashSetReader<T: Clone + Eq + Hash>(Arc<RwLock<MutableHashSetState<T>>>);
+
+impl<T: Clone + Eq + Hash> Clone for MutableHashSetReader<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        MutableHashSetReader { 0: self.0.clone() }
+    }
+}
+
+impl<T: Clone + Eq + Hash> MutableHashSetReader<T> {
+    /// Returns a readonly view into the underlying state that can be used
+    /// to read values from the HashSet.
+    #[inline]
+    pub fn read(&self) -> RwLockReadGuard<MutableHashSetState<T>> {
+        self.0.read()
+    }
+
+    /// Creates a signal that tracks the value of this HashSet. Signals can be directly
+    /// used for UI, or can be transformed with SignalHashSetExt.
+    #[inline]
+    pub fn as_signal(&self) -> PullSourceStructuralSignal<MutableHashSetState<T>> {
+        PullSourceStructuralSignal::new(self.0.clone())
+    }
+}
+
+impl<T: Clone + Eq + Hash> MutableHashSetState<T> {
+    #[inline]
+    fn add_diff(&mut self, diff: SetDiff<T>) {
+        self.pull_source.add_diff(diff);
+    }
+
+    /// Returns true if the set currently contains the given element.
+    #[inline]
+    pub fn contains(&self, element: &T) -> bool {
+        self.hash_set.contains(element)
+    }
+
+    /// Creates an immutable snapshot of the current state of this HashSet. This
+    /// operation is fairly cheap thanks to the backing Immutable data structure.
+    /// Future changes to this MutableHashSet will not alter the snapshot.
+    #[inline]
+    pub fn snapshot(&self) -> HashSet<T> {
+        self.hash_set.clone()
+    }
+
+    /// Replaces the entire contents of this HashSet with new elements. All existing
+    /// elements will be cleared. Like `clear`, replacing an empty HashSet with no
+    /// elements does nothing.
+    pub fn replace<E>(&mut self, elements: E)
+    where
+        E: Iterator<Item = T>,
+    {
+        let was_empty = self.hash_set.is_empty();
+        self.hash_set = elements.collect();
+        if was_empty && self.hash_set.is_empty() {
+            return;
+        }
+        self.add_diff(SetDiff::Replace);
+    }
+
+    /// Adds an element to this HashSet. Returns false, without emitting a diff, if the
+    /// element was already present.
+    pub fn insert(&mut self, element: T) -> bool {
+        if self.hash_set.contains(&element) {
+            return false;
+        }
+
+        self.hash_set.insert(element.clone());
+        self.add_diff(SetDiff::Insert(element));
+        true
+    }
+
+    /// Removes an element from this HashSet. Returns false, without emitting a diff,
+    /// if the element was not present.
+    pub fn remove(&mut self, element: &T) -> bool {
+        if self.hash_set.remove(element).is_none() {
+            return false;
+        }
+
+        self.add_diff(SetDiff::Remove(element.clone()));
+        true
+    }
+
+    /// Removes every element in this HashSet.
+    pub fn clear(&mut self) {
+        if self.hash_set.is_empty() {
+            return;
+        }
+
+        self.hash_set.clear();
+        self.add_diff(SetDiff::Clear)
+    }
+}
@@ -0,0 +1,7 @@
+mod event;
+mod hash_set;
+mod signal_ext;
+
+pub use event::{HashSetEvent, SetDiff};
+pub use hash_set::{MutableHashSet, MutableHashSetReader};
+pub use signal_ext::{SignalHashSetContainsWatcher, SignalHashSetExt};
@@ -0,0 +1,108 @@
+use super::event::HashSetEvent;
+use crate::structural_signal::pull_source::PullableDiff;
+use crate::StructuralSignal;
+use core::hash::Hash;
+use futures_signals::signal::Signal;
+use pin_project::pin_project;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// A Signal that tracks whether a HashSet contains a particular element. It fires once
+/// initially, and then only when membership of that element actually changes.
+#[pin_project(project = SignalHashSetContainsWatcherProj)]
+pub struct SignalHashSetContainsWatcher<T, S>
+where
+    T: Clone + Eq + Hash,
+    S: StructuralSignal<Item = HashSetEvent<T>>,
+{
+    #[pin]
+    signal: S,
+    element: T,
+    last_contains: Option<bool>,
+}
+
+impl<T, S> Signal for SignalHashSetContainsWatcher<T, S>
+where
+    T: Clone + Eq + Hash,
+    S: StructuralSignal<Item = HashSetEvent<T>>,
+{
+    type Item = bool;
+
+    fn poll_change(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<bool>> {
+        let SignalHashSetContainsWatcherProj {
+            mut signal,
+            element,
+            last_contains,
+        } = self.project();
+
+        loop {
+            match signal.as_mut().poll_change(cx) {
+                Poll::Ready(Some(event)) => {
+                    let affects_element = event.diffs.iter().any(|diff| match diff.get_key() {
+                        Some(key) => *key == *element,
+                        None => true,
+                    });
+                    if !affects_element && last_contains.is_some() {
+                        continue;
+                    }
+
+                    let contains = event.snapshot.contains(&*element);
+                    if *last_contains != Some(contains) {
+                        *last_contains = Some(contains);
+                        return Poll::Ready(Some(contains));
+                    }
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+pub trait SignalHashSetExt: StructuralSignal
+where
+    Self: Sized,
+{
+    type Element: Clone + Eq + Hash;
+    type SelfType: StructuralSignal<Item = HashSetEvent<Self::Element>>;
+
+    /// Returns a Signal that tracks whether a particular element is in the set.
+    ///
+    /// ```
+    /// use futures_executor::block_on;
+    /// use futures_signals::signal::SignalExt;
+    /// use futures_util::StreamExt;
+    /// use signals_im::hash_set::{MutableHashSet, SignalHashSetExt};
+    ///
+    /// let selected = MutableHashSet::<u8>::new();
+    /// let mut is_selected = selected.as_signal().contains_signal(1).to_stream();
+    ///
+    /// selected.write().insert(1);
+    /// assert_eq!(block_on(is_selected.next()), Some(true));
+    ///
+    /// selected.write().insert(2);
+    /// selected.write().remove(&1);
+    /// assert_eq!(block_on(is_selected.next()), Some(false));
+    /// ```
+    fn contains_signal(
+        self,
+        element: Self::Element,
+    ) -> SignalHashSetContainsWatcher<Self::Element, Self::SelfType>;
+}
+
+impl<T, I> SignalHashSetExt for I
+where
+    I: StructuralSignal<Item = HashSetEvent<T>>,
+    T: Clone + Eq + Hash,
+{
+    type Element = T;
+    type SelfType = I;
+
+    fn contains_signal(self, element: T) -> SignalHashSetContainsWatcher<T, Self> {
+        SignalHashSetContainsWatcher {
+            signal: self,
+            element,
+            last_contains: None,
+        }
+    }
+}
@@ -1,7 +1,28 @@
+pub mod btree_map;
 pub mod hash_map;
+pub mod hash_set;
+pub mod testing;
 pub mod vector;
 pub(crate) mod structural_signal;
 pub(crate) mod util;
 
+pub use structural_signal::adapters::{
+    select_all, structural_from_fn, AssertInvariantStructuralSignal, BlockingIter,
+    DebounceStructuralSignal, DedupeByStructuralSignal, DirtySignal, FilterEventsStructuralSignal,
+    FromFnStructuralSignal, LenSignal, MapEventStructuralSignal, MergeReadyStructuralSignal,
+    OneDiffPerEventStructuralSignal, PipeToFuture, SelectAllStructuralSignal, SnapshotFuture,
+    StructuralSignalStream, TakeUntilStructuralSignal, WithInitialStructuralSignal,
+};
+pub use structural_signal::pull_source::{
+    coalesce_diffs, DiffMergeResult, PullSourceStateSnapshot, PullableDiff,
+};
 pub use structural_signal::structural_signal::{StructuralSignal, ChannelStructuralSignal};
-pub use structural_signal::structural_signal_ext::{StructuralSignalExt, SnapshottableEvent};
\ No newline at end of file
+pub use structural_signal::structural_signal_ext::{
+    BroadcastedStructuralSignal, MergeableEvent, SnapshottableEvent, SplittableEvent,
+    StructuralSignalBroadcaster, StructuralSignalBroadcasterState, StructuralSignalExt,
+};
+pub use structural_signal::transformer::{
+    CachedTransformedStructuralSignal, FallibleStructuralSignalTransformer,
+    FallibleTransformedStructuralSignal, StructuralSignalTransformCache,
+    StructuralSignalTransformer, TransformedStructuralSignal,
+};
\ No newline at end of file
@@ -0,0 +1,813 @@
+use super::structural_signal_ext::{MergeableEvent, SnapshottableEvent, SplittableEvent};
+use crate::StructuralSignal;
+use futures_executor::block_on;
+use futures_signals::signal::Signal;
+use futures_util::future::poll_fn;
+use futures_util::stream::Stream;
+use pin_project::pin_project;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+// ** FROM_FN ** //
+
+/// A StructuralSignal that gets its events from a polling function. Created by
+/// `structural_from_fn`.
+#[pin_project(project = FromFnStructuralSignalProj)]
+pub struct FromFnStructuralSignal<F> {
+    poll_fn: F,
+}
+
+/// Creates a StructuralSignal from a function that polls for its next event, much
+/// like `futures::stream::poll_fn`. This is handy for prototypes and tests that need
+/// a StructuralSignal without implementing the trait or setting up a Mutable.
+///
+/// ```
+/// use signals_im::hash_map::{HashMapEvent, MapDiff};
+/// use signals_im::{structural_from_fn, StructuralSignalExt};
+/// use im::hashmap;
+/// use std::task::Poll;
+///
+/// let mut remaining = 1;
+/// let signal = structural_from_fn(move |_| {
+///     if remaining == 0 {
+///         return Poll::Ready(None);
+///     }
+///     remaining -= 1;
+///     Poll::Ready(Some(HashMapEvent::new(hashmap!{1 => 1}, vec![MapDiff::Replace {}])))
+/// });
+/// assert_eq!(signal.snapshot().unwrap(), hashmap!{1 => 1});
+/// ```
+pub fn structural_from_fn<I, F>(poll_fn: F) -> FromFnStructuralSignal<F>
+where
+    I: Clone,
+    F: FnMut(&mut Context) -> Poll<Option<I>>,
+{
+    FromFnStructuralSignal { poll_fn }
+}
+
+impl<I, F> StructuralSignal for FromFnStructuralSignal<F>
+where
+    I: Clone,
+    F: FnMut(&mut Context) -> Poll<Option<I>>,
+{
+    type Item = I;
+
+    #[inline]
+    fn poll_change(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<I>> {
+        let FromFnStructuralSignalProj { poll_fn } = self.project();
+        poll_fn(cx)
+    }
+}
+
+// ** SELECT_ALL ** //
+
+/// A StructuralSignal that interleaves the events of many StructuralSignals. Created
+/// by `select_all`.
+pub struct SelectAllStructuralSignal<S> {
+    signals: Vec<S>,
+    is_done: Vec<bool>,
+    next_index: usize,
+}
+
+/// Merges many StructuralSignals of the same type into one that forwards each of their
+/// events as soon as it is ready, like `futures::stream::select_all`. The inputs are
+/// polled round-robin, starting after whichever input emitted last, so one busy input
+/// cannot starve the others. The output ends once every input has ended.
+///
+/// Only the events are interleaved, not the data structures: every event still carries
+/// the snapshot of the input that emitted it.
+///
+/// ```
+/// use signals_im::hash_map::{MutableHashMap, SignalHashMapExt};
+/// use signals_im::{select_all, StructuralSignalExt};
+/// use im::hashmap;
+///
+/// let shard_1 = MutableHashMap::<u8, u8>::new();
+/// shard_1.write().insert(1, 1);
+/// let shard_2 = MutableHashMap::<u8, u8>::new();
+/// shard_2.write().insert(2, 2);
+///
+/// let events = select_all(vec![shard_1.as_signal(), shard_2.as_signal()]).take_ready_events(2);
+/// assert_eq!(events[0].snapshot, hashmap!{1 => 1});
+/// assert_eq!(events[1].snapshot, hashmap!{2 => 2});
+/// ```
+pub fn select_all<S>(signals: Vec<S>) -> SelectAllStructuralSignal<S>
+where
+    S: StructuralSignal + Unpin,
+{
+    SelectAllStructuralSignal {
+        is_done: vec![false; signals.len()],
+        signals,
+        next_index: 0,
+    }
+}
+
+impl<S> StructuralSignal for SelectAllStructuralSignal<S>
+where
+    S: StructuralSignal + Unpin,
+{
+    type Item = S::Item;
+
+    fn poll_change(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<S::Item>> {
+        let this = self.get_mut();
+        let signal_count = this.signals.len();
+        for offset in 0..signal_count {
+            let index = (this.next_index + offset) % signal_count;
+            if this.is_done[index] {
+                continue;
+            }
+            match Pin::new(&mut this.signals[index]).poll_change(cx) {
+                Poll::Ready(Some(event)) => {
+                    this.next_index = (index + 1) % signal_count;
+                    return Poll::Ready(Some(event));
+                }
+                Poll::Ready(None) => this.is_done[index] = true,
+                Poll::Pending => {}
+            }
+        }
+
+        if this.is_done.iter().all(|is_done| *is_done) {
+            Poll::Ready(None)
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+// ** MAP_EVENT ** //
+
+/// A StructuralSignal that runs every event from its input through a function.
+#[pin_project(project = MapEventStructuralSignalProj)]
+pub struct MapEventStructuralSignal<S, F> {
+    #[pin]
+    signal: S,
+    map_fn: F,
+}
+
+impl<S, F> MapEventStructuralSignal<S, F> {
+    pub(crate) fn new(signal: S, map_fn: F) -> MapEventStructuralSignal<S, F> {
+        MapEventStructuralSignal { signal, map_fn }
+    }
+}
+
+impl<S, F, O> StructuralSignal for MapEventStructuralSignal<S, F>
+where
+    S: StructuralSignal,
+    F: Fn(S::Item) -> O,
+    O: Clone,
+{
+    type Item = O;
+
+    #[inline]
+    fn poll_change(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<O>> {
+        let MapEventStructuralSignalProj { signal, map_fn } = self.project();
+        signal.poll_change(cx).map(|maybe_event| maybe_event.map(map_fn))
+    }
+}
+
+// ** FILTER_EVENTS ** //
+
+/// A StructuralSignal that drops every event from its input that fails a predicate.
+#[pin_project(project = FilterEventsStructuralSignalProj)]
+pub struct FilterEventsStructuralSignal<S, F> {
+    #[pin]
+    signal: S,
+    predicate: F,
+}
+
+impl<S, F> FilterEventsStructuralSignal<S, F> {
+    pub(crate) fn new(signal: S, predicate: F) -> FilterEventsStructuralSignal<S, F> {
+        FilterEventsStructuralSignal { signal, predicate }
+    }
+}
+
+impl<S, F> StructuralSignal for FilterEventsStructuralSignal<S, F>
+where
+    S: StructuralSignal,
+    F: Fn(&S::Item) -> bool,
+{
+    type Item = S::Item;
+
+    #[inline]
+    fn poll_change(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<S::Item>> {
+        let FilterEventsStructuralSignalProj {
+            mut signal,
+            predicate,
+        } = self.project();
+        loop {
+            match signal.as_mut().poll_change(cx) {
+                Poll::Ready(Some(event)) => {
+                    if predicate(&event) {
+                        return Poll::Ready(Some(event));
+                    }
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+// ** ASSERT_INVARIANT ** //
+
+/// A StructuralSignal that runs a checking function on every event before passing
+/// it through unchanged.
+#[pin_project(project = AssertInvariantStructuralSignalProj)]
+pub struct AssertInvariantStructuralSignal<S, F> {
+    #[pin]
+    signal: S,
+    check: F,
+}
+
+impl<S, F> AssertInvariantStructuralSignal<S, F> {
+    pub(crate) fn new(signal: S, check: F) -> AssertInvariantStructuralSignal<S, F> {
+        AssertInvariantStructuralSignal { signal, check }
+    }
+}
+
+impl<S, F> StructuralSignal for AssertInvariantStructuralSignal<S, F>
+where
+    S: StructuralSignal,
+    F: Fn(&S::Item),
+{
+    type Item = S::Item;
+
+    #[inline]
+    fn poll_change(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<S::Item>> {
+        let AssertInvariantStructuralSignalProj { signal, check } = self.project();
+        let poll = signal.poll_change(cx);
+        if let Poll::Ready(Some(event)) = &poll {
+            check(event);
+        }
+        poll
+    }
+}
+
+// ** DEDUPE_BY ** //
+
+/// A StructuralSignal that drops every event considered equal to the last event it emitted.
+#[pin_project(project = DedupeByStructuralSignalProj)]
+pub struct DedupeByStructuralSignal<S, F, I> {
+    #[pin]
+    signal: S,
+    eq_fn: F,
+    last_event: Option<I>,
+}
+
+impl<S, F, I> DedupeByStructuralSignal<S, F, I> {
+    pub(crate) fn new(signal: S, eq_fn: F) -> DedupeByStructuralSignal<S, F, I> {
+        DedupeByStructuralSignal {
+            signal,
+            eq_fn,
+            last_event: None,
+        }
+    }
+}
+
+impl<S, F> StructuralSignal for DedupeByStructuralSignal<S, F, S::Item>
+where
+    S: StructuralSignal,
+    S::Item: Clone,
+    F: Fn(&S::Item, &S::Item) -> bool,
+{
+    type Item = S::Item;
+
+    #[inline]
+    fn poll_change(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<S::Item>> {
+        let DedupeByStructuralSignalProj {
+            mut signal,
+            eq_fn,
+            last_event,
+        } = self.project();
+        loop {
+            match signal.as_mut().poll_change(cx) {
+                Poll::Ready(Some(event)) => {
+                    if let Some(previous) = last_event.as_ref() {
+                        if eq_fn(previous, &event) {
+                            continue;
+                        }
+                    }
+                    *last_event = Some(event.clone());
+                    return Poll::Ready(Some(event));
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+// ** MERGE_READY ** //
+
+/// A StructuralSignal that merges all of the events that are ready on each poll.
+#[pin_project(project = MergeReadyStructuralSignalProj)]
+pub struct MergeReadyStructuralSignal<S> {
+    #[pin]
+    signal: S,
+    is_closed: bool,
+}
+
+impl<S> MergeReadyStructuralSignal<S> {
+    pub(crate) fn new(signal: S) -> MergeReadyStructuralSignal<S> {
+        MergeReadyStructuralSignal {
+            signal,
+            is_closed: false,
+        }
+    }
+}
+
+impl<S> StructuralSignal for MergeReadyStructuralSignal<S>
+where
+    S: StructuralSignal,
+    S::Item: MergeableEvent,
+{
+    type Item = S::Item;
+
+    #[inline]
+    fn poll_change(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<S::Item>> {
+        let MergeReadyStructuralSignalProj {
+            mut signal,
+            is_closed,
+        } = self.project();
+
+        let mut merged: Option<S::Item> = None;
+        while !*is_closed {
+            match signal.as_mut().poll_change(cx) {
+                Poll::Ready(Some(event)) => {
+                    merged = Some(match merged {
+                        Some(previous) => previous.merge(event),
+                        None => event,
+                    });
+                }
+                Poll::Ready(None) => {
+                    *is_closed = true;
+                }
+                Poll::Pending => {
+                    break;
+                }
+            }
+        }
+
+        match merged {
+            Some(event) => Poll::Ready(Some(event)),
+            None if *is_closed => Poll::Ready(None),
+            None => Poll::Pending,
+        }
+    }
+}
+
+// ** DEBOUNCE ** //
+
+/// A StructuralSignal that holds back events until its input has been quiet for a
+/// while, then emits them all merged into one.
+#[pin_project(project = DebounceStructuralSignalProj)]
+pub struct DebounceStructuralSignal<S, F, Fut, I> {
+    #[pin]
+    signal: S,
+    #[pin]
+    sleep: Option<Fut>,
+    make_sleep: F,
+    duration: Duration,
+    pending: Option<I>,
+    is_closed: bool,
+}
+
+impl<S, F, Fut, I> DebounceStructuralSignal<S, F, Fut, I> {
+    pub(crate) fn new(
+        signal: S,
+        duration: Duration,
+        make_sleep: F,
+    ) -> DebounceStructuralSignal<S, F, Fut, I> {
+        DebounceStructuralSignal {
+            signal,
+            sleep: None,
+            make_sleep,
+            duration,
+            pending: None,
+            is_closed: false,
+        }
+    }
+}
+
+impl<S, F, Fut> StructuralSignal for DebounceStructuralSignal<S, F, Fut, S::Item>
+where
+    S: StructuralSignal,
+    S::Item: MergeableEvent,
+    F: FnMut(Duration) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    type Item = S::Item;
+
+    fn poll_change(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<S::Item>> {
+        let DebounceStructuralSignalProj {
+            mut signal,
+            mut sleep,
+            make_sleep,
+            duration,
+            pending,
+            is_closed,
+        } = self.project();
+
+        while !*is_closed {
+            match signal.as_mut().poll_change(cx) {
+                Poll::Ready(Some(event)) => {
+                    *pending = Some(match pending.take() {
+                        Some(previous) => previous.merge(event),
+                        None => event,
+                    });
+                    // Every new event restarts the quiet period.
+                    sleep.set(Some(make_sleep(*duration)));
+                }
+                Poll::Ready(None) => {
+                    *is_closed = true;
+                }
+                Poll::Pending => {
+                    break;
+                }
+            }
+        }
+
+        if pending.is_none() {
+            return if *is_closed {
+                Poll::Ready(None)
+            } else {
+                Poll::Pending
+            };
+        }
+
+        // Nothing else can arrive once the input ends, so there is no need to wait.
+        if !*is_closed {
+            if let Some(timer) = sleep.as_mut().as_pin_mut() {
+                if timer.poll(cx).is_pending() {
+                    return Poll::Pending;
+                }
+            }
+        }
+        sleep.set(None);
+        Poll::Ready(pending.take())
+    }
+}
+
+// ** ONE_DIFF_PER_EVENT ** //
+
+/// A StructuralSignal that splits up its input events so that each carries one diff.
+#[pin_project(project = OneDiffPerEventStructuralSignalProj)]
+pub struct OneDiffPerEventStructuralSignal<S, I> {
+    #[pin]
+    signal: S,
+    pending: VecDeque<I>,
+}
+
+impl<S, I> OneDiffPerEventStructuralSignal<S, I> {
+    pub(crate) fn new(signal: S) -> OneDiffPerEventStructuralSignal<S, I> {
+        OneDiffPerEventStructuralSignal {
+            signal,
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+impl<S> StructuralSignal for OneDiffPerEventStructuralSignal<S, S::Item>
+where
+    S: StructuralSignal,
+    S::Item: SplittableEvent,
+{
+    type Item = S::Item;
+
+    #[inline]
+    fn poll_change(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<S::Item>> {
+        let OneDiffPerEventStructuralSignalProj {
+            mut signal,
+            pending,
+        } = self.project();
+        loop {
+            if let Some(event) = pending.pop_front() {
+                return Poll::Ready(Some(event));
+            }
+            match signal.as_mut().poll_change(cx) {
+                Poll::Ready(Some(event)) => pending.extend(event.split()),
+                result => return result,
+            }
+        }
+    }
+}
+
+// ** WITH_INITIAL ** //
+
+/// A StructuralSignal that emits a given event before any events from its input.
+#[pin_project(project = WithInitialStructuralSignalProj)]
+pub struct WithInitialStructuralSignal<S, I> {
+    #[pin]
+    signal: S,
+    initial: Option<I>,
+}
+
+impl<S, I> WithInitialStructuralSignal<S, I> {
+    pub(crate) fn new(signal: S, initial: I) -> WithInitialStructuralSignal<S, I> {
+        WithInitialStructuralSignal {
+            signal,
+            initial: Some(initial),
+        }
+    }
+}
+
+impl<S> StructuralSignal for WithInitialStructuralSignal<S, S::Item>
+where
+    S: StructuralSignal,
+{
+    type Item = S::Item;
+
+    #[inline]
+    fn poll_change(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<S::Item>> {
+        let WithInitialStructuralSignalProj { signal, initial } = self.project();
+        match initial.take() {
+            Some(event) => Poll::Ready(Some(event)),
+            None => signal.poll_change(cx),
+        }
+    }
+}
+
+// ** TAKE_UNTIL ** //
+
+/// A StructuralSignal that forwards events from its input until a trigger Future resolves.
+#[pin_project(project = TakeUntilStructuralSignalProj)]
+pub struct TakeUntilStructuralSignal<S, F> {
+    #[pin]
+    signal: S,
+    #[pin]
+    trigger: F,
+    is_triggered: bool,
+}
+
+impl<S, F> TakeUntilStructuralSignal<S, F> {
+    pub(crate) fn new(signal: S, trigger: F) -> TakeUntilStructuralSignal<S, F> {
+        TakeUntilStructuralSignal {
+            signal,
+            trigger,
+            is_triggered: false,
+        }
+    }
+}
+
+impl<S, F> StructuralSignal for TakeUntilStructuralSignal<S, F>
+where
+    S: StructuralSignal,
+    F: Future<Output = ()>,
+{
+    type Item = S::Item;
+
+    #[inline]
+    fn poll_change(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<S::Item>> {
+        let TakeUntilStructuralSignalProj {
+            signal,
+            trigger,
+            is_triggered,
+        } = self.project();
+
+        if !*is_triggered && trigger.poll(cx).is_ready() {
+            *is_triggered = true;
+        }
+        if *is_triggered {
+            return Poll::Ready(None);
+        }
+        signal.poll_change(cx)
+    }
+}
+
+// ** PIPE_TO ** //
+
+/// A Future that drives a StructuralSignal to completion, passing every event to a sink.
+#[pin_project(project = PipeToFutureProj)]
+#[must_use = "Futures do nothing unless polled"]
+pub struct PipeToFuture<S, F> {
+    #[pin]
+    signal: S,
+    sink: F,
+}
+
+impl<S, F> PipeToFuture<S, F> {
+    pub(crate) fn new(signal: S, sink: F) -> PipeToFuture<S, F> {
+        PipeToFuture { signal, sink }
+    }
+}
+
+impl<S, F> Future for PipeToFuture<S, F>
+where
+    S: StructuralSignal,
+    F: FnMut(S::Item),
+{
+    type Output = ();
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+        let PipeToFutureProj { mut signal, sink } = self.project();
+        loop {
+            match signal.as_mut().poll_change(cx) {
+                Poll::Ready(Some(event)) => sink(event),
+                Poll::Ready(None) => return Poll::Ready(()),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+// ** SNAPSHOT_ASYNC ** //
+
+/// A Future that resolves to the latest snapshot of a StructuralSignal. Created by
+/// `StructuralSignalExt::snapshot_async`.
+#[pin_project(project = SnapshotFutureProj)]
+#[must_use = "Futures do nothing unless polled"]
+pub struct SnapshotFuture<S> {
+    #[pin]
+    signal: S,
+}
+
+impl<S> SnapshotFuture<S> {
+    pub(crate) fn new(signal: S) -> SnapshotFuture<S> {
+        SnapshotFuture { signal }
+    }
+}
+
+impl<S> Future for SnapshotFuture<S>
+where
+    S: StructuralSignal,
+    S::Item: SnapshottableEvent,
+{
+    type Output = Option<<S::Item as SnapshottableEvent>::SnapshotType>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let SnapshotFutureProj { mut signal } = self.project();
+        let mut latest_event = None;
+        loop {
+            match signal.as_mut().poll_change(cx) {
+                Poll::Ready(Some(event)) => latest_event = Some(event),
+                Poll::Ready(None) => {
+                    return Poll::Ready(latest_event.map(|event| event.snapshot()));
+                }
+                Poll::Pending => {
+                    return match latest_event {
+                        Some(event) => Poll::Ready(Some(event.snapshot())),
+                        None => Poll::Pending,
+                    };
+                }
+            }
+        }
+    }
+}
+
+// ** BLOCKING_ITER ** //
+
+/// An Iterator that blocks the current thread until the wrapped StructuralSignal
+/// produces its next event. Created by `StructuralSignalExt::into_blocking_iter`.
+#[must_use = "Iterators do nothing unless iterated"]
+pub struct BlockingIter<S> {
+    signal: Pin<Box<S>>,
+    ended: bool,
+}
+
+impl<S> BlockingIter<S> {
+    pub(crate) fn new(signal: S) -> BlockingIter<S> {
+        BlockingIter {
+            signal: Box::pin(signal),
+            ended: false,
+        }
+    }
+}
+
+impl<S> Iterator for BlockingIter<S>
+where
+    S: StructuralSignal,
+{
+    type Item = S::Item;
+
+    fn next(&mut self) -> Option<S::Item> {
+        if self.ended {
+            return None;
+        }
+        let signal = &mut self.signal;
+        let next = block_on(poll_fn(|cx| signal.as_mut().poll_change(cx)));
+        self.ended = next.is_none();
+        next
+    }
+}
+
+// ** TO_STREAM ** //
+
+/// A Stream of the events of a StructuralSignal. Created by
+/// `StructuralSignalExt::to_stream`.
+#[pin_project(project = StructuralSignalStreamProj)]
+#[must_use = "Streams do nothing unless polled"]
+pub struct StructuralSignalStream<S> {
+    #[pin]
+    signal: S,
+}
+
+impl<S> StructuralSignalStream<S> {
+    pub(crate) fn new(signal: S) -> StructuralSignalStream<S> {
+        StructuralSignalStream { signal }
+    }
+}
+
+impl<S> Stream for StructuralSignalStream<S>
+where
+    S: StructuralSignal,
+{
+    type Item = S::Item;
+
+    #[inline]
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<S::Item>> {
+        let StructuralSignalStreamProj { signal } = self.project();
+        signal.poll_change(cx)
+    }
+}
+
+// ** DIRTY_SIGNAL ** //
+
+/// A Signal that fires once for every event of a StructuralSignal, without carrying any
+/// of its data. Since pull sources coalesce all of the changes made between polls into
+/// a single event, this fires at most once per batch of changes.
+#[pin_project(project = DirtySignalProj)]
+#[must_use = "Signals do nothing unless polled"]
+pub struct DirtySignal<S> {
+    #[pin]
+    signal: S,
+}
+
+impl<S> DirtySignal<S> {
+    pub(crate) fn new(signal: S) -> DirtySignal<S> {
+        DirtySignal { signal }
+    }
+}
+
+impl<S> Signal for DirtySignal<S>
+where
+    S: StructuralSignal,
+{
+    type Item = ();
+
+    #[inline]
+    fn poll_change(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<()>> {
+        let DirtySignalProj { signal } = self.project();
+        signal.poll_change(cx).map(|maybe_event| maybe_event.map(|_| ()))
+    }
+}
+
+// ** LEN_SIGNAL ** //
+
+/// A Signal of the length of a data structure, which only fires when the length
+/// changes. Events that leave the length alone, like updates to existing values,
+/// or removes and inserts that cancel out, are skipped.
+#[pin_project(project = LenSignalProj)]
+#[must_use = "Signals do nothing unless polled"]
+pub struct LenSignal<S>
+where
+    S: StructuralSignal,
+{
+    #[pin]
+    signal: S,
+    len_fn: fn(&S::Item) -> usize,
+    last_len: Option<usize>,
+}
+
+impl<S> LenSignal<S>
+where
+    S: StructuralSignal,
+{
+    pub(crate) fn new(signal: S, len_fn: fn(&S::Item) -> usize) -> LenSignal<S> {
+        LenSignal {
+            signal,
+            len_fn,
+            last_len: None,
+        }
+    }
+}
+
+impl<S> Signal for LenSignal<S>
+where
+    S: StructuralSignal,
+{
+    type Item = usize;
+
+    #[inline]
+    fn poll_change(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<usize>> {
+        let LenSignalProj {
+            mut signal,
+            len_fn,
+            last_len,
+        } = self.project();
+        loop {
+            match signal.as_mut().poll_change(cx) {
+                Poll::Ready(Some(event)) => {
+                    let len = len_fn(&event);
+                    if *last_len != Some(len) {
+                        *last_len = Some(len);
+                        return Poll::Ready(Some(len));
+                    }
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
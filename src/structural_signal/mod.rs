@@ -1,3 +1,4 @@
+pub(crate) mod adapters;
 pub(crate) mod pull_source;
 pub(crate) mod structural_signal;
 pub(crate) mod structural_signal_ext;
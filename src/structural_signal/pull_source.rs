@@ -7,7 +7,7 @@ use std::collections::BTreeMap;
 use std::hash::Hash;
 use std::pin::Pin;
 use std::sync::Arc;
-use std::task::{Context, Poll};
+use std::task::{Context, Poll, Waker};
 
 pub(crate) type DiffNumber = usize;
 pub(crate) type SignalId = usize;
@@ -121,6 +121,39 @@ where
     fn set_snapshot_key(&mut self, new_snapshot_key: Self::KeyType);
     fn merge_with_previous(&self, previous: &Self) -> DiffMergeResult<Self>;
     fn full_replace() -> Self;
+
+    /// Returns true if this diff removes every key from the structure. Unlike other
+    /// global diffs, a clear can be forwarded as-is along with any diffs after it.
+    fn is_clear(&self) -> bool;
+
+    /// Runs the snapshot keys of this diff through an updater. Diffs that track more
+    /// than one snapshot key should override this to update all of them.
+    fn update_snapshot_keys<F>(&mut self, updater: F)
+    where
+        F: Fn(&Self::KeyType) -> Self::KeyType,
+    {
+        if let Some(snapshot_key) = self.get_snapshot_key() {
+            let updated = updater(snapshot_key);
+            self.set_snapshot_key(updated);
+        }
+    }
+
+    /// Runs the keys of this diff through a reindexer, which is given each key along
+    /// with its snapshot key. Diffs that track more than one key should override this
+    /// to reindex all of them.
+    fn reindex<F>(&mut self, reindexer: F)
+    where
+        F: Fn(&Self::KeyType, &Self::KeyType) -> Self::KeyType,
+    {
+        if let Some(key) = self.get_key() {
+            if let Some(snapshot_key) = self.get_snapshot_key() {
+                let updated = reindexer(key, snapshot_key);
+                self.set_key(updated);
+            } else {
+                panic!("Diff had a key but not a snapshot key");
+            }
+        }
+    }
 }
 
 pub trait PullSourceHost
@@ -132,6 +165,10 @@ where
 
     fn get_pull_source<'a>(&'a mut self) -> &'a mut StructrualSignalPullSource<Self::DiffType>;
     fn make_event(&self, diffs: Vec<Self::DiffType>) -> Self::EventType;
+
+    /// Describes the current contents of the host as one insert diff per item, for
+    /// signals that would rather not start with a full replace.
+    fn make_granular_diffs(&self) -> Vec<Self::DiffType>;
 }
 
 /// A PullSource is a more efficient way of broadcasting StructrualSignals than channel broadcasting
@@ -144,6 +181,11 @@ pub struct StructrualSignalPullSource<DiffType: PullableDiff> {
     diffs_per_key: HashMap<DiffType::KeyType, DiffNumber>,
     next_diff_index: DiffNumber,
     next_signal_id: SignalId,
+    // How many `batch` calls are currently open on the host. Nothing can be pulled
+    // until all of them have finished.
+    batch_depth: usize,
+    // Signals that found nothing to pull, to be woken by the next diff.
+    wakers: BTreeMap<SignalId, Waker>,
 }
 
 impl<DiffType: PullableDiff> StructrualSignalPullSource<DiffType> {
@@ -154,12 +196,15 @@ impl<DiffType: PullableDiff> StructrualSignalPullSource<DiffType> {
             diffs_per_key: HashMap::new(),
             next_diff_index: 1,
             next_signal_id: 1,
+            batch_depth: 0,
+            wakers: BTreeMap::new(),
         }
     }
 }
 
 impl<DiffType: PullableDiff> StructrualSignalPullSource<DiffType> {
     pub fn add_diff(&mut self, mut diff: DiffType) {
+        self.wake_signals();
         if !self.has_listening_signal() {
             return;
         }
@@ -219,6 +264,11 @@ impl<DiffType: PullableDiff> StructrualSignalPullSource<DiffType> {
     }
 
     pub fn pull_signal(&mut self, signal_id: SignalId) -> Vec<DiffType> {
+        // A batch in progress may have left the host half-way through a change.
+        if self.batch_depth > 0 {
+            return vec![];
+        }
+
         let current_diff_number = self.next_diff_index - 1;
         let maybe_last_diff_number = self
             .signal_last_diff_numbers
@@ -239,7 +289,7 @@ impl<DiffType: PullableDiff> StructrualSignalPullSource<DiffType> {
         };
 
         let start_at = maybe_last_diff_number.unwrap() + 1;
-        let diffs_in_range: Vec<DiffType> = self
+        let mut diffs_in_range: Vec<DiffType> = self
             .diffs
             .range(start_at..)
             .map(|(_k, v)| v.clone())
@@ -247,12 +297,15 @@ impl<DiffType: PullableDiff> StructrualSignalPullSource<DiffType> {
 
         // If any of the diffs affect every key (think Replace or Clear) then the
         // end result of this diff will be an entirely different data set from the
-        // original, therefore a full replace is appropriate.
-        let has_global_diff = diffs_in_range
+        // original, therefore a full replace is appropriate. Clears are the exception,
+        // since the diffs that follow them fully describe the new data set.
+        let maybe_global_diff_index = diffs_in_range
             .iter()
-            .find(|diff| diff.get_key().is_none())
-            .is_some();
-        if has_global_diff {
+            .rposition(|diff| diff.get_key().is_none());
+        if let Some(global_diff_index) = maybe_global_diff_index {
+            if diffs_in_range[global_diff_index].is_clear() {
+                return diffs_in_range.split_off(global_diff_index);
+            }
             return vec![DiffType::full_replace()];
         }
 
@@ -265,16 +318,17 @@ impl<DiffType: PullableDiff> StructrualSignalPullSource<DiffType> {
     {
         let mut tmp_diffs_per_key = hashmap! {};
         std::mem::swap(&mut self.diffs_per_key, &mut tmp_diffs_per_key);
-        self.diffs_per_key = tmp_diffs_per_key
-            .into_iter()
-            .map(|(k, v)| (updater(&k), v))
-            .collect();
+        for (key, diff_number) in tmp_diffs_per_key.into_iter() {
+            let updated_key = updater(&key);
+            // Two keys can only land on the same slot when the one that stayed put was
+            // removed, in which case the key that moved into its place wins.
+            if updated_key != key || !self.diffs_per_key.contains_key(&updated_key) {
+                self.diffs_per_key.insert(updated_key, diff_number);
+            }
+        }
 
         for (_index, diff) in self.diffs.iter_mut() {
-            if let Some(existing_key) = diff.get_snapshot_key() {
-                let updated = updater(existing_key);
-                diff.set_snapshot_key(updated);
-            }
+            diff.update_snapshot_keys(&updater);
         }
     }
 
@@ -286,17 +340,46 @@ impl<DiffType: PullableDiff> StructrualSignalPullSource<DiffType> {
     {
         for i in start_diff..self.next_diff_index {
             if let Some(diff) = self.diffs.get_mut(&i) {
-                if let Some(key) = diff.get_key() {
-                    if let Some(snapshot_key) = diff.get_snapshot_key() {
-                        diff.set_key(updater(key, snapshot_key));
-                    } else {
-                        panic!("Diff had a key but not a snapshot key");
-                    }
-                }
+                diff.reindex(&updater);
+            }
+        }
+    }
+
+    /// Holds back every diff from pulls until the matching `end_batch`. Batches can be
+    /// nested, in which case only ending the outermost one releases the diffs.
+    pub(crate) fn begin_batch(&mut self) {
+        self.batch_depth += 1;
+    }
+
+    pub(crate) fn end_batch(&mut self) {
+        self.batch_depth -= 1;
+        if self.batch_depth == 0 {
+            self.wake_signals();
+        }
+    }
+
+    /// Returns true while a batch is in progress on the host.
+    pub fn is_in_batch(&self) -> bool {
+        self.batch_depth > 0
+    }
+
+    /// Stores a waker to wake once there may be something new for a signal to pull.
+    /// Each signal keeps only its most recent waker.
+    pub(crate) fn register_waker(&mut self, signal_id: SignalId, waker: &Waker) {
+        match self.wakers.get(&signal_id) {
+            Some(existing) if existing.will_wake(waker) => {}
+            _ => {
+                self.wakers.insert(signal_id, waker.clone());
             }
         }
     }
 
+    fn wake_signals(&mut self) {
+        for (_signal_id, waker) in std::mem::take(&mut self.wakers) {
+            waker.wake();
+        }
+    }
+
     pub fn get_next_signal_id(&mut self) -> SignalId {
         let next_id = self.next_signal_id;
         self.next_signal_id += 1;
@@ -306,16 +389,108 @@ impl<DiffType: PullableDiff> StructrualSignalPullSource<DiffType> {
     pub fn has_listening_signal(&self) -> bool {
         self.signal_last_diff_numbers.len() > 0
     }
+
+    /// The number of signals that have pulled from this source at least once.
+    pub fn listening_signal_count(&self) -> usize {
+        self.signal_last_diff_numbers.len()
+    }
+
+    /// The number of the most recently added diff.
+    pub(crate) fn last_diff_number(&self) -> DiffNumber {
+        self.next_diff_index - 1
+    }
+
+    /// Whether every listening signal has already pulled the given diff.
+    pub(crate) fn is_pulled_by_all(&self, diff_number: DiffNumber) -> bool {
+        self.signal_last_diff_numbers
+            .values()
+            .all(|last_diff_number| *last_diff_number >= diff_number)
+    }
+
+    /// The number of diffs retained since the last global diff, whether or not every
+    /// signal has pulled them.
+    pub fn pending_diff_count(&self) -> usize {
+        self.diffs.len()
+    }
+
+    /// The number of keys whose most recent diff can still be merged with a new diff.
+    pub fn keyed_diff_count(&self) -> usize {
+        self.diffs_per_key.len()
+    }
+
+    /// Captures the internal state of this source in a deterministic order, so that
+    /// runs can be compared while fuzzing or debugging.
+    pub fn state_snapshot(&self) -> PullSourceStateSnapshot<DiffType> {
+        let mut keyed_diff_numbers: Vec<DiffNumber> =
+            self.diffs_per_key.values().cloned().collect();
+        keyed_diff_numbers.sort();
+        PullSourceStateSnapshot {
+            diffs: self
+                .diffs
+                .iter()
+                .map(|(number, diff)| (*number, diff.clone()))
+                .collect(),
+            keyed_diff_numbers,
+            signal_positions: self
+                .signal_last_diff_numbers
+                .iter()
+                .map(|(id, number)| (*id, *number))
+                .collect(),
+        }
+    }
+}
+
+/// The internal state of a PullSource at one point in time. Pending diffs are held in
+/// diff number order, so this never depends on the iteration order of a HashMap, and
+/// keys are identified by the number of their most recent diff so they need not be `Ord`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PullSourceStateSnapshot<DiffType: PullableDiff> {
+    /// Every pending diff, along with its diff number.
+    pub diffs: Vec<(usize, DiffType)>,
+    /// The numbers of the diffs that can still be merged with later diffs on their key.
+    pub keyed_diff_numbers: Vec<usize>,
+    /// The number of the last diff pulled by each signal, by signal id.
+    pub signal_positions: Vec<(usize, usize)>,
+}
+
+/// Runs a list of diffs through the same merge logic a pull source applies between two
+/// polls, producing the smallest equivalent list. This is useful for events that come
+/// from an unbatched source, such as a `ChannelStructuralSignal`.
+///
+/// The keys of the diffs are never shifted, so this is only correct for diff types
+/// whose keys are stable, like `MapDiff`. `VectorDiff` indices move as items are
+/// inserted and removed, which only a `MutableVector` keeps track of.
+pub fn coalesce_diffs<DiffType: PullableDiff>(diffs: Vec<DiffType>) -> Vec<DiffType> {
+    let mut pull_source = StructrualSignalPullSource::new();
+    let signal_id = pull_source.get_next_signal_id();
+    pull_source.pull_signal(signal_id);
+    for diff in diffs {
+        pull_source.add_diff(diff);
+    }
+    pull_source.pull_signal(signal_id)
 }
 
 /// A Signal derived from a PullSource.
+///
+/// Creating this Signal is cheap and does not register it with the PullSource. It is
+/// only assigned an id and counted as listening once it is first polled. That first
+/// poll always returns the full current contents, and only diffs made after it are
+/// tracked and coalesced for this Signal. When there is nothing new to pull, the Signal
+/// returns Pending and is woken by the next change to its host.
+///
+/// This Signal is `Send` whenever its host is `Send + Sync`, which is the case for
+/// every Mutable structure whose keys and values are `Send + Sync`, so it can be
+/// moved into a spawned task.
 #[pin_project(project = PullSourceStructuralSignalProj)]
 pub struct PullSourceStructuralSignal<H>
 where
     H: PullSourceHost,
 {
-    id: SignalId,
+    // Assigned on the first poll, so that signals that are never polled cost nothing.
+    id: Option<SignalId>,
     pull_source_host: Arc<RwLock<H>>,
+    // When set, the initial full replace is swapped out for granular insert diffs.
+    granular_initial: bool,
 }
 
 impl<H> PullSourceStructuralSignal<H>
@@ -323,13 +498,32 @@ where
     H: PullSourceHost,
 {
     pub(crate) fn new(pull_source_host: Arc<RwLock<H>>) -> PullSourceStructuralSignal<H> {
-        let id = pull_source_host
-            .write()
-            .get_pull_source()
-            .get_next_signal_id();
         PullSourceStructuralSignal {
-            id,
+            id: None,
             pull_source_host,
+            granular_initial: false,
+        }
+    }
+
+    pub(crate) fn new_granular(pull_source_host: Arc<RwLock<H>>) -> PullSourceStructuralSignal<H> {
+        let mut signal = PullSourceStructuralSignal::new(pull_source_host);
+        signal.granular_initial = true;
+        signal
+    }
+}
+
+/// Cloning creates an independent subscription to the same host. The clone does not
+/// share a position with the original, and starts with a full replace of the current
+/// contents when it is first polled.
+impl<H> Clone for PullSourceStructuralSignal<H>
+where
+    H: PullSourceHost,
+{
+    fn clone(&self) -> Self {
+        PullSourceStructuralSignal {
+            id: None,
+            pull_source_host: self.pull_source_host.clone(),
+            granular_initial: self.granular_initial,
         }
     }
 }
@@ -340,16 +534,32 @@ where
 {
     type Item = H::EventType;
 
-    fn poll_change(self: Pin<&mut Self>, _: &mut Context) -> Poll<Option<H::EventType>> {
-        let diffs = self
-            .pull_source_host
-            .write()
-            .get_pull_source()
-            .pull_signal(self.id);
+    fn poll_change(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<H::EventType>> {
+        let PullSourceStructuralSignalProj {
+            id,
+            pull_source_host,
+            granular_initial,
+        } = self.project();
+        // The event is built under the same lock as the pull, so that a write in between
+        // can never show up in both this event and the next one.
+        let mut host = pull_source_host.write();
+        let pull_source = host.get_pull_source();
+        let signal_id = *id.get_or_insert_with(|| pull_source.get_next_signal_id());
+        let diffs = pull_source.pull_signal(signal_id);
         if diffs.is_empty() {
-            Poll::Pending
-        } else {
-            Poll::Ready(Some(self.pull_source_host.read().make_event(diffs)))
+            pull_source.register_waker(signal_id, cx.waker());
+            return Poll::Pending;
+        }
+
+        if *granular_initial {
+            // The first pull is always a full replace, which describes the current contents.
+            // An empty host has no items to insert, so it keeps that replace instead.
+            *granular_initial = false;
+            let granular_diffs = host.make_granular_diffs();
+            if !granular_diffs.is_empty() {
+                return Poll::Ready(Some(host.make_event(granular_diffs)));
+            }
         }
+        Poll::Ready(Some(host.make_event(diffs)))
     }
 }
@@ -1,4 +1,10 @@
-use crate::util::{close_senders, notify_senders};
+use super::adapters::{
+    AssertInvariantStructuralSignal, BlockingIter, DebounceStructuralSignal,
+    DedupeByStructuralSignal, DirtySignal, FilterEventsStructuralSignal, MapEventStructuralSignal,
+    MergeReadyStructuralSignal, OneDiffPerEventStructuralSignal, PipeToFuture, SnapshotFuture,
+    StructuralSignalStream, TakeUntilStructuralSignal, WithInitialStructuralSignal,
+};
+use crate::util::{close_senders, has_live_senders, live_sender_count, notify_senders};
 use crate::StructuralSignal;
 use futures::channel::mpsc;
 use futures_executor::block_on;
@@ -7,9 +13,12 @@ use futures_util::stream::StreamExt;
 use parking_lot::RwLock;
 use pin_project::pin_project;
 use pin_utils::pin_mut;
+use std::cell::RefCell;
+use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
+use std::time::Duration;
 
 pub trait SnapshottableEvent {
     type SnapshotType;
@@ -17,6 +26,17 @@ pub trait SnapshottableEvent {
     fn snapshot(&self) -> Self::SnapshotType;
 }
 
+/// An event that can absorb the event that came after it, producing a single event
+/// with the later snapshot whose diffs describe both changes.
+pub trait MergeableEvent {
+    fn merge(self, next: Self) -> Self;
+}
+
+/// An event that can be split into one event per diff, each with the same snapshot.
+pub trait SplittableEvent: Sized {
+    fn split(self) -> Vec<Self>;
+}
+
 pub trait StructuralSignalExt: StructuralSignal
 where
     Self: Sized,
@@ -41,6 +61,10 @@ where
     /// input_map.write().insert(2, 2);
     /// assert_eq!(broadcaster.get_signal().snapshot().unwrap(), hashmap!{1 => 2, 2 => 4});
     /// ```
+    ///
+    /// Cycles, where this Signal depends on one of the broadcasted Signals, are not
+    /// supported. They will not hang, but the broadcasted Signal that closes the cycle
+    /// only receives events pulled in by the outer poll.
     fn broadcast(self) -> StructuralSignalBroadcaster<Self::Item, Self>
     where
         Self: Unpin,
@@ -48,6 +72,9 @@ where
 
     /// Retrieves the a clone of the current value of the Signal as a standard data structure.
     ///
+    /// This blocks the current thread while polling, so it must not be called from
+    /// inside an async executor. Poll the Signal directly from async code instead.
+    ///
     /// ```
     /// use signals_im::hash_map::{MutableHashMap, SignalHashMapExt};
     /// use signals_im::StructuralSignalExt;
@@ -63,6 +90,361 @@ where
     fn snapshot(self) -> Option<<Self::Item as SnapshottableEvent>::SnapshotType>
     where
         Self::Item: SnapshottableEvent;
+
+    /// Like `snapshot`, but returns a Future instead of blocking the current thread, so
+    /// it can be used from inside an async executor. The Future waits for the first
+    /// event, then drains every event that is ready and resolves to the latest
+    /// snapshot. It resolves to None if the Signal ends without emitting anything.
+    ///
+    /// ```
+    /// use futures_executor::block_on;
+    /// use signals_im::hash_map::MutableHashMap;
+    /// use signals_im::StructuralSignalExt;
+    /// use im::hashmap;
+    ///
+    /// let input_map = MutableHashMap::<u8, u8>::new();
+    /// input_map.write().insert(1, 1);
+    ///
+    /// let current_val = block_on(async { input_map.as_signal().snapshot_async().await });
+    /// assert_eq!(current_val, Some(hashmap!{1 => 1}));
+    /// ```
+    fn snapshot_async(self) -> SnapshotFuture<Self>
+    where
+        Self::Item: SnapshottableEvent;
+
+    /// Polls the Signal for up to `n` events without blocking, returning the events
+    /// that were ready. It stops at the first poll that is not ready, so it can return
+    /// fewer than `n` events even if more arrive later. Stopping there also makes it
+    /// safe to use on Signals that never end.
+    ///
+    /// ```
+    /// use signals_im::hash_map::MutableHashMap;
+    /// use signals_im::StructuralSignalExt;
+    ///
+    /// let input_map = MutableHashMap::<u8, u8>::new();
+    /// let mut signal = input_map.as_signal();
+    ///
+    /// input_map.write().insert(1, 1);
+    /// assert_eq!((&mut signal).take_ready_events(2).len(), 1);
+    ///
+    /// input_map.write().insert(2, 2);
+    /// assert_eq!((&mut signal).take_ready_events(2)[0].diffs.len(), 1);
+    /// ```
+    fn take_ready_events(self, n: usize) -> Vec<Self::Item>;
+
+    /// Drives the Signal until it has produced `n` events or ended, returning the
+    /// events it collected. Unlike `collect_events`, the Signal does not have to end,
+    /// but this blocks until `n` events arrive, so use `take_ready_events` to only
+    /// take the events that are already available.
+    ///
+    /// ```
+    /// use signals_im::hash_map::MutableHashMap;
+    /// use signals_im::StructuralSignalExt;
+    /// use std::thread;
+    ///
+    /// let input_map = MutableHashMap::<u8, u8>::new();
+    /// input_map.write().insert(1, 1);
+    /// let signal = input_map.as_signal();
+    ///
+    /// let producer = thread::spawn(move || input_map.write().insert(2, 2));
+    /// assert_eq!(signal.take_events(2).len(), 2);
+    /// producer.join().unwrap();
+    /// ```
+    fn take_events(self, n: usize) -> Vec<Self::Item>;
+
+    /// Folds every event that is currently ready into an accumulator, starting from
+    /// `init`. This generalizes `snapshot` to Signals whose events are not
+    /// `SnapshottableEvent`, such as the output of `map_event`. Stops as soon as the
+    /// Signal ends or has no more events ready, so it will not wait for future events.
+    ///
+    /// Like `snapshot`, this blocks the current thread while polling.
+    ///
+    /// ```
+    /// use signals_im::hash_map::MutableHashMap;
+    /// use signals_im::StructuralSignalExt;
+    ///
+    /// let input_map = MutableHashMap::<u8, u8>::new();
+    /// input_map.write().insert(1, 1);
+    ///
+    /// let sizes = input_map.as_signal().map_event(|event| event.snapshot.len());
+    /// assert_eq!(sizes.fold_to(0, |total, size| *total += size), 1);
+    /// ```
+    fn fold_to<B, F>(self, init: B, fold_fn: F) -> B
+    where
+        F: FnMut(&mut B, Self::Item);
+
+    /// Runs every event emitted by this Signal through a function, which is useful
+    /// for wrapping events in another type. Unlike `map_values`, this does not build
+    /// any intermediate data structure.
+    ///
+    /// ```
+    /// use signals_im::hash_map::MutableHashMap;
+    /// use signals_im::StructuralSignalExt;
+    ///
+    /// let input_map = MutableHashMap::<u8, u8>::new();
+    /// input_map.write().insert(1, 1);
+    ///
+    /// let sizes = input_map.as_signal().map_event(|event| event.snapshot.len());
+    /// assert_eq!(sizes.take_ready_events(1), vec![1]);
+    /// ```
+    fn map_event<O, F>(self, map_fn: F) -> MapEventStructuralSignal<Self, F>
+    where
+        O: Clone,
+        F: Fn(Self::Item) -> O;
+
+    /// Drops every event emitted by this Signal that fails a predicate test.
+    ///
+    /// Note that dropping an event also drops its diffs, so this is only safe for
+    /// consumers that look at snapshots. Consumers that accumulate diffs (such as the
+    /// SignalVec compatibility layer) will fall out of sync.
+    ///
+    /// ```
+    /// use signals_im::hash_map::MutableHashMap;
+    /// use signals_im::StructuralSignalExt;
+    ///
+    /// let input_map = MutableHashMap::<u8, u8>::new();
+    /// let signal = input_map.as_signal().filter_events(|event| !event.snapshot.is_empty());
+    /// assert!(signal.take_ready_events(1).is_empty());
+    /// ```
+    fn filter_events<F>(self, predicate: F) -> FilterEventsStructuralSignal<Self, F>
+    where
+        F: Fn(&Self::Item) -> bool;
+
+    /// Runs `check` on every event before passing it through unchanged. This is meant
+    /// for tests: `check` should `assert!` some property of the events, so that the
+    /// pipeline panics as soon as an event violates it.
+    ///
+    /// ```
+    /// use signals_im::hash_map::MutableHashMap;
+    /// use signals_im::StructuralSignalExt;
+    ///
+    /// let input_map = MutableHashMap::<u8, u8>::new();
+    /// input_map.write().insert(1, 1);
+    ///
+    /// let signal = input_map
+    ///     .as_signal()
+    ///     .assert_invariant(|event| assert!(event.snapshot.len() < 100));
+    /// assert_eq!(signal.take_ready_events(1).len(), 1);
+    /// ```
+    fn assert_invariant<F>(self, check: F) -> AssertInvariantStructuralSignal<Self, F>
+    where
+        F: Fn(&Self::Item);
+
+    /// Drops every event that `eq_fn` considers equal to the last event this Signal
+    /// emitted. The first event is always emitted. Unlike deduping on `PartialEq`,
+    /// this can compare just part of an event, such as an id or the snapshot length.
+    ///
+    /// Like `filter_events`, the diffs of dropped events are lost.
+    ///
+    /// ```
+    /// use signals_im::hash_map::MutableHashMap;
+    /// use signals_im::StructuralSignalExt;
+    ///
+    /// let input_map = MutableHashMap::<u8, u8>::new();
+    /// input_map.write().insert(1, 1);
+    /// let signal = input_map
+    ///     .as_signal()
+    ///     .dedupe_by(|prev, current| prev.snapshot.len() == current.snapshot.len());
+    /// assert_eq!(signal.take_ready_events(1).len(), 1);
+    /// ```
+    fn dedupe_by<F>(self, eq_fn: F) -> DedupeByStructuralSignal<Self, F, Self::Item>
+    where
+        Self::Item: Clone,
+        F: Fn(&Self::Item, &Self::Item) -> bool;
+
+    /// Merges all of the events that are ready each time this Signal is polled into a
+    /// single event. Unlike simply skipping to the latest event, no diffs are lost, so
+    /// consumers that accumulate diffs stay correct. Diffs that cancel each other out
+    /// are removed where possible.
+    ///
+    /// ```
+    /// use signals_im::hash_map::MutableHashMap;
+    /// use signals_im::StructuralSignalExt;
+    ///
+    /// let input_map = MutableHashMap::<u8, u8>::new();
+    /// let broadcaster = input_map.as_signal().broadcast();
+    /// let mut signal = broadcaster.get_signal();
+    ///
+    /// input_map.write().insert(1, 1);
+    /// let events = (&mut signal).merge_ready().take_ready_events(2);
+    /// assert_eq!(events.len(), 1);
+    /// ```
+    fn merge_ready(self) -> MergeReadyStructuralSignal<Self>
+    where
+        Self::Item: MergeableEvent;
+
+    /// Holds back events until no new event has arrived for `duration`, then emits
+    /// everything that arrived in the meantime as a single event. This is useful when a
+    /// structure is updated in bursts and consumers only care about where it settles.
+    ///
+    /// The crate does not depend on any runtime, so `make_sleep` supplies the timer: it
+    /// is called with `duration` every time the quiet period restarts and should return
+    /// a future that completes once that much time has passed, such as
+    /// `tokio::time::sleep` or `async_std::task::sleep`.
+    ///
+    /// Like `merge_ready`, the held back events are merged rather than dropped, so the
+    /// emitted event carries every diff since the last emission and diff-based
+    /// consumers stay correct. Only the intermediate snapshots are skipped.
+    ///
+    /// ```
+    /// use futures::future::{ready, Ready};
+    /// use signals_im::hash_map::MutableHashMap;
+    /// use signals_im::StructuralSignalExt;
+    /// use std::time::Duration;
+    ///
+    /// let input_map = MutableHashMap::<u8, u8>::new();
+    /// let mut signal = input_map.as_signal();
+    ///
+    /// // A timer that has always already run out.
+    /// let make_sleep = |_: Duration| -> Ready<()> { ready(()) };
+    /// input_map.write().insert(1, 1);
+    /// let events = (&mut signal)
+    ///     .debounce(Duration::from_millis(50), make_sleep)
+    ///     .take_ready_events(2);
+    /// assert_eq!(events.len(), 1);
+    /// ```
+    fn debounce<F, Fut>(
+        self,
+        duration: Duration,
+        make_sleep: F,
+    ) -> DebounceStructuralSignal<Self, F, Fut, Self::Item>
+    where
+        Self::Item: MergeableEvent,
+        F: FnMut(Duration) -> Fut,
+        Fut: Future<Output = ()>;
+
+    /// Splits every event carrying several diffs into one event per diff, which is
+    /// simpler for consumers that handle one change at a time.
+    ///
+    /// Note that every event split from the same event carries its final snapshot,
+    /// since the snapshots in between are not reconstructed. Values should be read
+    /// from it the same way as from the original event.
+    ///
+    /// ```
+    /// use signals_im::hash_map::MutableHashMap;
+    /// use signals_im::StructuralSignalExt;
+    ///
+    /// let input_map = MutableHashMap::<u8, u8>::new();
+    /// let mut signal = input_map.as_signal().one_diff_per_event();
+    /// (&mut signal).take_ready_events(1);
+    ///
+    /// input_map.write().insert(1, 1);
+    /// input_map.write().insert(2, 2);
+    /// assert_eq!(signal.take_ready_events(2).len(), 2);
+    /// ```
+    fn one_diff_per_event(self) -> OneDiffPerEventStructuralSignal<Self, Self::Item>
+    where
+        Self::Item: SplittableEvent;
+
+    /// Emits the given event exactly once, before any events from this Signal. This
+    /// is useful for bridging from sources that do not start with a full replace.
+    ///
+    /// ```
+    /// use signals_im::hash_map::{HashMapEvent, MapDiff, MutableHashMap};
+    /// use signals_im::StructuralSignalExt;
+    /// use im::hashmap;
+    ///
+    /// let input_map = MutableHashMap::<u8, u8>::new();
+    /// let baseline = HashMapEvent::new(hashmap!{}, vec![MapDiff::Clear {}]);
+    ///
+    /// let events = input_map.as_signal().with_initial(baseline.clone()).take_ready_events(2);
+    /// assert_eq!(events[0], baseline);
+    /// assert_eq!(events[1].diffs, vec![MapDiff::Replace {}]);
+    /// ```
+    fn with_initial(self, event: Self::Item) -> WithInitialStructuralSignal<Self, Self::Item>;
+
+    /// Forwards events from this Signal until the trigger Future resolves, then ends
+    /// the Signal. This is useful for tying a subscription to the lifetime of
+    /// something else, such as a UI component.
+    ///
+    /// ```
+    /// use futures::channel::oneshot;
+    /// use futures_util::future::FutureExt;
+    /// use signals_im::hash_map::MutableHashMap;
+    /// use signals_im::StructuralSignalExt;
+    ///
+    /// let input_map = MutableHashMap::<u8, u8>::new();
+    /// let (unmount, unmounted) = oneshot::channel::<()>();
+    /// let mut signal = input_map.as_signal().take_until(unmounted.map(|_| ()));
+    ///
+    /// assert_eq!((&mut signal).take_ready_events(2).len(), 1);
+    /// unmount.send(()).unwrap();
+    /// input_map.write().insert(1, 1);
+    /// assert!(signal.take_ready_events(2).is_empty());
+    /// ```
+    fn take_until<F>(self, trigger: F) -> TakeUntilStructuralSignal<Self, F>
+    where
+        F: Future<Output = ()>;
+
+    /// Returns a Future that drives this Signal, passing every event to a sink
+    /// function, and resolves once the Signal ends. This bridges structural events
+    /// into non-async code, for example by sending them on a `std::sync::mpsc`
+    /// channel read by a worker thread.
+    ///
+    /// ```
+    /// use futures::channel::mpsc;
+    /// use futures_executor::block_on;
+    /// use signals_im::hash_map::{HashMapEvent, MapDiff};
+    /// use signals_im::{ChannelStructuralSignal, StructuralSignalExt};
+    /// use im::hashmap;
+    ///
+    /// let (sender, receiver) = mpsc::unbounded();
+    /// sender.unbounded_send(HashMapEvent::new(hashmap!{1 => 1}, vec![MapDiff::Replace {}])).unwrap();
+    /// drop(sender);
+    ///
+    /// let mut snapshots = vec![];
+    /// block_on(ChannelStructuralSignal::new(receiver).pipe_to(|event: HashMapEvent<u8, u8>| {
+    ///     snapshots.push(event.snapshot)
+    /// }));
+    /// assert_eq!(snapshots, vec![hashmap!{1 => 1}]);
+    /// ```
+    fn pipe_to<F>(self, sink: F) -> PipeToFuture<Self, F>
+    where
+        F: FnMut(Self::Item);
+
+    /// Converts this Signal into an Iterator for fully synchronous code. Each call
+    /// to `next()` blocks the current thread until the Signal produces an event, and
+    /// returns `None` once the Signal ends. If the Signal never ends, neither does
+    /// the iteration, so something else (usually another thread) must drive it.
+    ///
+    /// Blocking is done with `futures_executor::block_on`, which panics if it is
+    /// called from within another `block_on`-style executor on the same thread.
+    /// Don't iterate from inside async code.
+    ///
+    /// ```
+    /// use futures::channel::mpsc;
+    /// use signals_im::hash_map::{HashMapEvent, MapDiff};
+    /// use signals_im::{ChannelStructuralSignal, StructuralSignalExt};
+    /// use im::hashmap;
+    ///
+    /// let (sender, receiver) = mpsc::unbounded();
+    /// sender.unbounded_send(HashMapEvent::new(hashmap!{1 => 1}, vec![MapDiff::Replace {}])).unwrap();
+    /// drop(sender);
+    ///
+    /// let events: Vec<HashMapEvent<u8, u8>> =
+    ///     ChannelStructuralSignal::new(receiver).into_blocking_iter().collect();
+    /// assert_eq!(events.len(), 1);
+    /// ```
+    fn into_blocking_iter(self) -> BlockingIter<Self>;
+
+    /// Converts this Signal into a `futures` Stream of its events, which ends when the
+    /// Signal ends. This makes the combinators of `StreamExt` available.
+    ///
+    /// ```
+    /// use futures::StreamExt;
+    /// use futures_executor::block_on;
+    /// use signals_im::hash_map::MutableHashMap;
+    /// use signals_im::StructuralSignalExt;
+    /// use im::hashmap;
+    ///
+    /// let input_map = MutableHashMap::<u8, u8>::new();
+    /// input_map.write().insert(1, 1);
+    ///
+    /// let mut stream = input_map.as_signal().to_stream();
+    /// let event = block_on(stream.next()).unwrap();
+    /// assert_eq!(event.snapshot, hashmap!{1 => 1});
+    /// ```
+    fn to_stream(self) -> StructuralSignalStream<Self>;
 }
 
 impl<I> StructuralSignalExt for I
@@ -104,6 +486,148 @@ where
         }));
         return poll_result.into();
     }
+
+    fn snapshot_async(self) -> SnapshotFuture<Self>
+    where
+        Self::Item: SnapshottableEvent,
+    {
+        SnapshotFuture::new(self)
+    }
+
+    fn map_event<O, F>(self, map_fn: F) -> MapEventStructuralSignal<Self, F>
+    where
+        O: Clone,
+        F: Fn(Self::Item) -> O,
+    {
+        MapEventStructuralSignal::new(self, map_fn)
+    }
+
+    fn filter_events<F>(self, predicate: F) -> FilterEventsStructuralSignal<Self, F>
+    where
+        F: Fn(&Self::Item) -> bool,
+    {
+        FilterEventsStructuralSignal::new(self, predicate)
+    }
+
+    fn assert_invariant<F>(self, check: F) -> AssertInvariantStructuralSignal<Self, F>
+    where
+        F: Fn(&Self::Item),
+    {
+        AssertInvariantStructuralSignal::new(self, check)
+    }
+
+    fn one_diff_per_event(self) -> OneDiffPerEventStructuralSignal<Self, Self::Item>
+    where
+        Self::Item: SplittableEvent,
+    {
+        OneDiffPerEventStructuralSignal::new(self)
+    }
+
+    fn dedupe_by<F>(self, eq_fn: F) -> DedupeByStructuralSignal<Self, F, Self::Item>
+    where
+        Self::Item: Clone,
+        F: Fn(&Self::Item, &Self::Item) -> bool,
+    {
+        DedupeByStructuralSignal::new(self, eq_fn)
+    }
+
+    fn merge_ready(self) -> MergeReadyStructuralSignal<Self>
+    where
+        Self::Item: MergeableEvent,
+    {
+        MergeReadyStructuralSignal::new(self)
+    }
+
+    fn debounce<F, Fut>(
+        self,
+        duration: Duration,
+        make_sleep: F,
+    ) -> DebounceStructuralSignal<Self, F, Fut, Self::Item>
+    where
+        Self::Item: MergeableEvent,
+        F: FnMut(Duration) -> Fut,
+        Fut: Future<Output = ()>,
+    {
+        DebounceStructuralSignal::new(self, duration, make_sleep)
+    }
+
+    fn with_initial(self, event: Self::Item) -> WithInitialStructuralSignal<Self, Self::Item> {
+        WithInitialStructuralSignal::new(self, event)
+    }
+
+    fn take_until<F>(self, trigger: F) -> TakeUntilStructuralSignal<Self, F>
+    where
+        F: Future<Output = ()>,
+    {
+        TakeUntilStructuralSignal::new(self, trigger)
+    }
+
+    fn pipe_to<F>(self, sink: F) -> PipeToFuture<Self, F>
+    where
+        F: FnMut(Self::Item),
+    {
+        PipeToFuture::new(self, sink)
+    }
+
+    fn into_blocking_iter(self) -> BlockingIter<Self> {
+        BlockingIter::new(self)
+    }
+
+    fn to_stream(self) -> StructuralSignalStream<Self> {
+        StructuralSignalStream::new(self)
+    }
+
+    fn fold_to<B, F>(self, init: B, mut fold_fn: F) -> B
+    where
+        F: FnMut(&mut B, Self::Item),
+    {
+        let signal = self;
+        pin_mut!(signal);
+        let mut accumulator = init;
+        block_on(poll_fn(|cx| {
+            loop {
+                match Pin::as_mut(&mut signal).poll_change(cx) {
+                    Poll::Ready(Some(event)) => fold_fn(&mut accumulator, event),
+                    Poll::Ready(None) | Poll::Pending => break,
+                }
+            }
+            Poll::Ready(())
+        }));
+        accumulator
+    }
+
+    fn take_ready_events(self, n: usize) -> Vec<Self::Item> {
+        let signal = self;
+        pin_mut!(signal);
+        let mut events = vec![];
+        block_on(poll_fn(|cx| {
+            while events.len() < n {
+                match Pin::as_mut(&mut signal).poll_change(cx) {
+                    Poll::Ready(Some(event)) => events.push(event),
+                    Poll::Ready(None) | Poll::Pending => break,
+                }
+            }
+            Poll::Ready(())
+        }));
+        events
+    }
+
+    fn take_events(self, n: usize) -> Vec<Self::Item> {
+        let signal = self;
+        pin_mut!(signal);
+        let mut events = vec![];
+        block_on(poll_fn(|cx| {
+            while events.len() < n {
+                match Pin::as_mut(&mut signal).poll_change(cx) {
+                    Poll::Ready(Some(event)) => events.push(event),
+                    Poll::Ready(None) => return Poll::Ready(()),
+                    Poll::Pending => return Poll::Pending,
+                }
+            }
+            Poll::Ready(())
+        }));
+        events
+    }
 }
 
 #[pin_project(project = StructuralSignalBroadcasterStateProj)]
@@ -131,18 +655,21 @@ where
             most_recent_event,
             senders,
         } = self.project();
-        let poll_channel = input.poll_change(cx);
-        if let Poll::Ready(maybe_event) = &poll_channel {
-            if let Some(event) = maybe_event {
-                most_recent_event.replace(event.clone());
-                notify_senders(event.clone(), senders);
-            } else {
+        match input.poll_change(cx) {
+            Poll::Ready(Some(event)) => {
+                // The upstream still has to be advanced so no diffs are missed, but
+                // with nobody listening there is no need to clone the event.
+                if has_live_senders(senders) {
+                    notify_senders(&event, senders);
+                }
+                most_recent_event.replace(event);
+                true
+            }
+            Poll::Ready(None) => {
                 close_senders(senders);
+                true
             }
-
-            return true;
-        } else {
-            return false;
+            Poll::Pending => false,
         }
     }
 }
@@ -167,10 +694,35 @@ where
         })))
     }
 
+    /// Returns true if at least one signal created by this broadcaster is still alive.
+    pub fn has_subscribers(&self) -> bool {
+        has_live_senders(&self.0.read().senders)
+    }
+
+    /// The number of signals created by this broadcaster that are still alive. Signals
+    /// stop counting as soon as they are dropped.
+    pub fn subscriber_count(&self) -> usize {
+        live_sender_count(&self.0.read().senders)
+    }
+
+    /// Creates a Signal that starts with the most recent event of the broadcasted
+    /// Signal, if there is one, followed by every event after it.
     pub fn get_signal(&self) -> BroadcastedStructuralSignal<I, S> {
+        self.subscribe(true)
+    }
+
+    /// Like `get_signal`, but the most recent event is not replayed, so the Signal only
+    /// sees events that arrive after it was created. Note that this includes changes
+    /// made before then that the broadcaster had not pulled in yet, such as the
+    /// initial contents of a Mutable structure nobody has polled.
+    pub fn get_signal_without_replay(&self) -> BroadcastedStructuralSignal<I, S> {
+        self.subscribe(false)
+    }
+
+    fn subscribe(&self, replay: bool) -> BroadcastedStructuralSignal<I, S> {
         let (sender, receiver) = mpsc::unbounded();
 
-        {
+        if replay {
             let most_recent_event = &self.0.read().most_recent_event;
             if let Some(event) = most_recent_event {
                 sender.unbounded_send(event.clone()).unwrap();
@@ -183,8 +735,44 @@ where
             parent: self.0.clone(),
         }
     }
+
+    /// Creates a Signal that fires whenever the broadcasted Signal emits an event,
+    /// without carrying any data. Read the current state separately when it fires.
+    pub fn dirty_signal(&self) -> DirtySignal<BroadcastedStructuralSignal<I, S>> {
+        DirtySignal::new(self.get_signal())
+    }
+}
+
+thread_local! {
+    // The broadcasters that are pulling in changes on this thread, by address.
+    static PULLING_BROADCASTERS: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
 }
 
+// Marks a broadcaster as pulling in changes on this thread until it is dropped.
+struct BroadcastPullGuard(usize);
+
+impl BroadcastPullGuard {
+    fn enter<T>(broadcaster: &T) -> Option<BroadcastPullGuard> {
+        let address = broadcaster as *const T as usize;
+        PULLING_BROADCASTERS.with(|pulling| {
+            let mut pulling = pulling.borrow_mut();
+            if pulling.contains(&address) {
+                return None;
+            }
+            pulling.push(address);
+            Some(BroadcastPullGuard(address))
+        })
+    }
+}
+
+impl Drop for BroadcastPullGuard {
+    fn drop(&mut self) {
+        PULLING_BROADCASTERS.with(|pulling| pulling.borrow_mut().retain(|a| *a != self.0));
+    }
+}
+
+/// A Signal created by a StructuralSignalBroadcaster. This Signal is `Send` whenever
+/// the broadcasted Signal and its events are `Send + Sync`.
 #[pin_project(project = BroadcastedStructuralSignalProj)]
 pub struct BroadcastedStructuralSignal<I, S>
 where
@@ -196,6 +784,45 @@ where
     parent: Arc<RwLock<StructuralSignalBroadcasterState<I, S>>>,
 }
 
+impl<I, S> BroadcastedStructuralSignal<I, S>
+where
+    I: Clone,
+    S: StructuralSignal<Item = I>,
+    S: Unpin,
+{
+    /// Returns the next event that has already been delivered to this Signal, or None
+    /// if there is none. Unlike polling, this never pulls new events from upstream, so
+    /// it only sees events that another Signal of the same broadcaster pulled in.
+    pub fn try_next(&mut self) -> Option<I> {
+        // An empty channel and a closed one both mean there is nothing to return.
+        self.receiver.try_recv().ok()
+    }
+
+    /// Splits this Signal into the channel it receives events on and the state of the
+    /// broadcaster that feeds that channel. This is useful for building custom fan-out
+    /// topologies, such as filtering events per subscriber before they are consumed.
+    pub fn into_parts(
+        self,
+    ) -> (
+        mpsc::UnboundedReceiver<I>,
+        Arc<RwLock<StructuralSignalBroadcasterState<I, S>>>,
+    ) {
+        (self.receiver, self.parent)
+    }
+
+    /// Rebuilds a Signal from the parts returned by `into_parts`.
+    ///
+    /// The receiver must belong to a sender that is registered with the given parent,
+    /// which is the case for any receiver that came out of `into_parts` along with it.
+    /// Otherwise events pulled in by this Signal will never be delivered to it.
+    pub fn from_parts(
+        receiver: mpsc::UnboundedReceiver<I>,
+        parent: Arc<RwLock<StructuralSignalBroadcasterState<I, S>>>,
+    ) -> BroadcastedStructuralSignal<I, S> {
+        BroadcastedStructuralSignal { receiver, parent }
+    }
+}
+
 impl<I, S> StructuralSignal for BroadcastedStructuralSignal<I, S>
 where
     I: Clone,
@@ -211,6 +838,13 @@ where
             return Poll::Ready(result);
         }
 
+        // If this thread is already pulling in changes for the same broadcaster, the
+        // broadcasted Signal depends on one of its own Signals. Taking the lock again
+        // would deadlock, so wait for the outer pull to deliver to the channel instead.
+        let _pull_guard = match BroadcastPullGuard::enter(&**parent) {
+            Some(guard) => guard,
+            None => return Poll::Pending,
+        };
         let mut writer = parent.write();
         let has_new_changes = Pin::new(&mut *writer).pull_in_new_changes(cx);
         if has_new_changes {
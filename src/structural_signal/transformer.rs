@@ -1,7 +1,10 @@
 use crate::StructuralSignal;
+use futures::task::{waker, ArcWake};
+use parking_lot::{Mutex, RwLock};
 use pin_project::pin_project;
 use std::pin::Pin;
-use std::task::{Context, Poll};
+use std::sync::Arc;
+use std::task::{Context, Poll, Waker};
 
 /// A trait that takes a series of events from an input StructuralSignal and
 /// applies it to an internal data structure to create a modified output
@@ -15,6 +18,9 @@ pub trait StructuralSignalTransformer {
 }
 
 /// A StructuralSignal that has been run through a StructuralSignalTransformer.
+///
+/// This Signal is `Send` whenever its input Signal and transformer are, which for the
+/// built-in transformers means the transform function must be `Send` too.
 #[pin_project(project = TransformedStructuralSignalProj)]
 pub struct TransformedStructuralSignal<IS, II, T>
 where
@@ -29,6 +35,7 @@ where
     transformer: T,
 
     is_closed: bool,
+    max_events_per_poll: Option<usize>,
 }
 
 impl<IS, II, T> TransformedStructuralSignal<IS, II, T>
@@ -44,8 +51,43 @@ where
             transformed_signal,
             transformer,
             is_closed: false,
+            max_events_per_poll: None,
         }
     }
+
+    /// Limits how many input events are applied in a single poll. Once the limit is
+    /// reached the signal wakes itself and returns `Poll::Pending`, yielding the
+    /// executor to other tasks before continuing on the next poll. This keeps expensive
+    /// transforms over a large backlog of events from starving everything else, at
+    /// the cost of a `Poll::Pending` no longer meaning the output is caught up.
+    pub fn with_yield_budget(mut self, max_events_per_poll: usize) -> Self {
+        assert!(max_events_per_poll > 0, "Yield budget must allow at least one event");
+        self.max_events_per_poll = Some(max_events_per_poll);
+        self
+    }
+
+    /// Moves the transformer into a cache that outlives any one subscription. Every
+    /// signal from the returned cache reads from the same materialized output, so
+    /// dropping a signal and subscribing again later starts from the already
+    /// transformed result instead of re-running the transform over the full input.
+    ///
+    /// The cache keeps the input signal alive for as long as the cache or any of its
+    /// signals exist, so changes made while nobody is subscribed are still applied
+    /// (once, on the next poll). Dropping the cache and all of its signals discards
+    /// the cached output along with the input subscription.
+    pub fn cached_transform(self) -> StructuralSignalTransformCache<IS, II, T>
+    where
+        IS: Unpin,
+    {
+        let subscriber_wakers = Arc::new(SubscriberWakers(Mutex::new(Vec::new())));
+        StructuralSignalTransformCache(Arc::new(RwLock::new(StructuralSignalTransformCacheState {
+            input_signal: self.input_signal,
+            transformer: self.transformer,
+            is_closed: self.is_closed,
+            input_waker: waker(subscriber_wakers.clone()),
+            subscriber_wakers,
+        })))
+    }
 }
 
 impl<IS, II, T> StructuralSignal for TransformedStructuralSignal<IS, II, T>
@@ -66,13 +108,21 @@ where
             transformed_signal,
             transformer,
             is_closed,
+            max_events_per_poll,
         } = self.project();
 
+        let mut applied_events = 0;
         loop {
+            if Some(applied_events) == *max_events_per_poll {
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+
             let input_poll = input_signal.as_mut().poll_change(cx);
             match input_poll {
                 Poll::Ready(Some(event)) => {
                     transformer.apply_event(event);
+                    applied_events += 1;
                 }
                 Poll::Ready(None) => {
                     *is_closed = true;
@@ -91,4 +141,249 @@ where
             result
         }
     }
-}
\ No newline at end of file
+}
+
+#[pin_project(project = StructuralSignalTransformCacheStateProj)]
+pub struct StructuralSignalTransformCacheState<IS, II, T>
+where
+    IS: StructuralSignal<Item=II>,
+    IS: Unpin,
+    II: Clone,
+    T: StructuralSignalTransformer<InputEvent=II>,
+{
+    #[pin]
+    input_signal: IS,
+    transformer: T,
+    is_closed: bool,
+    // The input is always polled with this waker, which wakes every subscriber rather
+    // than only the one that happened to poll last.
+    input_waker: Waker,
+    subscriber_wakers: Arc<SubscriberWakers>,
+}
+
+// The wakers of every signal of a StructuralSignalTransformCache that has polled since
+// the input last changed.
+struct SubscriberWakers(Mutex<Vec<Waker>>);
+
+impl ArcWake for SubscriberWakers {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        let wakers = std::mem::take(&mut *arc_self.0.lock());
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+}
+
+impl<IS, II, T> StructuralSignalTransformCacheState<IS, II, T>
+where
+    IS: StructuralSignal<Item=II>,
+    IS: Unpin,
+    II: Clone,
+    T: StructuralSignalTransformer<InputEvent=II>,
+{
+    fn pull_in_new_changes(self: Pin<&mut Self>, cx: &mut Context) {
+        let StructuralSignalTransformCacheStateProj {
+            mut input_signal,
+            transformer,
+            is_closed,
+            input_waker,
+            subscriber_wakers,
+        } = self.project();
+
+        {
+            // Signals are usually polled again with the same waker while they wait, and
+            // that waker is already registered.
+            let mut wakers = subscriber_wakers.0.lock();
+            if !wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+                wakers.push(cx.waker().clone());
+            }
+        }
+
+        let mut input_cx = Context::from_waker(input_waker);
+        while !*is_closed {
+            match input_signal.as_mut().poll_change(&mut input_cx) {
+                Poll::Ready(Some(event)) => transformer.apply_event(event),
+                Poll::Ready(None) => *is_closed = true,
+                Poll::Pending => break,
+            }
+        }
+    }
+}
+
+/// Holds the output of a StructuralSignalTransformer across subscriptions. Created
+/// by `TransformedStructuralSignal::cached_transform`.
+pub struct StructuralSignalTransformCache<IS, II, T>(
+    Arc<RwLock<StructuralSignalTransformCacheState<IS, II, T>>>,
+)
+where
+    IS: StructuralSignal<Item=II>,
+    IS: Unpin,
+    II: Clone,
+    T: StructuralSignalTransformer<InputEvent=II>;
+
+impl<IS, II, T> StructuralSignalTransformCache<IS, II, T>
+where
+    IS: StructuralSignal<Item=II>,
+    IS: Unpin,
+    II: Clone,
+    T: StructuralSignalTransformer<InputEvent=II>,
+{
+    /// Creates a new signal of the cached output. Its first event is a full replace
+    /// with the output as it stands, which does not re-run the transform.
+    pub fn get_signal(&self) -> CachedTransformedStructuralSignal<IS, II, T> {
+        let transformed_signal = self.0.read().transformer.get_signal();
+        CachedTransformedStructuralSignal {
+            transformed_signal,
+            parent: self.0.clone(),
+        }
+    }
+}
+
+/// A Signal created by a StructuralSignalTransformCache.
+#[pin_project(project = CachedTransformedStructuralSignalProj)]
+pub struct CachedTransformedStructuralSignal<IS, II, T>
+where
+    IS: StructuralSignal<Item=II>,
+    IS: Unpin,
+    II: Clone,
+    T: StructuralSignalTransformer<InputEvent=II>,
+{
+    #[pin]
+    transformed_signal: T::OutputSignal,
+    parent: Arc<RwLock<StructuralSignalTransformCacheState<IS, II, T>>>,
+}
+
+impl<IS, II, T> StructuralSignal for CachedTransformedStructuralSignal<IS, II, T>
+where
+    IS: StructuralSignal<Item=II>,
+    IS: Unpin,
+    II: Clone,
+    T: StructuralSignalTransformer<InputEvent=II>,
+{
+    type Item = <T::OutputSignal as StructuralSignal>::Item;
+
+    #[inline]
+    fn poll_change(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<Option<Self::Item>> {
+        let CachedTransformedStructuralSignalProj {
+            transformed_signal,
+            parent,
+        } = self.project();
+
+        let is_closed = {
+            let mut writer = parent.write();
+            Pin::new(&mut *writer).pull_in_new_changes(cx);
+            writer.is_closed
+        };
+
+        let result = transformed_signal.poll_change(cx);
+        if is_closed && !result.is_ready() {
+            Poll::Ready(None)
+        } else {
+            result
+        }
+    }
+}
+
+/// A StructuralSignalTransformer that can fail to apply an event. Implementations
+/// should leave their internal data structure untouched when returning an error, so
+/// that everything emitted before the error remains valid.
+pub trait FallibleStructuralSignalTransformer {
+    type InputEvent;
+    type OutputSignal: StructuralSignal;
+    type Error: Clone;
+
+    fn apply_event(&mut self, event: Self::InputEvent) -> Result<(), Self::Error>;
+    fn get_signal(&self) -> Self::OutputSignal;
+}
+
+/// A StructuralSignal that has been run through a FallibleStructuralSignalTransformer.
+///
+/// Events are emitted as `Ok` until the transformer fails. Any changes applied before
+/// the failure are flushed first, then the error is emitted as an `Err` event and the
+/// signal ends. The offending event and everything after it is never applied.
+#[pin_project(project = FallibleTransformedStructuralSignalProj)]
+pub struct FallibleTransformedStructuralSignal<IS, II, T>
+where
+    IS: StructuralSignal<Item=II>,
+    II: Clone,
+    T: FallibleStructuralSignalTransformer<InputEvent=II>,
+{
+    #[pin]
+    input_signal: IS,
+    #[pin]
+    transformed_signal: T::OutputSignal,
+    transformer: T,
+
+    error: Option<T::Error>,
+    is_closed: bool,
+}
+
+impl<IS, II, T> FallibleTransformedStructuralSignal<IS, II, T>
+where
+    IS: StructuralSignal<Item=II>,
+    II: Clone,
+    T: FallibleStructuralSignalTransformer<InputEvent=II>,
+{
+    pub(crate) fn new(input_signal: IS, transformer: T) -> FallibleTransformedStructuralSignal<IS, II, T> {
+        let transformed_signal = transformer.get_signal();
+        FallibleTransformedStructuralSignal {
+            input_signal,
+            transformed_signal,
+            transformer,
+            error: None,
+            is_closed: false,
+        }
+    }
+}
+
+impl<IS, II, T> StructuralSignal for FallibleTransformedStructuralSignal<IS, II, T>
+where
+    IS: StructuralSignal<Item=II>,
+    II: Clone,
+    T: FallibleStructuralSignalTransformer<InputEvent=II>,
+{
+    type Item = Result<<T::OutputSignal as StructuralSignal>::Item, T::Error>;
+
+    #[inline]
+    fn poll_change(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<Option<Self::Item>> {
+        let FallibleTransformedStructuralSignalProj {
+            mut input_signal,
+            transformed_signal,
+            transformer,
+            error,
+            is_closed,
+        } = self.project();
+
+        while !*is_closed {
+            let input_poll = input_signal.as_mut().poll_change(cx);
+            match input_poll {
+                Poll::Ready(Some(event)) => {
+                    if let Err(e) = transformer.apply_event(event) {
+                        error.replace(e);
+                        *is_closed = true;
+                    }
+                }
+                Poll::Ready(None) => {
+                    *is_closed = true;
+                }
+                Poll::Pending => {
+                    break;
+                }
+            }
+        }
+
+        match transformed_signal.poll_change(cx) {
+            Poll::Ready(Some(event)) => Poll::Ready(Some(Ok(event))),
+            _ if error.is_some() => Poll::Ready(error.take().map(Err)),
+            Poll::Ready(None) => Poll::Ready(None),
+            Poll::Pending if *is_closed => Poll::Ready(None),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
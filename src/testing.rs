@@ -0,0 +1,171 @@
+//! Helpers for testing custom `PullableDiff` implementations.
+//!
+//! A pull source coalesces the diffs added between two pulls, merging or dropping
+//! diffs that touch the same key. `DiffCoalescingHarness` checks that coalescing by
+//! replaying the coalesced diffs onto the state from the previous pull and comparing
+//! the result against the state that was mutated directly.
+//!
+//! ```
+//! use im::hashmap;
+//! use signals_im::hash_map::MapDiff;
+//! use signals_im::testing::DiffCoalescingHarness;
+//!
+//! let mut harness = DiffCoalescingHarness::new(hashmap!{1 => 1});
+//! harness.state_mut().insert(2, 2);
+//! harness.add_diff(MapDiff::Insert { key: 2 });
+//! harness.state_mut().remove(&2);
+//! harness.add_diff(MapDiff::Remove { key: 2 });
+//! harness.state_mut().insert(1, 3);
+//! harness.add_diff(MapDiff::Update { key: 1 });
+//!
+//! let coalesced = harness.assert_coalesced_diffs_replay();
+//! assert_eq!(coalesced, vec![MapDiff::Update { key: 1 }]);
+//! ```
+
+use crate::hash_map::MapDiff;
+use crate::structural_signal::pull_source::{
+    PullSourceStateSnapshot, PullableDiff, SignalId, StructrualSignalPullSource,
+};
+use crate::vector::VectorDiff;
+use im::{HashMap, Vector};
+use std::fmt::Debug;
+use std::hash::Hash;
+
+/// A data structure that diffs of a given type can be replayed against.
+pub trait DiffTarget<D: PullableDiff>: Clone + PartialEq + Debug {
+    /// Applies a diff, reading any values it needs from the snapshot of the structure
+    /// taken when the diff was pulled.
+    fn apply_diff(&mut self, diff: &D, snapshot: &Self);
+}
+
+impl<T> DiffTarget<VectorDiff> for Vector<T>
+where
+    T: Clone + PartialEq + Debug,
+{
+    fn apply_diff(&mut self, diff: &VectorDiff, snapshot: &Vector<T>) {
+        match *diff {
+            VectorDiff::Replace {} => *self = snapshot.clone(),
+            VectorDiff::Insert { index, .. } => {
+                self.insert(index, diff.get_value_from_snapshot(snapshot).unwrap().clone());
+            }
+            VectorDiff::Update { index, .. } => {
+                self.set(index, diff.get_value_from_snapshot(snapshot).unwrap().clone());
+            }
+            VectorDiff::Remove { index, .. } => {
+                self.remove(index);
+            }
+            VectorDiff::Move { from, to, .. } => {
+                let value = self.remove(from);
+                self.insert(to, value);
+            }
+            VectorDiff::Extend {
+                index,
+                snapshot_index,
+                count,
+            } => {
+                for offset in 0..count {
+                    self.insert(index + offset, snapshot[snapshot_index + offset].clone());
+                }
+            }
+            VectorDiff::Clear {} => self.clear(),
+        }
+    }
+}
+
+impl<K, V> DiffTarget<MapDiff<K>> for HashMap<K, V>
+where
+    K: Clone + Eq + Hash + Debug,
+    V: Clone + PartialEq + Debug,
+{
+    fn apply_diff(&mut self, diff: &MapDiff<K>, snapshot: &HashMap<K, V>) {
+        match diff {
+            MapDiff::Replace {} => *self = snapshot.clone(),
+            MapDiff::Insert { key } | MapDiff::Update { key } => {
+                self.insert(key.clone(), snapshot.get(key).unwrap().clone());
+            }
+            MapDiff::Remove { key } => {
+                self.remove(key);
+            }
+            MapDiff::Clear {} => self.clear(),
+        }
+    }
+}
+
+/// Checks that a PullableDiff implementation coalesces correctly. Mutate the state
+/// with `state_mut`, then record the diff the mutation would emit with `add_diff`,
+/// exactly like a `PullSourceHost` would.
+pub struct DiffCoalescingHarness<D, S>
+where
+    D: PullableDiff,
+    S: DiffTarget<D>,
+{
+    pull_source: StructrualSignalPullSource<D>,
+    signal_id: SignalId,
+    pulled_state: S,
+    state: S,
+}
+
+impl<D, S> DiffCoalescingHarness<D, S>
+where
+    D: PullableDiff,
+    S: DiffTarget<D>,
+{
+    pub fn new(initial: S) -> DiffCoalescingHarness<D, S> {
+        let mut pull_source = StructrualSignalPullSource::new();
+        let signal_id = pull_source.get_next_signal_id();
+        // The first pull registers the signal, so that later diffs are recorded.
+        pull_source.pull_signal(signal_id);
+        DiffCoalescingHarness {
+            pull_source,
+            signal_id,
+            pulled_state: initial.clone(),
+            state: initial,
+        }
+    }
+
+    /// The state as mutated directly, which the coalesced diffs must reproduce.
+    pub fn state_mut(&mut self) -> &mut S {
+        &mut self.state
+    }
+
+    /// Records a diff, merging it with earlier diffs on the same key.
+    pub fn add_diff(&mut self, diff: D) {
+        self.pull_source.add_diff(diff);
+    }
+
+    /// Updates the keys of every pending diff, like `MutableVectorState` does when an
+    /// insert or remove shifts the indices after it.
+    pub fn update_keys<F>(&mut self, updater: F)
+    where
+        F: Fn(&D::KeyType) -> D::KeyType,
+    {
+        self.pull_source.update_keys(updater);
+    }
+
+    /// The internal state of the pull source, in a deterministic order.
+    pub fn pull_source_snapshot(&self) -> PullSourceStateSnapshot<D> {
+        self.pull_source.state_snapshot()
+    }
+
+    /// Pulls the coalesced diffs and replays them onto the state from the previous
+    /// pull, panicking if the result does not match the directly mutated state.
+    /// Returns the coalesced diffs for further inspection.
+    pub fn assert_coalesced_diffs_replay(&mut self) -> Vec<D>
+    where
+        D: Debug,
+    {
+        let diffs = self.pull_source.pull_signal(self.signal_id);
+        let mut replayed = self.pulled_state.clone();
+        for diff in diffs.iter() {
+            replayed.apply_diff(diff, &self.state);
+        }
+        assert_eq!(
+            replayed, self.state,
+            "Coalesced diffs {:?} did not reproduce the mutated state",
+            diffs
+        );
+
+        self.pulled_state = self.state.clone();
+        diffs
+    }
+}
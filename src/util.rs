@@ -8,7 +8,7 @@ pub(crate) static CHANNEL_CLEANUP_MIN_COUNT: i16 = 20;
 /// Distributes an event out to each channel in a vector, removing channels
 /// as they become closed.
 #[inline]
-pub(crate) fn notify_senders<T: Clone, D>(event: T, mut senders: D)
+pub(crate) fn notify_senders<T: Clone, D>(event: &T, mut senders: D)
 where
     D: DerefMut<Target = Vec<Option<mpsc::UnboundedSender<T>>>>,
 {
@@ -43,6 +43,23 @@ where
     senders.retain(|maybe_sender| maybe_sender.is_some());
 }
 
+/// Returns true if any channel in a vector is still open.
+#[inline]
+pub(crate) fn has_live_senders<T>(senders: &[Option<mpsc::UnboundedSender<T>>]) -> bool {
+    senders
+        .iter()
+        .any(|maybe_sender| matches!(maybe_sender, Some(sender) if !sender.is_closed()))
+}
+
+/// Counts the channels in a vector that are still open.
+#[inline]
+pub(crate) fn live_sender_count<T>(senders: &[Option<mpsc::UnboundedSender<T>>]) -> usize {
+    senders
+        .iter()
+        .filter(|maybe_sender| matches!(maybe_sender, Some(sender) if !sender.is_closed()))
+        .count()
+}
+
 /// Closes all channels in a vector.
 #[inline]
 pub(crate) fn close_senders<T, D>(mut senders: D)
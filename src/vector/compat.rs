@@ -1,12 +1,18 @@
 use super::event::{VectorDiff, VectorEvent};
+use super::vector::{MutableVector, MutableVectorState};
+use crate::structural_signal::pull_source::PullSourceStructuralSignal;
 use crate::StructuralSignal;
 use futures_signals::signal_vec::{SignalVec, VecDiff};
 use pin_project::pin_project;
+use std::cmp::max;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 
+/// Adapts a vector StructuralSignal into a `futures_signals` SignalVec, emitting one
+/// VecDiff for each VectorDiff. Created by `SignalVectorExt::to_signal_vec`.
 #[pin_project(project = StructuralSignalVecCompatProj)]
-struct StructuralSignalVecCompat<S, T>
+#[must_use = "SignalVecs do nothing unless polled"]
+pub struct StructuralSignalVecCompat<S, T>
 where
     T: Clone,
     S: StructuralSignal<Item = VectorEvent<T>>,
@@ -42,57 +48,101 @@ where
     S: StructuralSignal<Item = VectorEvent<T>>,
     S: Unpin,
 {
-    fn convert_next_diff(
-        last_event: &mut VectorEvent<T>,
+    // Works out which diff of a new event to convert first, and how long the vector is
+    // just before it. The length is derived from the snapshot rather than tracked across
+    // events, so that an unusual event can never leave the adapter out of sync. Events
+    // that contain a Replace are converted into a single Replace, since the snapshot
+    // already reflects every other diff in the event.
+    fn start_event(
+        event: &VectorEvent<T>,
         diffs_buffer_next_index: &mut usize,
         length: &mut usize,
     ) -> Option<VecDiff<T>> {
+        let diffs = &event.diffs;
+        if diffs.iter().any(|diff| matches!(diff, VectorDiff::Replace {})) {
+            *length = event.snapshot.len();
+            return Some(VecDiff::Replace {
+                values: event.snapshot.iter().cloned().collect(),
+            });
+        }
+
+        // Anything before a Clear is wiped out by it.
+        *diffs_buffer_next_index = diffs
+            .iter()
+            .rposition(|diff| matches!(diff, VectorDiff::Clear {}))
+            .unwrap_or(0);
+        let net_inserts = diffs[*diffs_buffer_next_index..]
+            .iter()
+            .fold(0isize, |net, diff| match diff {
+                VectorDiff::Insert { .. } => net + 1,
+                VectorDiff::Remove { .. } => net - 1,
+                _ => net,
+            });
+        *length = max(event.snapshot.len() as isize - net_inserts, 0) as usize;
+        None
+    }
+
+    fn convert_next_diff(
+        last_event: &VectorEvent<T>,
+        diffs_buffer_next_index: usize,
+        length: &mut usize,
+    ) -> VecDiff<T> {
         let snapshot = &last_event.snapshot;
-        let vector_diff = &last_event.diffs[*diffs_buffer_next_index];
+        let vector_diff = &last_event.diffs[diffs_buffer_next_index];
 
-        Some(match vector_diff {
+        match vector_diff {
             VectorDiff::Replace {} => {
                 *length = snapshot.len();
                 VecDiff::Replace {
                     values: snapshot.clone().into_iter().collect(),
                 }
             }
+            // Later diffs in the same event may have moved the value, so it has to be
+            // looked up by its snapshot index rather than by its index.
             VectorDiff::Insert {
-                index, 
+                index,
                 snapshot_index: _,
             } => {
+                let value = vector_diff.get_value_from_snapshot(snapshot).unwrap().clone();
+                let is_push = *index == *length;
                 *length += 1;
-                if *index == *length - 1 {
-                    VecDiff::Push {
-                        value: snapshot.back().unwrap().clone(),
-                    }
+                if is_push {
+                    VecDiff::Push { value }
                 } else {
                     VecDiff::InsertAt {
                         index: *index,
-                        value: snapshot[*index].clone(),
+                        value,
                     }
                 }
-            },
+            }
             VectorDiff::Update {
                 index,
                 snapshot_index: _,
             } => VecDiff::UpdateAt {
                 index: *index,
-                value: snapshot[*index].clone(),
+                value: vector_diff.get_value_from_snapshot(snapshot).unwrap().clone(),
             },
             VectorDiff::Remove {
                 index,
                 snapshot_index: _,
             } => {
-                *length -= 1;
+                *length = length.saturating_sub(1);
                 if *index == *length {
                     VecDiff::Pop {}
                 } else {
                     VecDiff::RemoveAt { index: *index }
                 }
             }
-            VectorDiff::Clear {} => VecDiff::Clear {},
-        })
+            VectorDiff::Move { from, to, .. } => VecDiff::Move {
+                old_index: *from,
+                new_index: *to,
+            },
+            VectorDiff::Extend { .. } => unreachable!(),
+            VectorDiff::Clear {} => {
+                *length = 0;
+                VecDiff::Clear {}
+            }
+        }
     }
 }
 
@@ -110,17 +160,29 @@ where
         cx: &mut Context,
     ) -> Poll<Option<VecDiff<Self::Item>>> {
         let StructuralSignalVecCompatProj {
-            inner,
+            mut inner,
             last_event,
             diffs_buffer_next_index,
             length,
         } = self.project();
 
-        if last_event.is_none() {
-            match inner.poll_change(cx) {
+        while last_event.is_none() {
+            match inner.as_mut().poll_change(cx) {
                 Poll::Ready(Some(event)) => {
-                    *last_event = Some(event);
-                    *diffs_buffer_next_index = 0;
+                    // SignalVec has no batched push, so batches become one Push each.
+                    let event = event.expand_batches();
+                    let maybe_replace = StructuralSignalVecCompat::<S, T>::start_event(
+                        &event,
+                        diffs_buffer_next_index,
+                        length,
+                    );
+                    if maybe_replace.is_some() {
+                        return Poll::Ready(maybe_replace);
+                    }
+                    // Events without any diffs are skipped entirely.
+                    if *diffs_buffer_next_index < event.diffs.len() {
+                        *last_event = Some(event);
+                    }
                 }
                 Poll::Ready(None) => {
                     return Poll::Ready(None);
@@ -131,17 +193,128 @@ where
             }
         }
 
+        let event = last_event.as_ref().unwrap();
         let result = StructuralSignalVecCompat::<S, T>::convert_next_diff(
-            last_event.as_mut().unwrap(),
-            diffs_buffer_next_index,
+            event,
+            *diffs_buffer_next_index,
             length,
         );
 
-        if *diffs_buffer_next_index == last_event.as_ref().unwrap().diffs.len() - 1 {
+        *diffs_buffer_next_index += 1;
+        if *diffs_buffer_next_index >= event.diffs.len() {
             *last_event = None;
-            *diffs_buffer_next_index = 0;
         }
 
-        return Poll::Ready(result);
+        return Poll::Ready(Some(result));
+    }
+}
+
+/// Adapts a `futures_signals` SignalVec into a vector StructuralSignal, the inverse
+/// of `StructuralSignalVecCompat`. Created by `signal_vec_to_structural`.
+#[pin_project(project = SignalVecStructuralSignalProj)]
+#[must_use = "StructuralSignals do nothing unless polled"]
+pub struct SignalVecStructuralSignal<S, T>
+where
+    T: Clone,
+    S: SignalVec<Item = T>,
+{
+    #[pin]
+    inner: S,
+    vector: MutableVector<T>,
+    #[pin]
+    output: PullSourceStructuralSignal<MutableVectorState<T>>,
+    is_closed: bool,
+}
+
+/// Brings a `futures_signals` SignalVec, such as the signal of a `MutableVec`, into
+/// this crate. Every VecDiff is applied to an internal MutableVector, and the
+/// returned StructuralSignal emits that MutableVector's events.
+///
+/// ```
+/// use futures_signals::signal_vec::MutableVec;
+/// use signals_im::vector::signal_vec_to_structural;
+/// use signals_im::StructuralSignalExt;
+/// use im::vector;
+///
+/// let input_vec = MutableVec::new_with_values(vec![1, 2]);
+/// let signal = signal_vec_to_structural(input_vec.signal_vec());
+/// assert_eq!(signal.snapshot().unwrap(), vector![1, 2]);
+/// ```
+pub fn signal_vec_to_structural<S, T>(signal_vec: S) -> SignalVecStructuralSignal<S, T>
+where
+    T: Clone,
+    S: SignalVec<Item = T>,
+{
+    let vector = MutableVector::new();
+    let output = vector.as_signal();
+    SignalVecStructuralSignal {
+        inner: signal_vec,
+        vector,
+        output,
+        is_closed: false,
+    }
+}
+
+impl<S, T> SignalVecStructuralSignal<S, T>
+where
+    T: Clone,
+    S: SignalVec<Item = T>,
+{
+    fn apply_vec_diff(writer: &mut MutableVectorState<T>, diff: VecDiff<T>) {
+        match diff {
+            VecDiff::Replace { values } => writer.replace(values.into_iter()),
+            VecDiff::InsertAt { index, value } => writer.insert(index, value),
+            VecDiff::UpdateAt { index, value } => {
+                writer.set(index, value);
+            }
+            VecDiff::RemoveAt { index } => {
+                writer.remove(index);
+            }
+            VecDiff::Move {
+                old_index,
+                new_index,
+            } => writer.move_item(old_index, new_index),
+            VecDiff::Push { value } => writer.push_back(value),
+            VecDiff::Pop {} => {
+                writer.pop_back();
+            }
+            VecDiff::Clear {} => writer.clear(),
+        }
+    }
+}
+
+impl<S, T> StructuralSignal for SignalVecStructuralSignal<S, T>
+where
+    T: Clone,
+    S: SignalVec<Item = T>,
+{
+    type Item = VectorEvent<T>;
+
+    #[inline]
+    fn poll_change(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<VectorEvent<T>>> {
+        let SignalVecStructuralSignalProj {
+            mut inner,
+            vector,
+            output,
+            is_closed,
+        } = self.project();
+
+        while !*is_closed {
+            match inner.as_mut().poll_vec_change(cx) {
+                Poll::Ready(Some(diff)) => {
+                    let mut writer = vector.write();
+                    SignalVecStructuralSignal::<S, T>::apply_vec_diff(&mut writer, diff);
+                }
+                Poll::Ready(None) => *is_closed = true,
+                Poll::Pending => break,
+            }
+        }
+
+        let result = output.poll_change(cx);
+        if *is_closed && !result.is_ready() {
+            Poll::Ready(None)
+        } else {
+            result
+        }
     }
 }
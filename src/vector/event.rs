@@ -1,9 +1,13 @@
 use crate::structural_signal::pull_source::DiffMergeResult;
 use crate::structural_signal::pull_source::PullableDiff;
-use crate::structural_signal::structural_signal_ext::SnapshottableEvent;
+use crate::structural_signal::structural_signal_ext::{
+    MergeableEvent, SnapshottableEvent, SplittableEvent,
+};
 use im::Vector;
+use std::cmp::max;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum VectorDiff {
     Replace {},
 
@@ -13,8 +17,25 @@ pub enum VectorDiff {
 
     Remove { index: usize, snapshot_index: usize },
 
-    // TODO: Consider adding batch events, for example modeling
-    // extending an array with all the items from another array.
+    // Appends `count` values in one go, such as when extending the vector with all
+    // the items from another collection. The values are found in the snapshot starting
+    // at `snapshot_index`.
+    Extend {
+        index: usize,
+        snapshot_index: usize,
+        count: usize,
+    },
+
+    // Moves the value at `from` so that it ends up at `to`, like removing it and
+    // inserting it again, but keeping its identity. The moved value is found in the
+    // snapshot at `snapshot_to`.
+    Move {
+        from: usize,
+        to: usize,
+        snapshot_from: usize,
+        snapshot_to: usize,
+    },
+
     Clear {},
 }
 
@@ -34,7 +55,18 @@ impl PullableDiff for VectorDiff {
             | VectorDiff::Remove {
                 index,
                 snapshot_index: _,
+            }
+            | VectorDiff::Extend {
+                index,
+                snapshot_index: _,
+                count: _,
             } => Some(index),
+            VectorDiff::Move {
+                from: _,
+                to,
+                snapshot_from: _,
+                snapshot_to: _,
+            } => Some(to),
             VectorDiff::Replace {} | VectorDiff::Clear {} => None,
         }
     }
@@ -52,7 +84,18 @@ impl PullableDiff for VectorDiff {
             | VectorDiff::Remove {
                 index: _,
                 snapshot_index,
+            }
+            | VectorDiff::Extend {
+                index: _,
+                snapshot_index,
+                count: _,
             } => Some(snapshot_index),
+            VectorDiff::Move {
+                from: _,
+                to: _,
+                snapshot_from: _,
+                snapshot_to,
+            } => Some(snapshot_to),
             VectorDiff::Replace {} | VectorDiff::Clear {} => None,
         }
     }
@@ -70,9 +113,22 @@ impl PullableDiff for VectorDiff {
             | VectorDiff::Remove {
                 index,
                 snapshot_index: _,
+            }
+            | VectorDiff::Extend {
+                index,
+                snapshot_index: _,
+                count: _,
             } => {
                 *index = new_index;
             }
+            VectorDiff::Move {
+                from: _,
+                to,
+                snapshot_from: _,
+                snapshot_to: _,
+            } => {
+                *to = new_index;
+            }
             _ => {
                 panic!("Cannot set key on non-keyed VectorDiff");
             }
@@ -92,16 +148,99 @@ impl PullableDiff for VectorDiff {
             | VectorDiff::Remove {
                 index: _,
                 snapshot_index,
+            }
+            | VectorDiff::Extend {
+                index: _,
+                snapshot_index,
+                count: _,
             } => {
                 *snapshot_index = new_index;
             }
+            VectorDiff::Move {
+                from: _,
+                to: _,
+                snapshot_from: _,
+                snapshot_to,
+            } => {
+                *snapshot_to = new_index;
+            }
             _ => {
                 panic!("Cannot set key on non-keyed VectorDiff");
             }
         }
     }
 
+    fn update_snapshot_keys<F>(&mut self, updater: F)
+    where
+        F: Fn(&usize) -> usize,
+    {
+        match self {
+            VectorDiff::Move {
+                from: _,
+                to: _,
+                snapshot_from,
+                snapshot_to,
+            } => {
+                *snapshot_from = updater(snapshot_from);
+                *snapshot_to = updater(snapshot_to);
+            }
+            _ => {
+                if let Some(snapshot_index) = self.get_snapshot_key() {
+                    let updated = updater(snapshot_index);
+                    self.set_snapshot_key(updated);
+                }
+            }
+        }
+    }
+
+    fn reindex<F>(&mut self, reindexer: F)
+    where
+        F: Fn(&usize, &usize) -> usize,
+    {
+        match self {
+            VectorDiff::Move {
+                from,
+                to,
+                snapshot_from,
+                snapshot_to,
+            } => {
+                *from = reindexer(from, snapshot_from);
+                *to = reindexer(to, snapshot_to);
+            }
+            _ => {
+                if let (Some(&index), Some(&snapshot_index)) =
+                    (self.get_key(), self.get_snapshot_key())
+                {
+                    self.set_key(reindexer(&index, &snapshot_index));
+                }
+            }
+        }
+    }
+
     fn merge_with_previous(&self, previous: &VectorDiff) -> DiffMergeResult<VectorDiff> {
+        // Moves are never merged. A Move is indexed as if every diff before it had
+        // happened, so dropping one would leave the diffs after it pointing at the
+        // wrong values, even when a later Move looks like its inverse.
+        if let VectorDiff::Move { .. } = self {
+            return DiffMergeResult::keep_both();
+        }
+        if let VectorDiff::Move { .. } = previous {
+            return DiffMergeResult::keep_both();
+        }
+
+        // Batches are never merged. MutableVectorState falls back to a Replace before an
+        // insert or remove could land inside a batch that has not been pulled yet.
+        if let VectorDiff::Extend { .. } = self {
+            return DiffMergeResult::keep_both();
+        }
+        if let VectorDiff::Extend { .. } = previous {
+            // Extend then Update => Extend (the batch reads the updated value)
+            if let VectorDiff::Update { .. } = self {
+                return DiffMergeResult::<VectorDiff>::ignore();
+            }
+            return DiffMergeResult::keep_both();
+        }
+
         if let &VectorDiff::Insert {
             index: _,
             snapshot_index,
@@ -175,9 +314,180 @@ impl PullableDiff for VectorDiff {
     fn full_replace() -> VectorDiff {
         VectorDiff::Replace {}
     }
+
+    fn is_clear(&self) -> bool {
+        matches!(self, VectorDiff::Clear {})
+    }
+}
+
+// Returns where the value at `index` ends up when the value at `from` is moved to `to`.
+pub(crate) fn moved_index(index: usize, from: usize, to: usize) -> usize {
+    if index == from {
+        to
+    } else if from < to && index > from && index <= to {
+        index - 1
+    } else if to < from && index >= to && index < from {
+        index + 1
+    } else {
+        index
+    }
+}
+
+/// Computes a minimal list of Move diffs that rearranges a vector by a permutation, where
+/// `new_positions[i]` is the index that the value currently at index `i` should end up
+/// at. The values along the longest run that is already in order stay where they are,
+/// and every other value is moved exactly once. The diffs are applied in order.
+///
+/// This takes O(n²) time in the worst case for a permutation of length n. Panics if
+/// `new_positions` is not a permutation of `0..n`.
+///
+/// ```
+/// use signals_im::vector::{permutation_to_moves, VectorDiff};
+///
+/// // Rotating [a, b, c] into [b, c, a] only has to move `a`.
+/// let moves = permutation_to_moves(&[2, 0, 1]);
+/// assert_eq!(moves, vec![VectorDiff::Move { from: 0, to: 2, snapshot_from: 0, snapshot_to: 2 }]);
+/// ```
+pub fn permutation_to_moves(new_positions: &[usize]) -> Vec<VectorDiff> {
+    let len = new_positions.len();
+
+    // Find the longest increasing run of new positions. tails[k] is the index of the
+    // smallest position that ends an increasing run of length k + 1.
+    let mut tails: Vec<usize> = vec![];
+    let mut previous = vec![None; len];
+    for (index, &position) in new_positions.iter().enumerate() {
+        let run = match tails.binary_search_by(|&tail| new_positions[tail].cmp(&position)) {
+            Ok(run) | Err(run) => run,
+        };
+        if run > 0 {
+            previous[index] = Some(tails[run - 1]);
+        }
+        if run == tails.len() {
+            tails.push(index);
+        } else {
+            tails[run] = index;
+        }
+    }
+    let mut settled = vec![false; len];
+    let mut next = tails.last().copied();
+    while let Some(index) = next {
+        settled[new_positions[index]] = true;
+        next = previous[index];
+    }
+
+    // The settled values are always in order, so each moved value lands just before the
+    // first settled value that belongs after it.
+    let mut current = new_positions.to_vec();
+    let mut moves = vec![];
+    for position in 0..len {
+        if settled[position] {
+            continue;
+        }
+        let from = current
+            .iter()
+            .position(|&p| p == position)
+            .expect("new_positions must be a permutation");
+        current.remove(from);
+        let to = current
+            .iter()
+            .position(|&p| settled[p] && p > position)
+            .unwrap_or(current.len());
+        current.insert(to, position);
+        settled[position] = true;
+        if from != to {
+            moves.push(VectorDiff::Move {
+                from,
+                to,
+                snapshot_from: from,
+                snapshot_to: to,
+            });
+        }
+    }
+    moves
+}
+
+/// Computes a minimal list of diffs that turns `old` into `new`, based on their longest
+/// common subsequence. The diffs are applied in order and read their values from
+/// `new`, which acts as their snapshot. A removal and an insertion at the same position
+/// are combined into an Update, and emptying the vector is a single Clear.
+///
+/// This takes O(n * m) time and memory for vectors of length n and m.
+///
+/// ```
+/// use im::vector;
+/// use signals_im::vector::{diff_vectors, VectorDiff};
+///
+/// let diffs = diff_vectors(&vector![1, 2, 3], &vector![1, 3, 4]);
+/// assert_eq!(
+///     diffs,
+///     vec![
+///         VectorDiff::Remove { index: 1, snapshot_index: 1 },
+///         VectorDiff::Insert { index: 2, snapshot_index: 2 },
+///     ]
+/// );
+/// ```
+pub fn diff_vectors<T>(old: &Vector<T>, new: &Vector<T>) -> Vec<VectorDiff>
+where
+    T: Clone + PartialEq,
+{
+    if new.is_empty() && !old.is_empty() {
+        return vec![VectorDiff::Clear {}];
+    }
+
+    // common[i][j] is the length of the longest common subsequence of old[i..] and
+    // new[j..].
+    let (old_len, new_len) = (old.len(), new.len());
+    let mut common = vec![vec![0usize; new_len + 1]; old_len + 1];
+    for i in (0..old_len).rev() {
+        for j in (0..new_len).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                max(common[i + 1][j], common[i][j + 1])
+            };
+        }
+    }
+
+    // After each step, the values before index j are exactly new[..j], so j is both
+    // the index to apply the next diff at and its index into the snapshot.
+    let mut diffs = vec![];
+    let (mut i, mut j) = (0, 0);
+    while i < old_len || j < new_len {
+        if i < old_len && j < new_len && old[i] == new[j] {
+            i += 1;
+            j += 1;
+        } else if i < old_len && j < new_len && common[i + 1][j + 1] == common[i][j] {
+            diffs.push(VectorDiff::Update { index: j, snapshot_index: j });
+            i += 1;
+            j += 1;
+        } else if j == new_len || (i < old_len && common[i + 1][j] >= common[i][j + 1]) {
+            diffs.push(VectorDiff::Remove { index: j, snapshot_index: j });
+            i += 1;
+        } else {
+            diffs.push(VectorDiff::Insert { index: j, snapshot_index: j });
+            j += 1;
+        }
+    }
+    diffs
 }
 
 impl VectorDiff {
+    // Returns where a value at the given snapshot index ends up after this diff is
+    // applied, or None if this diff overwrites or removes it.
+    fn move_snapshot_index(&self, snapshot_index: usize) -> Option<usize> {
+        match *self {
+            VectorDiff::Insert { index, .. } if snapshot_index >= index => Some(snapshot_index + 1),
+            VectorDiff::Extend { index, count, .. } if snapshot_index >= index => {
+                Some(snapshot_index + count)
+            }
+            VectorDiff::Update { index, .. } if snapshot_index == index => None,
+            VectorDiff::Remove { index, .. } if snapshot_index == index => None,
+            VectorDiff::Remove { index, .. } if snapshot_index > index => Some(snapshot_index - 1),
+            VectorDiff::Move { from, to, .. } => Some(moved_index(snapshot_index, from, to)),
+            _ => Some(snapshot_index),
+        }
+    }
+
     pub fn get_value_from_snapshot<'a, C>(&self, from_snapshot: &'a C) -> Option<&'a C::Output>
     where
         C: std::ops::Index<usize>,
@@ -192,12 +502,106 @@ impl VectorDiff {
                 index: _,
                 snapshot_index,
             } => Some(&from_snapshot[*snapshot_index]),
+            VectorDiff::Move { snapshot_to, .. } => Some(&from_snapshot[*snapshot_to]),
             _ => None,
         }
     }
 }
 
+/// Like VectorDiff, but carries the values it inserts or updates instead of pointing
+/// into the snapshot of a VectorEvent. This is useful for sending diffs to consumers
+/// that do not keep snapshots around, such as over a network connection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VectorDiffWithValue<T>
+where
+    T: Clone,
+{
+    Replace { values: Vector<T> },
+
+    Insert { index: usize, value: T },
+
+    Update { index: usize, value: T },
+
+    Remove { index: usize },
+
+    Move { from: usize, to: usize },
+
+    Clear {},
+}
+
+impl<T: Clone> VectorDiffWithValue<T> {
+    /// Resolves the values of a VectorDiff from the snapshot of the event it came from.
+    /// Panics on an `Extend`, which has to be split up with `VectorEvent::expand_batches`.
+    pub fn from_diff(diff: &VectorDiff, snapshot: &Vector<T>) -> VectorDiffWithValue<T> {
+        match *diff {
+            VectorDiff::Replace {} => VectorDiffWithValue::Replace {
+                values: snapshot.clone(),
+            },
+            VectorDiff::Insert { index, .. } => VectorDiffWithValue::Insert {
+                index,
+                value: diff.get_value_from_snapshot(snapshot).unwrap().clone(),
+            },
+            VectorDiff::Update { index, .. } => VectorDiffWithValue::Update {
+                index,
+                value: diff.get_value_from_snapshot(snapshot).unwrap().clone(),
+            },
+            VectorDiff::Remove { index, .. } => VectorDiffWithValue::Remove { index },
+            VectorDiff::Extend { .. } => {
+                panic!("Batched diffs must be expanded before resolving their values")
+            }
+            VectorDiff::Move { from, to, .. } => VectorDiffWithValue::Move { from, to },
+            VectorDiff::Clear {} => VectorDiffWithValue::Clear {},
+        }
+    }
+
+    /// Applies this diff to a vector.
+    pub fn apply_to(&self, vector: &mut Vector<T>) {
+        match self {
+            VectorDiffWithValue::Replace { values } => *vector = values.clone(),
+            VectorDiffWithValue::Insert { index, value } => vector.insert(*index, value.clone()),
+            VectorDiffWithValue::Update { index, value } => {
+                vector.set(*index, value.clone());
+            }
+            VectorDiffWithValue::Remove { index } => {
+                vector.remove(*index);
+            }
+            VectorDiffWithValue::Move { from, to } => {
+                let value = vector.remove(*from);
+                vector.insert(*to, value);
+            }
+            VectorDiffWithValue::Clear {} => vector.clear(),
+        }
+    }
+
+    fn without_value(&self) -> VectorDiff {
+        match *self {
+            VectorDiffWithValue::Replace { .. } => VectorDiff::Replace {},
+            VectorDiffWithValue::Insert { index, .. } => VectorDiff::Insert {
+                index,
+                snapshot_index: index,
+            },
+            VectorDiffWithValue::Update { index, .. } => VectorDiff::Update {
+                index,
+                snapshot_index: index,
+            },
+            VectorDiffWithValue::Remove { index } => VectorDiff::Remove {
+                index,
+                snapshot_index: index,
+            },
+            VectorDiffWithValue::Move { from, to } => VectorDiff::Move {
+                from,
+                to,
+                snapshot_from: from,
+                snapshot_to: to,
+            },
+            VectorDiffWithValue::Clear {} => VectorDiff::Clear {},
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VectorEvent<T>
 where
     T: Clone,
@@ -206,6 +610,126 @@ where
     pub diffs: Vec<VectorDiff>,
 }
 
+impl<T: Clone> VectorEvent<T> {
+    /// Creates an event directly from a snapshot and a list of diffs. This is mostly
+    /// useful for feeding hand-crafted events into a transformer during testing.
+    pub fn new(snapshot: Vector<T>, diffs: Vec<VectorDiff>) -> VectorEvent<T> {
+        VectorEvent { snapshot, diffs }
+    }
+}
+
+impl<T: Clone> VectorEvent<T> {
+    /// Converts the diffs of this event into diffs that carry their own values, so
+    /// they can be used without the snapshot.
+    pub fn diffs_with_values(&self) -> Vec<VectorDiffWithValue<T>> {
+        let expanded = self.clone().expand_batches();
+        expanded
+            .diffs
+            .iter()
+            .map(|diff| VectorDiffWithValue::from_diff(diff, &expanded.snapshot))
+            .collect()
+    }
+
+    /// Splits every `Extend` in this event into one Insert per value, for consumers that
+    /// apply values one at a time.
+    pub fn expand_batches(self) -> VectorEvent<T> {
+        if !self.diffs.iter().any(|diff| matches!(diff, VectorDiff::Extend { .. })) {
+            return self;
+        }
+        let diffs = self
+            .diffs
+            .into_iter()
+            .flat_map(|diff| match diff {
+                VectorDiff::Extend {
+                    index,
+                    snapshot_index,
+                    count,
+                } => (0..count)
+                    .map(|offset| VectorDiff::Insert {
+                        index: index + offset,
+                        snapshot_index: snapshot_index + offset,
+                    })
+                    .collect(),
+                diff => vec![diff],
+            })
+            .collect();
+        VectorEvent {
+            snapshot: self.snapshot,
+            diffs,
+        }
+    }
+
+    /// Splits every `Move` in this event into a Remove followed by an Insert, for
+    /// consumers that do not need to keep track of the identity of values.
+    pub fn split_moves(self) -> VectorEvent<T> {
+        if !self.diffs.iter().any(|diff| matches!(diff, VectorDiff::Move { .. })) {
+            return self;
+        }
+        let diffs = self
+            .diffs
+            .into_iter()
+            .flat_map(|diff| match diff {
+                VectorDiff::Move {
+                    from,
+                    to,
+                    snapshot_from,
+                    snapshot_to,
+                } => vec![
+                    VectorDiff::Remove {
+                        index: from,
+                        snapshot_index: snapshot_from,
+                    },
+                    VectorDiff::Insert {
+                        index: to,
+                        snapshot_index: snapshot_to,
+                    },
+                ],
+                diff => vec![diff],
+            })
+            .collect();
+        VectorEvent {
+            snapshot: self.snapshot,
+            diffs,
+        }
+    }
+
+    /// The inverse of `diffs_with_values`. Rebuilds an event by applying value-carrying
+    /// diffs to the snapshot of the event that came before them.
+    pub fn from_diffs_with_values(
+        previous_snapshot: Vector<T>,
+        diffs_with_values: Vec<VectorDiffWithValue<T>>,
+    ) -> VectorEvent<T> {
+        let mut snapshot = previous_snapshot;
+        for diff in diffs_with_values.iter() {
+            diff.apply_to(&mut snapshot);
+        }
+
+        // Each diff's snapshot index has to be carried through the diffs after it to
+        // find its value in the final snapshot, just like when merging events.
+        let index_diffs: Vec<VectorDiff> = diffs_with_values
+            .iter()
+            .map(VectorDiffWithValue::without_value)
+            .collect();
+        let diffs = index_diffs
+            .iter()
+            .enumerate()
+            .map(|(position, diff)| {
+                let mut diff = diff.clone();
+                if let Some(&snapshot_index) = diff.get_snapshot_key() {
+                    let mut moved_index = Some(snapshot_index);
+                    for later_diff in index_diffs[position + 1..].iter() {
+                        moved_index = moved_index.and_then(|i| later_diff.move_snapshot_index(i));
+                    }
+                    diff.set_snapshot_key(moved_index.unwrap_or(0));
+                }
+                diff
+            })
+            .collect();
+
+        VectorEvent { snapshot, diffs }
+    }
+}
+
 impl<T: Clone> SnapshottableEvent for VectorEvent<T> {
     type SnapshotType = Vector<T>;
 
@@ -213,3 +737,67 @@ impl<T: Clone> SnapshottableEvent for VectorEvent<T> {
         self.snapshot.clone()
     }
 }
+
+impl<T: Clone> MergeableEvent for VectorEvent<T> {
+    fn merge(self, next: VectorEvent<T>) -> VectorEvent<T> {
+        let next_is_global = next.diffs.iter().any(|diff| diff.get_key().is_none());
+        let self_is_replace = self.diffs.contains(&VectorDiff::Replace {});
+        if next_is_global || self_is_replace {
+            // Either the next event already describes the full contents, or the replace
+            // will read the full contents from the next snapshot.
+            return VectorEvent {
+                snapshot: next.snapshot,
+                diffs: if next_is_global { next.diffs } else { self.diffs },
+            };
+        }
+        if next.snapshot.is_empty() {
+            return VectorEvent {
+                snapshot: next.snapshot,
+                diffs: vec![VectorDiff::Clear {}],
+            };
+        }
+
+        // The snapshot indices of the earlier diffs point into the earlier snapshot, so
+        // they have to be carried through the later diffs to find the same values in
+        // the later snapshot. Values that get overwritten or removed by a later diff
+        // can point anywhere, since that later diff will fix them up. Batches are
+        // expanded first, since the later diffs may split up their values.
+        let mut diffs: Vec<VectorDiff> = self
+            .expand_batches()
+            .diffs
+            .into_iter()
+            .map(|mut diff| {
+                if let Some(&snapshot_index) = diff.get_snapshot_key() {
+                    let mut moved_index = Some(snapshot_index);
+                    for next_diff in next.diffs.iter() {
+                        moved_index = moved_index.and_then(|i| next_diff.move_snapshot_index(i));
+                    }
+                    diff.set_snapshot_key(moved_index.unwrap_or(0));
+                }
+                diff
+            })
+            .collect();
+        diffs.extend(next.diffs);
+
+        VectorEvent {
+            snapshot: next.snapshot,
+            diffs,
+        }
+    }
+}
+
+impl<T: Clone> SplittableEvent for VectorEvent<T> {
+    fn split(self) -> Vec<VectorEvent<T>> {
+        if self.diffs.len() <= 1 {
+            return vec![self];
+        }
+        let snapshot = self.snapshot;
+        self.diffs
+            .into_iter()
+            .map(|diff| VectorEvent {
+                snapshot: snapshot.clone(),
+                diffs: vec![diff],
+            })
+            .collect()
+    }
+}
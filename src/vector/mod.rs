@@ -4,6 +4,14 @@ mod vector;
 mod vector_transforms;
 mod signal_ext;
 
-pub use event::{VectorDiff, VectorEvent};
+pub use compat::{signal_vec_to_structural, SignalVecStructuralSignal, StructuralSignalVecCompat};
+pub use event::{
+    diff_vectors, permutation_to_moves, VectorDiff, VectorDiffWithValue, VectorEvent,
+};
 pub use vector::{MutableVector, MutableVectorReader, MutableVectorState};
-pub use signal_ext::SignalVectorExt;
\ No newline at end of file
+pub use signal_ext::{SignalVectorExt, SignalVectorIndexWatcher};
+pub use vector_transforms::{
+    ChainVectorSignal, DedupVectorTransformer, DemuxedVectorSignal, Demuxer,
+    FilterVectorTransformer, IndexSignals, IndexValueSignal, OnDiffFuture,
+    SortedByVectorTransformer, VectorFoldSignal, WindowsMapVectorTransformer,
+};
\ No newline at end of file
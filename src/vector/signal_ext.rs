@@ -1,7 +1,87 @@
-use super::{VectorEvent};
-use super::vector_transforms::{MapVectorTransformer};
+use super::compat::StructuralSignalVecCompat;
+use super::event::{VectorDiff, VectorEvent};
+use super::vector_transforms::{
+    ChainVectorSignal, CombineWithVectorSignal, DedupVectorTransformer, Demuxer,
+    FilterVectorTransformer, IdentifyVectorTransformer, IndexSignals, MapVectorTransformer,
+    MapWithIndexVectorTransformer, OnDiffFuture, RangeVectorTransformer,
+    SortedByVectorTransformer, VectorFoldSignal, WindowsMapVectorTransformer,
+};
+use crate::structural_signal::adapters::LenSignal;
 use crate::structural_signal::transformer::TransformedStructuralSignal;
 use crate::StructuralSignal;
+use futures_signals::signal::Signal;
+use im::Vector;
+use pin_project::pin_project;
+use std::cmp::Ordering;
+use std::hash::Hash;
+use std::ops::Range;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+#[pin_project(project = SignalVectorIndexWatcherProj)]
+pub struct SignalVectorIndexWatcher<T, S>
+where
+    T: Clone + PartialEq,
+    S: StructuralSignal<Item = VectorEvent<T>>,
+{
+    #[pin]
+    signal: S,
+    index: usize,
+    last_value: Option<Option<T>>,
+}
+
+// Diffs are applied in order, each at a position in the vector as it was right after
+// the previous diff. So the value at `index` can only change when a diff touches that
+// position or something before it.
+fn diff_can_affect_index(diff: &VectorDiff, index: usize) -> bool {
+    match *diff {
+        VectorDiff::Replace {} | VectorDiff::Clear {} => true,
+        VectorDiff::Insert { index: at, .. }
+        | VectorDiff::Update { index: at, .. }
+        | VectorDiff::Remove { index: at, .. }
+        | VectorDiff::Extend { index: at, .. } => at <= index,
+        VectorDiff::Move { from, to, .. } => from.min(to) <= index,
+    }
+}
+
+impl<T, S> Signal for SignalVectorIndexWatcher<T, S>
+where
+    T: Clone + PartialEq,
+    S: StructuralSignal<Item = VectorEvent<T>>,
+{
+    type Item = Option<T>;
+
+    fn poll_change(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Option<T>>> {
+        let SignalVectorIndexWatcherProj {
+            mut signal,
+            index,
+            last_value,
+        } = self.project();
+
+        loop {
+            match signal.as_mut().poll_change(cx) {
+                Poll::Ready(Some(vector_event)) => {
+                    let is_affected = last_value.is_none()
+                        || vector_event
+                            .diffs
+                            .iter()
+                            .any(|diff| diff_can_affect_index(diff, *index));
+                    if !is_affected {
+                        continue;
+                    }
+
+                    let value = vector_event.snapshot.get(*index).cloned();
+                    if last_value.as_ref() != Some(&value) {
+                        *last_value = Some(value.clone());
+                        return Poll::Ready(Some(value));
+                    }
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
 
 pub trait SignalVectorExt: StructuralSignal
 where
@@ -40,6 +120,502 @@ where
         OV: Clone,
         Self::ValType: Clone,
         F: Fn(&Self::ValType) -> OV;
+
+    /// Like `map`, but the transformer function also receives the current index of
+    /// each value. Note that inserting or removing a value shifts the index of every
+    /// value after it, so those values are all re-mapped and emitted as updates.
+    ///
+    /// ```
+    /// use signals_im::vector::{MutableVector, SignalVectorExt};
+    /// use signals_im::StructuralSignalExt;
+    /// use im::vector;
+    ///
+    /// let input_vec = MutableVector::<u8>::new();
+    /// input_vec.write().push_back(5);
+    /// input_vec.write().push_back(6);
+    ///
+    /// let indexed = input_vec.as_signal().map_with_index(|i, v| (i, *v));
+    /// input_vec.write().push_front(4);
+    ///
+    /// assert_eq!(indexed.snapshot().unwrap(), vector![(0, 4), (1, 5), (2, 6)]);
+    /// ```
+    fn map_with_index<OV, F>(
+        self,
+        map_fn: F,
+    ) -> TransformedStructuralSignal<
+        Self::SelfType,
+        <Self::SelfType as StructuralSignal>::Item,
+        MapWithIndexVectorTransformer<F, Self::ValType, OV>,
+    >
+    where
+        OV: Clone,
+        F: Fn(usize, &Self::ValType) -> OV;
+
+    /// Pairs every value in this vector with its index. Like `map_with_index`, an
+    /// insert or remove in the middle emits updates for the rows after it, rather
+    /// than replacing the whole vector.
+    ///
+    /// ```
+    /// use signals_im::vector::{MutableVector, SignalVectorExt};
+    /// use signals_im::StructuralSignalExt;
+    /// use im::vector;
+    ///
+    /// let input_vec = MutableVector::<char>::new();
+    /// input_vec.write().push_back('a');
+    /// input_vec.write().push_back('c');
+    ///
+    /// let rows = input_vec.as_signal().enumerate();
+    /// input_vec.write().insert(1, 'b');
+    ///
+    /// assert_eq!(rows.snapshot().unwrap(), vector![(0, 'a'), (1, 'b'), (2, 'c')]);
+    /// ```
+    fn enumerate(
+        self,
+    ) -> TransformedStructuralSignal<
+        Self::SelfType,
+        <Self::SelfType as StructuralSignal>::Item,
+        MapWithIndexVectorTransformer<
+            fn(usize, &Self::ValType) -> (usize, Self::ValType),
+            Self::ValType,
+            (usize, Self::ValType),
+        >,
+    >;
+
+    /// Maps every run of `size` consecutive values in this vector, like `slice::windows`.
+    /// Element `i` of the output is `map_fn(&self[i..i + size])`, so the output is
+    /// `size - 1` elements shorter than the input, or empty if the input is shorter
+    /// than `size`.
+    ///
+    /// A change to one input value re-maps every window that contains it, so each
+    /// change causes at most `size` updates in the output. Panics if `size` is 0.
+    ///
+    /// ```
+    /// use signals_im::vector::{MutableVector, SignalVectorExt};
+    /// use signals_im::StructuralSignalExt;
+    /// use im::vector;
+    ///
+    /// let input_vec = MutableVector::<u32>::new();
+    /// input_vec.write().replace(vec![1, 2, 3, 4].into_iter());
+    ///
+    /// let moving_sums = input_vec.as_signal().windows_map(2, |w| w.iter().sum::<u32>());
+    /// assert_eq!(moving_sums.snapshot().unwrap(), vector![3, 5, 7]);
+    /// ```
+    fn windows_map<OV, F>(
+        self,
+        size: usize,
+        map_fn: F,
+    ) -> TransformedStructuralSignal<
+        Self::SelfType,
+        <Self::SelfType as StructuralSignal>::Item,
+        WindowsMapVectorTransformer<F, Self::ValType, OV>,
+    >
+    where
+        OV: Clone,
+        F: Fn(&[Self::ValType]) -> OV;
+
+    /// Returns a version of this signal that only contains the values that pass a
+    /// predicate test, in their original order. Values move in and out of the output
+    /// as updates change whether they pass.
+    ///
+    /// ```
+    /// use signals_im::vector::{MutableVector, SignalVectorExt};
+    /// use signals_im::StructuralSignalExt;
+    /// use im::vector;
+    ///
+    /// let input_vec = MutableVector::<u8>::new();
+    /// input_vec.write().replace(vec![1, 2, 3, 4].into_iter());
+    ///
+    /// let evens = input_vec.as_signal().filter(|v| v % 2 == 0);
+    /// input_vec.write().set(0, 6);
+    ///
+    /// assert_eq!(evens.snapshot().unwrap(), vector![6, 2, 4]);
+    /// ```
+    fn filter<F>(
+        self,
+        predicate: F,
+    ) -> TransformedStructuralSignal<
+        Self::SelfType,
+        <Self::SelfType as StructuralSignal>::Item,
+        FilterVectorTransformer<Self::ValType, F>,
+    >
+    where
+        F: Fn(&Self::ValType) -> bool;
+
+    /// Returns a version of this signal that keeps its values sorted with a comparator
+    /// function. New values are inserted at their sorted position, and updated values
+    /// move if their new value belongs somewhere else. Values that compare equal stay
+    /// in the order they were added in.
+    ///
+    /// ```
+    /// use signals_im::vector::{MutableVector, SignalVectorExt};
+    /// use signals_im::StructuralSignalExt;
+    /// use im::vector;
+    ///
+    /// let input_vec = MutableVector::<u8>::new();
+    /// input_vec.write().replace(vec![3, 1, 2].into_iter());
+    ///
+    /// let descending = input_vec.as_signal().sorted_by(|a, b| b.cmp(a));
+    /// input_vec.write().push_back(4);
+    ///
+    /// assert_eq!(descending.snapshot().unwrap(), vector![4, 3, 2, 1]);
+    /// ```
+    fn sorted_by<F>(
+        self,
+        compare: F,
+    ) -> TransformedStructuralSignal<
+        Self::SelfType,
+        <Self::SelfType as StructuralSignal>::Item,
+        SortedByVectorTransformer<Self::ValType, F>,
+    >
+    where
+        F: Fn(&Self::ValType, &Self::ValType) -> Ordering;
+
+    /// Returns a version of this signal with consecutive equal values collapsed into
+    /// one, like `Vec::dedup`. An update that makes a value equal to the one before it
+    /// removes it from the output, and a remove that brings two equal values together
+    /// collapses them.
+    ///
+    /// ```
+    /// use signals_im::vector::{MutableVector, SignalVectorExt};
+    /// use signals_im::StructuralSignalExt;
+    /// use im::vector;
+    ///
+    /// let input_vec = MutableVector::<u8>::new();
+    /// input_vec.write().replace(vec![1, 1, 2, 3, 2].into_iter());
+    ///
+    /// let transitions = input_vec.as_signal().dedup();
+    /// input_vec.write().remove(3);
+    ///
+    /// assert_eq!(transitions.snapshot().unwrap(), vector![1, 2]);
+    /// ```
+    fn dedup(
+        self,
+    ) -> TransformedStructuralSignal<
+        Self::SelfType,
+        <Self::SelfType as StructuralSignal>::Item,
+        DedupVectorTransformer<Self::ValType>,
+    >
+    where
+        Self::ValType: PartialEq;
+
+    /// Like `sorted_by`, but sorts values by their own ordering.
+    ///
+    /// ```
+    /// use signals_im::vector::{MutableVector, SignalVectorExt};
+    /// use signals_im::StructuralSignalExt;
+    /// use im::vector;
+    ///
+    /// let input_vec = MutableVector::<u8>::new();
+    /// input_vec.write().replace(vec![3, 1, 2].into_iter());
+    ///
+    /// let sorted = input_vec.as_signal().sorted();
+    /// input_vec.write().set(0, 0);
+    ///
+    /// assert_eq!(sorted.snapshot().unwrap(), vector![0, 1, 2]);
+    /// ```
+    fn sorted(
+        self,
+    ) -> TransformedStructuralSignal<
+        Self::SelfType,
+        <Self::SelfType as StructuralSignal>::Item,
+        SortedByVectorTransformer<Self::ValType, fn(&Self::ValType, &Self::ValType) -> Ordering>,
+    >
+    where
+        Self::ValType: Ord;
+
+    /// Pairs every value in this vector with a stable id, computed by the given function.
+    /// This is the starting point for keyed reconciliation, where consumers track each
+    /// element's identity rather than its position.
+    ///
+    /// Ids are not required to be unique, but consumers that reconcile by id will not be
+    /// able to tell elements with the same id apart.
+    ///
+    /// A Remove that is directly followed by an Insert of a value with the same id is
+    /// reported as a Move, followed by an Update with the inserted value.
+    ///
+    /// ```
+    /// use signals_im::vector::{MutableVector, SignalVectorExt};
+    /// use signals_im::StructuralSignalExt;
+    /// use im::vector;
+    ///
+    /// let input_vec = MutableVector::<&str>::new();
+    /// input_vec.write().push_back("a");
+    /// input_vec.write().push_back("bb");
+    ///
+    /// let identified = input_vec.as_signal().identify(|v| v.len());
+    /// assert_eq!(identified.snapshot().unwrap(), vector![(1, "a"), (2, "bb")]);
+    /// ```
+    fn identify<Id, F>(
+        self,
+        id_fn: F,
+    ) -> TransformedStructuralSignal<
+        Self::SelfType,
+        <Self::SelfType as StructuralSignal>::Item,
+        IdentifyVectorTransformer<F, Self::ValType, Id>,
+    >
+    where
+        Id: Clone + Eq + Hash,
+        F: Fn(&Self::ValType) -> Id;
+
+    /// Returns a signal that only tracks the elements within `[start, end)` of this
+    /// vector, re-based so that `start` is index 0. Inserts and removes before the
+    /// window shift its contents and are re-emitted as changes to the window. Panics if
+    /// `start` is greater than `end`.
+    ///
+    /// ```
+    /// use signals_im::vector::{MutableVector, SignalVectorExt};
+    /// use signals_im::StructuralSignalExt;
+    /// use im::vector;
+    ///
+    /// let input_vec = MutableVector::<u8>::new();
+    /// input_vec.write().replace(vec![0, 1, 2, 3, 4].into_iter());
+    ///
+    /// let window = input_vec.as_signal().get_signal_for_range(1..3);
+    /// input_vec.write().push_front(9);
+    ///
+    /// assert_eq!(window.snapshot().unwrap(), vector![0, 1]);
+    /// ```
+    fn get_signal_for_range(
+        self,
+        range: Range<usize>,
+    ) -> TransformedStructuralSignal<
+        Self::SelfType,
+        <Self::SelfType as StructuralSignal>::Item,
+        RangeVectorTransformer<Self::ValType>,
+    >;
+
+    /// Returns a Signal that tracks the value at a particular index, or None while the
+    /// vector is too short to have one. Inserting or removing values before the index
+    /// shifts a different value into it, but the Signal only fires when the value at
+    /// the index actually differs from the one it last emitted.
+    ///
+    /// ```
+    /// use futures::task::noop_waker_ref;
+    /// use futures_signals::signal::Signal;
+    /// use signals_im::vector::{MutableVector, SignalVectorExt};
+    /// use std::pin::Pin;
+    /// use std::task::{Context, Poll};
+    ///
+    /// let input_vec = MutableVector::<u8>::new();
+    /// input_vec.write().replace(vec![1, 2, 2].into_iter());
+    ///
+    /// let mut second = input_vec.as_signal().get_signal_for_index(1);
+    /// let mut cx = Context::from_waker(noop_waker_ref());
+    /// assert_eq!(Pin::new(&mut second).poll_change(&mut cx), Poll::Ready(Some(Some(2))));
+    ///
+    /// input_vec.write().remove(0);
+    /// assert_eq!(Pin::new(&mut second).poll_change(&mut cx), Poll::Pending);
+    /// ```
+    fn get_signal_for_index(
+        self,
+        index: usize,
+    ) -> SignalVectorIndexWatcher<Self::ValType, Self::SelfType>
+    where
+        Self::ValType: PartialEq;
+
+    /// Combines this vector with another, element by element. Element `i` of the output
+    /// is `combine_fn(&self[i], &other[i])`, and the output is as long as the shorter of
+    /// the two inputs. A change on either side re-combines every index it affects.
+    ///
+    /// To combine a signal with something derived from itself, `broadcast` it first and
+    /// pass two of its signals.
+    ///
+    /// ```
+    /// use signals_im::vector::{MutableVector, SignalVectorExt};
+    /// use signals_im::StructuralSignalExt;
+    /// use im::vector;
+    ///
+    /// let prices = MutableVector::<u32>::new();
+    /// prices.write().replace(vec![10, 20, 30].into_iter());
+    /// let quantities = MutableVector::<u32>::new();
+    /// quantities.write().replace(vec![1, 2].into_iter());
+    ///
+    /// let totals = prices.as_signal().combine_with(quantities.as_signal(), |p, q| p * q);
+    /// assert_eq!(totals.snapshot().unwrap(), vector![10, 40]);
+    /// ```
+    fn combine_with<R, O, F, RS>(
+        self,
+        other: RS,
+        combine_fn: F,
+    ) -> CombineWithVectorSignal<Self::SelfType, RS, F, Self::ValType, R, O>
+    where
+        RS: StructuralSignal<Item = VectorEvent<R>>,
+        R: Clone,
+        O: Clone,
+        F: Fn(&Self::ValType, &R) -> O;
+
+    /// Concatenates this vector with another: the output holds every element of this
+    /// vector followed by every element of `other`. Diffs from `other` are shifted past
+    /// the end of this vector, and a Replace from either side rebuilds the output.
+    ///
+    /// ```
+    /// use signals_im::vector::{MutableVector, SignalVectorExt};
+    /// use signals_im::StructuralSignalExt;
+    /// use im::vector;
+    ///
+    /// let pinned = MutableVector::<u8>::new();
+    /// pinned.write().push_back(1);
+    /// let normal = MutableVector::<u8>::new();
+    /// normal.write().replace(vec![5, 6].into_iter());
+    ///
+    /// let all = pinned.as_signal().chain(normal.as_signal());
+    /// pinned.write().push_back(2);
+    /// assert_eq!(all.snapshot().unwrap(), vector![1, 2, 5, 6]);
+    /// ```
+    fn chain<S2>(self, other: S2) -> ChainVectorSignal<Self::SelfType, S2, Self::ValType>
+    where
+        S2: StructuralSignal<Item = VectorEvent<Self::ValType>>;
+
+    /// Splits this vector into one vector signal per discriminant, as computed by the
+    /// given function. This is useful for lists of enums where each variant is shown
+    /// separately. Each output keeps its elements in the same relative order as the
+    /// input, and an element whose discriminant changes moves between outputs.
+    ///
+    /// Outputs are created lazily by `Demuxer::get`, so discriminants that only show
+    /// up later can still be subscribed to ahead of time.
+    ///
+    /// ```
+    /// use signals_im::vector::{MutableVector, SignalVectorExt};
+    /// use signals_im::StructuralSignalExt;
+    /// use im::vector;
+    ///
+    /// let input_vec = MutableVector::<u8>::new();
+    /// input_vec.write().replace(vec![1, 2, 3, 4].into_iter());
+    ///
+    /// let demuxer = input_vec.as_signal().demux(|v| v % 2 == 0);
+    /// assert_eq!(demuxer.get(true).snapshot().unwrap(), vector![2, 4]);
+    /// assert_eq!(demuxer.get(false).snapshot().unwrap(), vector![1, 3]);
+    /// ```
+    fn demux<D, F>(
+        self,
+        discriminant_fn: F,
+    ) -> Demuxer<Self::SelfType, D, Self::ValType, F>
+    where
+        Self::SelfType: Unpin,
+        D: Clone + Eq + Hash,
+        F: Fn(&Self::ValType) -> D;
+
+    /// Creates a structure that hands out a Signal for the value at each index of this
+    /// vector. Each Signal tracks a position rather than an element, so it also fires
+    /// when an insert or remove before it shifts a different value into its index.
+    ///
+    /// ```
+    /// use futures::task::noop_waker_ref;
+    /// use futures_signals::signal::Signal;
+    /// use signals_im::vector::{MutableVector, SignalVectorExt};
+    /// use std::pin::Pin;
+    /// use std::task::{Context, Poll};
+    ///
+    /// let input_vec = MutableVector::<u8>::new();
+    /// input_vec.write().replace(vec![1, 2, 3].into_iter());
+    ///
+    /// let index_signals = input_vec.as_signal().index_signals();
+    /// let mut second = index_signals.signal_for_index(1);
+    /// let mut cx = Context::from_waker(noop_waker_ref());
+    /// assert_eq!(Pin::new(&mut second).poll_change(&mut cx), Poll::Ready(Some(2)));
+    ///
+    /// input_vec.write().remove(0);
+    /// assert_eq!(Pin::new(&mut second).poll_change(&mut cx), Poll::Ready(Some(3)));
+    /// ```
+    fn index_signals(self) -> IndexSignals<Self::SelfType, Self::ValType>
+    where
+        Self::SelfType: Unpin;
+
+    /// Converts this signal into a `futures_signals` SignalVec, so that it can be fed
+    /// into code that consumes SignalVecs, such as dominator UIs. The first VecDiff is
+    /// always a Replace describing the initial contents.
+    ///
+    /// ```
+    /// use futures::task::noop_waker_ref;
+    /// use futures_signals::signal_vec::{SignalVec, VecDiff};
+    /// use signals_im::vector::{MutableVector, SignalVectorExt};
+    /// use std::pin::Pin;
+    /// use std::task::{Context, Poll};
+    ///
+    /// let input_vec = MutableVector::<u8>::new();
+    /// input_vec.write().push_back(1);
+    ///
+    /// let mut signal_vec = input_vec.as_signal().to_signal_vec();
+    /// let mut cx = Context::from_waker(noop_waker_ref());
+    /// assert_eq!(
+    ///     Pin::new(&mut signal_vec).poll_vec_change(&mut cx),
+    ///     Poll::Ready(Some(VecDiff::Replace { values: vec![1] }))
+    /// );
+    /// ```
+    fn to_signal_vec(self) -> StructuralSignalVecCompat<Self::SelfType, Self::ValType>
+    where
+        Self::SelfType: Unpin;
+
+    /// Returns a Signal of an aggregate of the values in this vector, such as their sum
+    /// or maximum. It fires with a new aggregate after every change, and a Clear resets
+    /// it to `init`.
+    ///
+    /// The aggregate is currently recomputed by folding the whole vector after each
+    /// change, rather than being updated from the diffs.
+    ///
+    /// ```
+    /// use futures_signals::signal::SignalExt;
+    /// use signals_im::vector::{MutableVector, SignalVectorExt};
+    ///
+    /// let input_vec = MutableVector::<u32>::new();
+    /// input_vec.write().replace(vec![1, 2, 3].into_iter());
+    /// let sums = input_vec.as_signal().fold(0, |sum, v| sum + v).to_stream();
+    ///
+    /// let sums: Vec<u32> = futures_executor::block_on_stream(sums).take(1).collect();
+    /// assert_eq!(sums, vec![6]);
+    /// ```
+    fn fold<B, F>(self, init: B, fold_fn: F) -> VectorFoldSignal<Self::SelfType, B, F>
+    where
+        B: Clone,
+        F: Fn(B, &Self::ValType) -> B;
+
+    /// Returns a Signal of the length of this vector. It fires with the initial length,
+    /// and then only when the length changes, so updates to existing values never
+    /// cause it to fire.
+    ///
+    /// ```
+    /// use futures_signals::signal::SignalExt;
+    /// use signals_im::vector::{MutableVector, SignalVectorExt};
+    ///
+    /// let input_vec = MutableVector::<u8>::new();
+    /// let lengths = input_vec.as_signal().len_signal().to_stream();
+    /// input_vec.write().push_back(1);
+    ///
+    /// let lengths: Vec<usize> = futures_executor::block_on_stream(lengths).take(1).collect();
+    /// assert_eq!(lengths, vec![1]);
+    /// ```
+    fn len_signal(self) -> LenSignal<Self::SelfType>;
+
+    /// Returns a Future that drives this signal and calls the given function once for
+    /// every individual diff, in order. This is finer grained than handling whole events,
+    /// which suits imperative integrations that sync each element somewhere else.
+    ///
+    /// The snapshot passed alongside each diff is the one from the end of its event, so
+    /// values should be read with `VectorDiff::get_value_from_snapshot`.
+    ///
+    /// ```
+    /// use futures::channel::mpsc;
+    /// use futures_executor::block_on;
+    /// use signals_im::vector::{SignalVectorExt, VectorDiff, VectorEvent};
+    /// use signals_im::ChannelStructuralSignal;
+    /// use im::vector;
+    ///
+    /// let (sender, receiver) = mpsc::unbounded();
+    /// sender.unbounded_send(VectorEvent::new(vector![1, 2], vec![
+    ///     VectorDiff::Insert { index: 0, snapshot_index: 1 },
+    ///     VectorDiff::Insert { index: 0, snapshot_index: 0 },
+    /// ])).unwrap();
+    /// drop(sender);
+    ///
+    /// let mut values = vec![];
+    /// block_on(ChannelStructuralSignal::new(receiver).on_diff(|diff, snapshot| {
+    ///     values.extend(diff.get_value_from_snapshot(snapshot).cloned());
+    /// }));
+    /// assert_eq!(values, vec![2, 1]);
+    /// ```
+    fn on_diff<F>(self, diff_fn: F) -> OnDiffFuture<Self::SelfType, F>
+    where
+        F: FnMut(&VectorDiff, &Vector<Self::ValType>);
 }
 
 impl<T, I> SignalVectorExt for I
@@ -66,4 +642,184 @@ where
     {
         TransformedStructuralSignal::new(self, MapVectorTransformer::new(map_fn))
     }
-}
\ No newline at end of file
+
+    fn map_with_index<OV, F>(
+        self,
+        map_fn: F,
+    ) -> TransformedStructuralSignal<
+        Self,
+        Self::Item,
+        MapWithIndexVectorTransformer<F, Self::ValType, OV>,
+    >
+    where
+        OV: Clone,
+        F: Fn(usize, &Self::ValType) -> OV,
+    {
+        TransformedStructuralSignal::new(self, MapWithIndexVectorTransformer::new(map_fn))
+    }
+
+    fn enumerate(
+        self,
+    ) -> TransformedStructuralSignal<
+        Self,
+        Self::Item,
+        MapWithIndexVectorTransformer<fn(usize, &T) -> (usize, T), T, (usize, T)>,
+    > {
+        let enumerate_fn: fn(usize, &T) -> (usize, T) = |index, value| (index, value.clone());
+        self.map_with_index(enumerate_fn)
+    }
+
+    fn windows_map<OV, F>(
+        self,
+        size: usize,
+        map_fn: F,
+    ) -> TransformedStructuralSignal<
+        Self,
+        Self::Item,
+        WindowsMapVectorTransformer<F, Self::ValType, OV>,
+    >
+    where
+        OV: Clone,
+        F: Fn(&[Self::ValType]) -> OV,
+    {
+        TransformedStructuralSignal::new(self, WindowsMapVectorTransformer::new(size, map_fn))
+    }
+
+    fn filter<F>(
+        self,
+        predicate: F,
+    ) -> TransformedStructuralSignal<Self, Self::Item, FilterVectorTransformer<Self::ValType, F>>
+    where
+        F: Fn(&Self::ValType) -> bool,
+    {
+        TransformedStructuralSignal::new(self, FilterVectorTransformer::new(predicate))
+    }
+
+    fn sorted_by<F>(
+        self,
+        compare: F,
+    ) -> TransformedStructuralSignal<Self, Self::Item, SortedByVectorTransformer<Self::ValType, F>>
+    where
+        F: Fn(&Self::ValType, &Self::ValType) -> Ordering,
+    {
+        TransformedStructuralSignal::new(self, SortedByVectorTransformer::new(compare))
+    }
+
+    fn sorted(
+        self,
+    ) -> TransformedStructuralSignal<
+        Self,
+        Self::Item,
+        SortedByVectorTransformer<T, fn(&T, &T) -> Ordering>,
+    >
+    where
+        T: Ord,
+    {
+        let compare_fn: fn(&T, &T) -> Ordering = Ord::cmp;
+        self.sorted_by(compare_fn)
+    }
+
+    fn dedup(self) -> TransformedStructuralSignal<Self, Self::Item, DedupVectorTransformer<T>>
+    where
+        T: PartialEq,
+    {
+        TransformedStructuralSignal::new(self, DedupVectorTransformer::new())
+    }
+
+    fn identify<Id, F>(
+        self,
+        id_fn: F,
+    ) -> TransformedStructuralSignal<
+        Self,
+        Self::Item,
+        IdentifyVectorTransformer<F, Self::ValType, Id>,
+    >
+    where
+        Id: Clone + Eq + Hash,
+        F: Fn(&Self::ValType) -> Id,
+    {
+        TransformedStructuralSignal::new(self, IdentifyVectorTransformer::new(id_fn))
+    }
+
+    fn get_signal_for_range(
+        self,
+        range: Range<usize>,
+    ) -> TransformedStructuralSignal<Self, Self::Item, RangeVectorTransformer<Self::ValType>>
+    {
+        TransformedStructuralSignal::new(self, RangeVectorTransformer::new(range))
+    }
+
+    fn get_signal_for_index(self, index: usize) -> SignalVectorIndexWatcher<Self::ValType, Self>
+    where
+        Self::ValType: PartialEq,
+    {
+        SignalVectorIndexWatcher {
+            signal: self,
+            index,
+            last_value: None,
+        }
+    }
+
+    fn combine_with<R, O, F, RS>(
+        self,
+        other: RS,
+        combine_fn: F,
+    ) -> CombineWithVectorSignal<Self, RS, F, Self::ValType, R, O>
+    where
+        RS: StructuralSignal<Item = VectorEvent<R>>,
+        R: Clone,
+        O: Clone,
+        F: Fn(&Self::ValType, &R) -> O,
+    {
+        CombineWithVectorSignal::new(self, other, combine_fn)
+    }
+
+    fn chain<S2>(self, other: S2) -> ChainVectorSignal<Self, S2, Self::ValType>
+    where
+        S2: StructuralSignal<Item = VectorEvent<Self::ValType>>,
+    {
+        ChainVectorSignal::new(self, other)
+    }
+
+    fn demux<D, F>(self, discriminant_fn: F) -> Demuxer<Self, D, Self::ValType, F>
+    where
+        Self: Unpin,
+        D: Clone + Eq + Hash,
+        F: Fn(&Self::ValType) -> D,
+    {
+        Demuxer::new(self, discriminant_fn)
+    }
+
+    fn index_signals(self) -> IndexSignals<Self, Self::ValType>
+    where
+        Self: Unpin,
+    {
+        IndexSignals::new(self)
+    }
+
+    fn to_signal_vec(self) -> StructuralSignalVecCompat<Self, Self::ValType>
+    where
+        Self: Unpin,
+    {
+        StructuralSignalVecCompat::from(self)
+    }
+
+    fn fold<B, F>(self, init: B, fold_fn: F) -> VectorFoldSignal<Self, B, F>
+    where
+        B: Clone,
+        F: Fn(B, &Self::ValType) -> B,
+    {
+        VectorFoldSignal::new(self, init, fold_fn)
+    }
+
+    fn len_signal(self) -> LenSignal<Self> {
+        LenSignal::new(self, |event| event.snapshot.len())
+    }
+
+    fn on_diff<F>(self, diff_fn: F) -> OnDiffFuture<Self, F>
+    where
+        F: FnMut(&VectorDiff, &Vector<Self::ValType>),
+    {
+        OnDiffFuture::new(self, diff_fn)
+    }
+}
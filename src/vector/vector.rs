@@ -1,14 +1,19 @@
-use super::event::{VectorDiff, VectorEvent};
+use super::event::{moved_index, permutation_to_moves, VectorDiff, VectorEvent};
+use crate::structural_signal::adapters::DirtySignal;
 use crate::structural_signal::pull_source::{
-    PullSourceHost, PullSourceStructuralSignal, StructrualSignalPullSource,
+    PullSourceHost, PullSourceStructuralSignal, PullableDiff, StructrualSignalPullSource,
 };
+use futures_signals::signal::{Signal, SignalExt};
 use im::Vector;
 use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
-use std::cmp::max;
+use std::cmp::{max, min, Ordering};
 use std::convert::TryInto;
+use std::fmt::{self, Debug, Formatter};
+use std::future::Future;
 use std::iter::FromIterator;
 use std::iter::Iterator;
 use std::ops::{Deref, Index};
+use std::panic::{catch_unwind, resume_unwind, AssertUnwindSafe};
 use std::slice::SliceIndex;
 use std::sync::Arc;
 
@@ -22,6 +27,17 @@ use std::sync::Arc;
 pub struct MutableVectorState<T: Clone> {
     vector: Vector<T>,
     pull_source: StructrualSignalPullSource<VectorDiff>,
+    pending_batches: Vec<PendingBatch>,
+    // The number of the most recent Move diff, until every signal has pulled it.
+    pending_move: Option<usize>,
+}
+
+// An Extend diff that some signal has yet to pull, and where its values currently are.
+#[derive(Debug, Clone, Copy)]
+struct PendingBatch {
+    diff_number: usize,
+    start: usize,
+    count: usize,
 }
 
 impl<T: Clone> PullSourceHost for MutableVectorState<T> {
@@ -38,22 +54,64 @@ impl<T: Clone> PullSourceHost for MutableVectorState<T> {
             diffs: diffs,
         }
     }
+
+    fn make_granular_diffs(&self) -> Vec<Self::DiffType> {
+        (0..self.vector.len())
+            .map(|index| VectorDiff::Insert {
+                index,
+                snapshot_index: index,
+            })
+            .collect()
+    }
 }
 
 impl<T: Clone> MutableVectorState<T> {
     #[inline]
     fn add_diff(&mut self, diff: VectorDiff) {
+        if diff.get_key().is_none() {
+            self.pending_batches.clear();
+            self.pending_move = None;
+        }
         self.pull_source.add_diff(diff);
     }
 
+    // An Extend reads its values from a contiguous run of the snapshot, so a single
+    // insert or remove inside a batch that has not been pulled yet would scatter them.
+    // Such changes are emitted as a Replace instead.
+    fn splits_pending_batch(&mut self, index: usize, is_insert: bool) -> bool {
+        let pull_source = &self.pull_source;
+        self.pending_batches
+            .retain(|batch| !pull_source.is_pulled_by_all(batch.diff_number));
+        self.pending_batches.iter().any(|batch| {
+            let first = if is_insert { batch.start + 1 } else { batch.start };
+            index >= first && index < batch.start + batch.count
+        })
+    }
+
+    // Moves are never merged, so a remove after a Move that has not been pulled yet
+    // could leave earlier diffs reading values that are no longer in the snapshot.
+    // Such removes are emitted as a Replace instead.
+    fn follows_pending_move(&mut self) -> bool {
+        if let Some(diff_number) = self.pending_move {
+            if self.pull_source.is_pulled_by_all(diff_number) {
+                self.pending_move = None;
+            }
+        }
+        self.pending_move.is_some()
+    }
+
     fn shift_diff_indices(&mut self, fulcrum: usize, delta: isize) {
-        self.pull_source.update_keys(|index| {
-            if *index >= fulcrum {
-                max((*index as isize) + delta, 0) as usize
+        let shift = |index: usize| {
+            if index >= fulcrum {
+                max((index as isize) + delta, 0) as usize
             } else {
-                *index
+                index
             }
-        })
+        };
+        self.pull_source.update_keys(|index| shift(*index));
+        for batch in self.pending_batches.iter_mut() {
+            batch.start = shift(batch.start);
+        }
     }
 }
 
@@ -69,6 +127,51 @@ impl<T: Clone> Clone for MutableVector<T> {
             0: Arc::new(RwLock::new(MutableVectorState {
                 vector: self.0.read().vector.clone(),
                 pull_source: StructrualSignalPullSource::new(),
+                pending_batches: Vec::new(),
+                pending_move: None,
+            })),
+        }
+    }
+}
+
+/// Compares the current contents of two Vectors, taking a read lock on each in turn.
+/// This says nothing about whether the two share state or signals.
+impl<T: Clone + PartialEq> PartialEq for MutableVector<T> {
+    fn eq(&self, other: &MutableVector<T>) -> bool {
+        if Arc::ptr_eq(&self.0, &other.0) {
+            return true;
+        }
+        // Snapshot one side first so that both locks are never held at once.
+        let snapshot = self.0.read().vector.clone();
+        snapshot == other.0.read().vector
+    }
+}
+
+/// Compares the current contents of this Vector with a plain `im::Vector`.
+impl<T: Clone + PartialEq> PartialEq<Vector<T>> for MutableVector<T> {
+    fn eq(&self, other: &Vector<T>) -> bool {
+        self.0.read().vector == *other
+    }
+}
+
+impl<T: Clone + Debug> Debug for MutableVector<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("MutableVector")
+            .field(&self.0.read().vector)
+            .finish()
+    }
+}
+
+impl<T: Clone> FromIterator<T> for MutableVector<T> {
+    /// Creates a MutableVector that starts out holding the given values. No diffs are
+    /// emitted, since nothing can be listening yet.
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        MutableVector {
+            0: Arc::new(RwLock::new(MutableVectorState {
+                vector: iter.into_iter().collect(),
+                pull_source: StructrualSignalPullSource::new(),
+                pending_batches: Vec::new(),
+                pending_move: None,
             })),
         }
     }
@@ -89,15 +192,73 @@ impl<T: Clone> MutableVector<T> {
         self.0.write()
     }
 
+    /// Runs a function that makes several changes to this Vector as one atomic batch.
+    /// The function can call `write` as many times as it needs to. Signals cannot pull
+    /// any of the changes until it returns, so they never see the Vector half-way
+    /// through, and they receive all of the changes in a single event. Batches can be
+    /// nested, and the changes are only released when the outermost batch ends.
+    ///
+    /// ```
+    /// use futures::task::noop_waker_ref;
+    /// use signals_im::vector::MutableVector;
+    /// use signals_im::StructuralSignal;
+    /// use std::pin::Pin;
+    /// use std::task::Context;
+    ///
+    /// let input_vec = MutableVector::<u8>::new();
+    /// let mut signal = input_vec.as_signal();
+    /// let mut cx = Context::from_waker(noop_waker_ref());
+    /// assert!(Pin::new(&mut signal).poll_change(&mut cx).is_ready());
+    ///
+    /// input_vec.batch(|| {
+    ///     input_vec.write().push_back(1);
+    ///     assert!(Pin::new(&mut signal).poll_change(&mut cx).is_pending());
+    ///     input_vec.write().push_back(2);
+    /// });
+    /// assert!(Pin::new(&mut signal).poll_change(&mut cx).is_ready());
+    /// ```
+    pub fn batch<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        self.write().pull_source.begin_batch();
+        let result = catch_unwind(AssertUnwindSafe(f));
+        self.write().pull_source.end_batch();
+        match result {
+            Ok(value) => value,
+            Err(panic) => resume_unwind(panic),
+        }
+    }
+
     pub fn new() -> Self {
         MutableVector {
             0: Arc::new(RwLock::new(MutableVectorState {
                 vector: Vector::new(),
                 pull_source: StructrualSignalPullSource::new(),
+                pending_batches: Vec::new(),
+                pending_move: None,
             })),
         }
     }
 
+    /// Replaces the contents of this Vector with the current contents of another
+    /// MutableVector. Unlike cloning, existing readers and signals of this Vector
+    /// stay attached and will see the new contents. Like `replace`, copying an empty
+    /// Vector into an empty Vector does nothing.
+    pub fn set_contents_from(&self, other: &MutableVector<T>) {
+        let contents = other.read().snapshot();
+        self.write().replace(contents.into_iter());
+    }
+
+    /// Consumes this MutableVector and returns its backing Vector without cloning it.
+    /// This only succeeds if no other readers or signals share the underlying
+    /// state, otherwise the original MutableVector is returned unchanged.
+    pub fn try_into_inner(self) -> Result<Vector<T>, Self> {
+        Arc::try_unwrap(self.0)
+            .map(|state| state.into_inner().vector)
+            .map_err(MutableVector)
+    }
+
     /// Creates a read-only view into this data structure. This Reader object
     /// can lookup items in this map at any time, but cannot modify it.
     /// Readers can be cloned. Note that Readers can hold a ReadLock which can
@@ -113,6 +274,74 @@ impl<T: Clone> MutableVector<T> {
     pub fn as_signal(&self) -> PullSourceStructuralSignal<MutableVectorState<T>> {
         PullSourceStructuralSignal::new(self.0.clone())
     }
+
+    /// Like `as_signal`, but the first event describes the current contents of this
+    /// Vector as one Insert diff per item rather than as a single Replace diff.
+    /// If it is empty, the first event is still a single Replace diff.
+    #[inline]
+    pub fn as_signal_granular(&self) -> PullSourceStructuralSignal<MutableVectorState<T>> {
+        PullSourceStructuralSignal::new_granular(self.0.clone())
+    }
+
+    /// Creates a Signal that fires once for each batch of changes made to this Vector
+    /// between polls, as well as once initially. It carries no data, which makes it
+    /// the cheapest way to find out that something changed. Pair it with `read` to
+    /// get the current contents.
+    #[inline]
+    pub fn dirty_signal(&self) -> DirtySignal<PullSourceStructuralSignal<MutableVectorState<T>>> {
+        DirtySignal::new(self.as_signal())
+    }
+}
+
+impl<T: Clone> MutableVector<T> {
+    /// Creates a MutableVector that follows a `futures_signals` Signal of whole vectors,
+    /// such as the signal of a `Mutable<im::Vector<T>>`. Each new value replaces the
+    /// entire contents of the MutableVector. The returned Future applies the changes
+    /// and must be spawned or awaited. It completes when the input Signal ends.
+    pub fn from_mutable_signal<S>(signal: S) -> (MutableVector<T>, impl Future<Output = ()>)
+    where
+        S: Signal<Item = Vector<T>>,
+    {
+        let vector = MutableVector::new();
+        let state = vector.0.clone();
+        let future = signal.for_each(move |contents| {
+            let mut writer = state.write();
+            writer.vector = contents;
+            writer.add_diff(VectorDiff::Replace {});
+            async {}
+        });
+        (vector, future)
+    }
+}
+
+impl<T: Clone + PartialEq> MutableVector<T> {
+    /// Like `from_mutable_signal`, but each new value is compared against the current
+    /// contents so that only the items that actually changed emit diffs.
+    pub fn from_mutable_signal_diffed<S>(
+        signal: S,
+    ) -> (MutableVector<T>, impl Future<Output = ()>)
+    where
+        S: Signal<Item = Vector<T>>,
+    {
+        let vector = MutableVector::new();
+        let state = vector.0.clone();
+        let future = signal.for_each(move |contents| {
+            let mut writer = state.write();
+            let shared_len = min(writer.len(), contents.len());
+            while writer.len() > contents.len() {
+                writer.pop_back();
+            }
+            for (index, value) in contents.into_iter().enumerate() {
+                if index < shared_len {
+                    writer.set_if_changed(index, value);
+                } else {
+                    writer.push_back(value);
+                }
+            }
+            async {}
+        });
+        (vector, future)
+    }
 }
 
 /// A read-only view into a MutableVector.
@@ -139,6 +368,14 @@ impl<T: Clone> MutableVectorReader<T> {
     pub fn as_signal(&self) -> PullSourceStructuralSignal<MutableVectorState<T>> {
         PullSourceStructuralSignal::new(self.0.clone())
     }
+
+    /// Like `as_signal`, but the first event describes the current contents of this
+    /// Vector as one Insert diff per item rather than as a single Replace diff.
+    /// If it is empty, the first event is still a single Replace diff.
+    #[inline]
+    pub fn as_signal_granular(&self) -> PullSourceStructuralSignal<MutableVectorState<T>> {
+        PullSourceStructuralSignal::new_granular(self.0.clone())
+    }
 }
 
 impl<T: Clone, I> Index<I> for MutableVectorState<T>
@@ -159,10 +396,10 @@ impl<T: Clone> MutableVectorState<T> {
         self.vector.len()
     }
 
-    /// Gets the current value at a given index. Throws if the given
-    /// index is not currently in the vector.
-    pub fn get(&self, index: usize) -> &T {
-        &self[index]
+    /// Gets the current value at a given index, or None if the index is not currently
+    /// in the vector. Index the state directly, as in `state[index]`, to panic instead.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.vector.get(index)
     }
 
     /// Creates an immutable snapshot of the current state of this Vector. This
@@ -174,13 +411,18 @@ impl<T: Clone> MutableVectorState<T> {
     }
 
     /// Replaces the entire contents of this Vector with new entries. All existing
-    /// data will be cleared.
+    /// data will be cleared. Like `clear`, replacing an empty Vector with no entries
+    /// does nothing.
     pub fn replace<E>(&mut self, entries: E)
     where
         E: Iterator<Item = T>,
     {
+        let was_empty = self.vector.is_empty();
         self.vector.clear();
         self.vector.append(Vector::from_iter(entries));
+        if was_empty && self.vector.is_empty() {
+            return;
+        }
         self.add_diff(VectorDiff::Replace {});
     }
 
@@ -200,6 +442,10 @@ impl<T: Clone> MutableVectorState<T> {
     /// index is not currently in the vector.
     pub fn insert(&mut self, index: usize, value: T) {
         let result = self.vector.insert(index, value);
+        if self.splits_pending_batch(index, true) {
+            self.add_diff(VectorDiff::Replace {});
+            return result;
+        }
 
         self.shift_diff_indices(index, 1);
         self.add_diff(VectorDiff::Insert {
@@ -221,15 +467,70 @@ impl<T: Clone> MutableVectorState<T> {
         return result;
     }
 
+    /// Appends every item from an iterator to the end of this Vector. Unlike calling
+    /// `push_back` repeatedly, this emits a single `VectorDiff::Extend`.
+    pub fn extend<I>(&mut self, items: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let index = self.vector.len();
+        self.vector.extend(items);
+        let count = self.vector.len() - index;
+        if count == 0 {
+            return;
+        }
+
+        self.add_diff(VectorDiff::Extend {
+            index,
+            snapshot_index: index,
+            count,
+        });
+        if self.pull_source.has_listening_signal() {
+            self.pending_batches.push(PendingBatch {
+                diff_number: self.pull_source.last_diff_number(),
+                start: index,
+                count,
+            });
+        }
+    }
+
     /// Inserts a new item at the front of this Vector.
     pub fn push_front(&mut self, value: T) {
         self.insert(0, value)
     }
 
+    /// Inserts every item from an iterator at the front of this Vector, keeping their
+    /// order. Unlike calling `push_front` repeatedly, pending diffs are only shifted
+    /// once, which keeps prepending many items linear.
+    pub fn prepend<I>(&mut self, items: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut prepended = Vector::from_iter(items);
+        let count = prepended.len();
+        if count == 0 {
+            return;
+        }
+
+        prepended.append(std::mem::take(&mut self.vector));
+        self.vector = prepended;
+        self.shift_diff_indices(0, count as isize);
+        for index in 0..count {
+            self.add_diff(VectorDiff::Insert {
+                index,
+                snapshot_index: index,
+            });
+        }
+    }
+
     /// Removes and returns the item at a given index. Throws if the given
     /// index is not currently in the vector.
     pub fn remove(&mut self, index: usize) -> T {
         let result = self.vector.remove(index);
+        if self.splits_pending_batch(index, false) || self.follows_pending_move() {
+            self.add_diff(VectorDiff::Replace {});
+            return result;
+        }
         self.add_diff(VectorDiff::Remove {
             index,
             snapshot_index: index.try_into().unwrap(),
@@ -238,6 +539,101 @@ impl<T: Clone> MutableVectorState<T> {
         return result;
     }
 
+    /// Moves the value at `from` so that it ends up at `to`, shifting the values in
+    /// between. Unlike a remove followed by an insert, this emits a single
+    /// `VectorDiff::Move`, so consumers can keep track of the identity of the value.
+    /// Any value removed before signals have pulled the move is reported as a Replace.
+    /// Throws if either index is not currently in the vector.
+    pub fn move_item(&mut self, from: usize, to: usize) {
+        assert!(to < self.vector.len(), "Cannot move a value past the end of the vector");
+        let value = self.vector.remove(from);
+        self.vector.insert(to, value);
+        if from == to {
+            return;
+        }
+        if self.splits_pending_batch(from, false) || self.splits_pending_batch(to, false) {
+            self.add_diff(VectorDiff::Replace {});
+            return;
+        }
+
+        self.pull_source
+            .update_keys(|index| moved_index(*index, from, to));
+        for batch in self.pending_batches.iter_mut() {
+            batch.start = moved_index(batch.start, from, to);
+        }
+        self.add_diff(VectorDiff::Move {
+            from,
+            to,
+            snapshot_from: from,
+            snapshot_to: to,
+        });
+        if self.pull_source.has_listening_signal() {
+            self.pending_move = Some(self.pull_source.last_diff_number());
+        }
+    }
+
+    /// Swaps the values at two indices, as two moves. Throws if either index is not
+    /// currently in the vector.
+    pub fn swap(&mut self, a: usize, b: usize) {
+        let (low, high) = (min(a, b), max(a, b));
+        assert!(high < self.vector.len(), "Cannot swap past the end of the vector");
+        if low == high {
+            return;
+        }
+        self.move_item(high, low);
+        self.move_item(low + 1, high);
+    }
+
+    // Moves the value at each index `i` to `new_positions[i]`, using as few moves as
+    // possible.
+    fn permute(&mut self, new_positions: &[usize]) {
+        for diff in permutation_to_moves(new_positions) {
+            if let VectorDiff::Move { from, to, .. } = diff {
+                self.move_item(from, to);
+            }
+        }
+    }
+
+    /// Reverses the order of the values in this Vector, as a series of moves.
+    pub fn reverse(&mut self) {
+        let len = self.vector.len();
+        let new_positions: Vec<usize> = (0..len).map(|index| len - 1 - index).collect();
+        self.permute(&new_positions);
+    }
+
+    /// Rotates this Vector in place so that the value at `mid` becomes the first value,
+    /// as a series of moves. Throws if `mid` is greater than the length of the vector.
+    pub fn rotate_left(&mut self, mid: usize) {
+        let len = self.vector.len();
+        assert!(mid <= len, "Cannot rotate past the end of the vector");
+        let new_positions: Vec<usize> = (0..len).map(|index| (index + len - mid) % len).collect();
+        self.permute(&new_positions);
+    }
+
+    /// Rotates this Vector in place so that the last `k` values move to the front, as a
+    /// series of moves. Throws if `k` is greater than the length of the vector.
+    pub fn rotate_right(&mut self, k: usize) {
+        let len = self.vector.len();
+        assert!(k <= len, "Cannot rotate past the end of the vector");
+        let new_positions: Vec<usize> = (0..len).map(|index| (index + k) % len).collect();
+        self.permute(&new_positions);
+    }
+
+    /// Sorts this Vector with a comparator function, as a series of moves. The sort is
+    /// stable, and values that are already in order are not moved.
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let mut sorted_indices: Vec<usize> = (0..self.vector.len()).collect();
+        sorted_indices.sort_by(|a, b| compare(&self.vector[*a], &self.vector[*b]));
+        let mut new_positions = vec![0; sorted_indices.len()];
+        for (position, index) in sorted_indices.into_iter().enumerate() {
+            new_positions[index] = position;
+        }
+        self.permute(&new_positions);
+    }
+
     /// Removes and returns the first value in this Vector, if the Vector is not empty.
     pub fn pop_front(&mut self) -> Option<T> {
         match self.vector.len() {
@@ -252,6 +648,12 @@ impl<T: Clone> MutableVectorState<T> {
             0 => None,
             _ => {
                 let result = self.vector.pop_back().unwrap();
+                if self.splits_pending_batch(self.vector.len(), false)
+                    || self.follows_pending_move()
+                {
+                    self.add_diff(VectorDiff::Replace {});
+                    return Some(result);
+                }
                 self.add_diff(VectorDiff::Remove {
                     index: self.vector.len(),
                     snapshot_index: self.vector.len().try_into().unwrap(),
@@ -270,6 +672,88 @@ impl<T: Clone> MutableVectorState<T> {
         self.vector.clear();
         self.add_diff(VectorDiff::Clear {});
     }
+
+    /// Removes every value that fails a predicate. The values are removed from the back
+    /// first, emitting one Remove diff each, so the indices of earlier removals are not
+    /// shifted. If no value passes, this emits a single Clear instead.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let removed_indices: Vec<usize> = self
+            .vector
+            .iter()
+            .enumerate()
+            .filter(|(_index, value)| !f(value))
+            .map(|(index, _value)| index)
+            .collect();
+        if !removed_indices.is_empty() && removed_indices.len() == self.vector.len() {
+            self.clear();
+            return;
+        }
+        for index in removed_indices.into_iter().rev() {
+            self.remove(index);
+        }
+    }
+}
+
+impl<T: Clone + PartialEq> MutableVectorState<T> {
+    /// Like `set`, but does nothing if the value at the given index is already equal
+    /// to the new value. Returns the previous value only if it was replaced. Throws if
+    /// the given index is not currently in the vector.
+    pub fn set_if_changed(&mut self, index: usize, value: T) -> Option<T> {
+        if self.vector[index] == value {
+            return None;
+        }
+        Some(self.set(index, value))
+    }
+
+    /// Replaces the entire contents of this Vector, but only emits diffs for the part
+    /// of the Vector between the leading and trailing runs of items that the old and
+    /// new contents share. The new items of that middle part are inserted, followed by
+    /// removes for the old ones. This handles appending, prepending and replacing a
+    /// slice of the Vector in O(n).
+    ///
+    /// The diffs are not minimal when the changes are interleaved with unchanged items,
+    /// since everything between the first and last change is treated as changed.
+    pub fn replace_common_prefix_suffix<I>(&mut self, items: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let new_items = Vector::from_iter(items);
+        let prefix_len = self
+            .vector
+            .iter()
+            .zip(new_items.iter())
+            .take_while(|(old, new)| old == new)
+            .count();
+        let suffix_len = self
+            .vector
+            .iter()
+            .rev()
+            .zip(new_items.iter().rev())
+            .take(min(self.vector.len(), new_items.len()) - prefix_len)
+            .take_while(|(old, new)| old == new)
+            .count();
+
+        let old_middle_len = self.vector.len() - prefix_len - suffix_len;
+        let new_middle_len = new_items.len() - prefix_len - suffix_len;
+        let new_middle = new_items.into_iter().skip(prefix_len).take(new_middle_len);
+        for (offset, value) in new_middle.enumerate() {
+            self.insert(prefix_len + offset, value);
+        }
+        for _ in 0..old_middle_len {
+            self.remove(prefix_len + new_middle_len);
+        }
+    }
+}
+
+impl<T: Clone + Ord> MutableVectorState<T> {
+    /// Sorts this Vector, as a series of moves. The sort is stable, and values that are
+    /// already in order are not moved.
+    pub fn sort(&mut self) {
+        self.sort_by(Ord::cmp)
+    }
 }
 
 impl<T: Clone> Deref for MutableVectorState<T> {
@@ -1,7 +1,20 @@
 use super::{MutableVector, MutableVectorState, VectorDiff, VectorEvent};
 use crate::structural_signal::pull_source::PullSourceStructuralSignal;
 use crate::structural_signal::transformer::StructuralSignalTransformer;
+use crate::StructuralSignal;
+use futures_signals::signal::Signal;
+use im::Vector;
+use parking_lot::RwLock;
+use pin_project::pin_project;
+use std::cmp::{max, min, Ordering};
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::Hash;
 use std::marker::PhantomData;
+use std::ops::Range;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Waker};
 
 // ** MAP ** //
 
@@ -39,6 +52,9 @@ where
     type OutputSignal = PullSourceStructuralSignal<MutableVectorState<OV>>;
 
     fn apply_event(&mut self, map_event: VectorEvent<IV>) {
+        // Only new or changed values are mapped. Inserts and removes shift the values
+        // around them in the output as well, so shifted values never need re-mapping.
+        let map_event = map_event.expand_batches();
         let mut writer = self.vector.write();
         for diff in map_event.diffs {
             match diff {
@@ -73,6 +89,8 @@ where
                 } => {
                     writer.remove(index);
                 }
+                VectorDiff::Move { from, to, .. } => writer.move_item(from, to),
+                VectorDiff::Extend { .. } => unreachable!(),
                 VectorDiff::Clear {} => {
                     writer.clear();
                 }
@@ -85,3 +103,1647 @@ where
         self.vector.as_signal()
     }
 }
+
+// ** MAP_WITH_INDEX ** //
+
+pub struct MapWithIndexVectorTransformer<F, IV, OV>
+where
+    IV: Clone,
+    OV: Clone,
+    F: Fn(usize, &IV) -> OV,
+{
+    vector: MutableVector<OV>,
+    // Mirrors the full input vector so that shifted elements can be re-mapped.
+    input: Vector<IV>,
+    map_fn: F,
+}
+
+impl<F, IV, OV> MapWithIndexVectorTransformer<F, IV, OV>
+where
+    IV: Clone,
+    OV: Clone,
+    F: Fn(usize, &IV) -> OV,
+{
+    pub(crate) fn new(map_fn: F) -> MapWithIndexVectorTransformer<F, IV, OV> {
+        MapWithIndexVectorTransformer {
+            vector: MutableVector::new(),
+            input: Vector::new(),
+            map_fn,
+        }
+    }
+
+    fn remap_from(&self, writer: &mut MutableVectorState<OV>, start: usize) {
+        for index in start..self.input.len() {
+            writer.set(index, (self.map_fn)(index, &self.input[index]));
+        }
+    }
+}
+
+impl<F, IV, OV> StructuralSignalTransformer for MapWithIndexVectorTransformer<F, IV, OV>
+where
+    IV: Clone,
+    OV: Clone,
+    F: Fn(usize, &IV) -> OV,
+{
+    type InputEvent = VectorEvent<IV>;
+    type OutputSignal = PullSourceStructuralSignal<MutableVectorState<OV>>;
+
+    fn apply_event(&mut self, event: VectorEvent<IV>) {
+        let event = event.expand_batches().split_moves();
+        let mut writer = self.vector.write();
+        for diff in event.diffs {
+            match diff {
+                VectorDiff::Replace {} => {
+                    self.input = event.snapshot.clone();
+                    writer.replace(
+                        self.input
+                            .iter()
+                            .enumerate()
+                            .map(|(index, v)| (self.map_fn)(index, v)),
+                    );
+                }
+                VectorDiff::Insert {
+                    index,
+                    snapshot_index: _,
+                } => {
+                    let value = diff.get_value_from_snapshot(&event.snapshot).unwrap();
+                    self.input.insert(index, value.clone());
+                    writer.insert(index, (self.map_fn)(index, value));
+                    self.remap_from(&mut writer, index + 1);
+                }
+                VectorDiff::Update {
+                    index,
+                    snapshot_index: _,
+                } => {
+                    let value = diff.get_value_from_snapshot(&event.snapshot).unwrap();
+                    self.input.set(index, value.clone());
+                    writer.set(index, (self.map_fn)(index, value));
+                }
+                VectorDiff::Remove {
+                    index,
+                    snapshot_index: _,
+                } => {
+                    self.input.remove(index);
+                    writer.remove(index);
+                    self.remap_from(&mut writer, index);
+                }
+                VectorDiff::Extend { .. } | VectorDiff::Move { .. } => unreachable!(),
+                VectorDiff::Clear {} => {
+                    self.input.clear();
+                    writer.clear();
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn get_signal(&self) -> Self::OutputSignal {
+        self.vector.as_signal()
+    }
+}
+
+// ** WINDOWS_MAP ** //
+
+pub struct WindowsMapVectorTransformer<F, IV, OV>
+where
+    IV: Clone,
+    OV: Clone,
+    F: Fn(&[IV]) -> OV,
+{
+    vector: MutableVector<OV>,
+    // Mirrors the full input vector so that every window around a change can be re-mapped.
+    input: Vector<IV>,
+    size: usize,
+    map_fn: F,
+}
+
+impl<F, IV, OV> WindowsMapVectorTransformer<F, IV, OV>
+where
+    IV: Clone,
+    OV: Clone,
+    F: Fn(&[IV]) -> OV,
+{
+    pub(crate) fn new(size: usize, map_fn: F) -> WindowsMapVectorTransformer<F, IV, OV> {
+        assert!(size > 0, "Window size must be greater than 0");
+        WindowsMapVectorTransformer {
+            vector: MutableVector::new(),
+            input: Vector::new(),
+            size,
+            map_fn,
+        }
+    }
+
+    fn output_len(&self) -> usize {
+        (self.input.len() + 1).saturating_sub(self.size)
+    }
+
+    fn map_window(&self, start: usize) -> OV {
+        let window: Vec<IV> = self.input.iter().skip(start).take(self.size).cloned().collect();
+        (self.map_fn)(&window)
+    }
+
+    fn remap(&self, writer: &mut MutableVectorState<OV>, range: Range<usize>, skip: Option<usize>) {
+        for start in range {
+            if Some(start) != skip {
+                writer.set(start, self.map_window(start));
+            }
+        }
+    }
+}
+
+impl<F, IV, OV> StructuralSignalTransformer for WindowsMapVectorTransformer<F, IV, OV>
+where
+    IV: Clone,
+    OV: Clone,
+    F: Fn(&[IV]) -> OV,
+{
+    type InputEvent = VectorEvent<IV>;
+    type OutputSignal = PullSourceStructuralSignal<MutableVectorState<OV>>;
+
+    fn apply_event(&mut self, event: VectorEvent<IV>) {
+        let event = event.expand_batches().split_moves();
+        let mut writer = self.vector.write();
+        for diff in event.diffs.iter() {
+            match *diff {
+                VectorDiff::Replace {} => {
+                    self.input = event.snapshot.clone();
+                    writer.replace((0..self.output_len()).map(|start| self.map_window(start)));
+                }
+                VectorDiff::Insert { index, .. } => {
+                    let value = diff.get_value_from_snapshot(&event.snapshot).unwrap();
+                    let old_output_len = self.output_len();
+                    self.input.insert(index, value.clone());
+
+                    // Windows after the new value shift along with it, and a new window
+                    // fills the gap.
+                    let mut inserted = None;
+                    if self.output_len() > old_output_len {
+                        let start = min(index, old_output_len);
+                        writer.insert(start, self.map_window(start));
+                        inserted = Some(start);
+                    }
+                    let first_affected = (index + 1).saturating_sub(self.size);
+                    let end = min(index + 1, self.output_len());
+                    self.remap(&mut writer, first_affected..end, inserted);
+                }
+                VectorDiff::Update { index, .. } => {
+                    let value = diff.get_value_from_snapshot(&event.snapshot).unwrap();
+                    self.input.set(index, value.clone());
+                    let first_affected = (index + 1).saturating_sub(self.size);
+                    let end = min(index + 1, self.output_len());
+                    self.remap(&mut writer, first_affected..end, None);
+                }
+                VectorDiff::Remove { index, .. } => {
+                    let old_output_len = self.output_len();
+                    self.input.remove(index);
+
+                    // Windows after the removed value shift back, so one window goes away.
+                    if self.output_len() < old_output_len {
+                        writer.remove(min(index, old_output_len - 1));
+                    }
+                    let first_affected = (index + 1).saturating_sub(self.size);
+                    let end = min(index, self.output_len());
+                    self.remap(&mut writer, first_affected..end, None);
+                }
+                VectorDiff::Extend { .. } | VectorDiff::Move { .. } => unreachable!(),
+                VectorDiff::Clear {} => {
+                    self.input.clear();
+                    writer.clear();
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn get_signal(&self) -> Self::OutputSignal {
+        self.vector.as_signal()
+    }
+}
+
+// ** FILTER ** //
+
+pub struct FilterVectorTransformer<T, F>
+where
+    T: Clone,
+    F: Fn(&T) -> bool,
+{
+    vector: MutableVector<T>,
+    // Whether each element of the input passes the predicate, which is all that is
+    // needed to translate input indices into output indices.
+    passes: Vector<bool>,
+    predicate: F,
+}
+
+impl<T, F> FilterVectorTransformer<T, F>
+where
+    T: Clone,
+    F: Fn(&T) -> bool,
+{
+    pub(crate) fn new(predicate: F) -> FilterVectorTransformer<T, F> {
+        FilterVectorTransformer {
+            vector: MutableVector::new(),
+            passes: Vector::new(),
+            predicate,
+        }
+    }
+
+    // Finds where the element at a given input index lives, or would live, in the output.
+    fn output_index(&self, index: usize) -> usize {
+        self.passes.iter().take(index).filter(|passes| **passes).count()
+    }
+}
+
+impl<T, F> StructuralSignalTransformer for FilterVectorTransformer<T, F>
+where
+    T: Clone,
+    F: Fn(&T) -> bool,
+{
+    type InputEvent = VectorEvent<T>;
+    type OutputSignal = PullSourceStructuralSignal<MutableVectorState<T>>;
+
+    fn apply_event(&mut self, event: VectorEvent<T>) {
+        let event = event.expand_batches().split_moves();
+        let mut writer = self.vector.write();
+        for diff in event.diffs.iter() {
+            match *diff {
+                VectorDiff::Replace {} => {
+                    self.passes = event.snapshot.iter().map(&self.predicate).collect();
+                    writer.replace(
+                        event
+                            .snapshot
+                            .iter()
+                            .filter(|value| (self.predicate)(value))
+                            .cloned(),
+                    );
+                }
+                VectorDiff::Insert { index, .. } => {
+                    let value = diff.get_value_from_snapshot(&event.snapshot).unwrap();
+                    let passes = (self.predicate)(value);
+                    if passes {
+                        writer.insert(self.output_index(index), value.clone());
+                    }
+                    self.passes.insert(index, passes);
+                }
+                VectorDiff::Update { index, .. } => {
+                    let value = diff.get_value_from_snapshot(&event.snapshot).unwrap();
+                    let passes = (self.predicate)(value);
+                    let output_index = self.output_index(index);
+                    match (self.passes[index], passes) {
+                        (true, true) => {
+                            writer.set(output_index, value.clone());
+                        }
+                        (false, true) => writer.insert(output_index, value.clone()),
+                        (true, false) => {
+                            writer.remove(output_index);
+                        }
+                        (false, false) => {}
+                    }
+                    self.passes.set(index, passes);
+                }
+                VectorDiff::Remove { index, .. } => {
+                    if self.passes[index] {
+                        writer.remove(self.output_index(index));
+                    }
+                    self.passes.remove(index);
+                }
+                VectorDiff::Extend { .. } | VectorDiff::Move { .. } => unreachable!(),
+                VectorDiff::Clear {} => {
+                    self.passes.clear();
+                    writer.clear();
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn get_signal(&self) -> Self::OutputSignal {
+        self.vector.as_signal()
+    }
+}
+
+// ** SORTED_BY ** //
+
+pub struct SortedByVectorTransformer<T, F>
+where
+    T: Clone,
+    F: Fn(&T, &T) -> Ordering,
+{
+    vector: MutableVector<T>,
+    // The id and value of every element, in input order. Ids are handed out in
+    // increasing order and break ties between equal values, so the output is sorted by
+    // value and then by id. That lets an element be found again with a binary search
+    // when the input removes or updates it.
+    input: Vector<(usize, T)>,
+    // The id of the element at each position of the output.
+    output_ids: Vector<usize>,
+    next_id: usize,
+    compare: F,
+}
+
+impl<T, F> SortedByVectorTransformer<T, F>
+where
+    T: Clone,
+    F: Fn(&T, &T) -> Ordering,
+{
+    pub(crate) fn new(compare: F) -> SortedByVectorTransformer<T, F> {
+        SortedByVectorTransformer {
+            vector: MutableVector::new(),
+            input: Vector::new(),
+            output_ids: Vector::new(),
+            next_id: 0,
+            compare,
+        }
+    }
+
+    // Compares the element at an output index against the given value and id.
+    fn compare_at(
+        &self,
+        sorted: &Vector<T>,
+        output_index: usize,
+        value: &T,
+        id: usize,
+    ) -> Ordering {
+        (self.compare)(&sorted[output_index], value).then(self.output_ids[output_index].cmp(&id))
+    }
+
+    // Finds the output index of the element with the given value and id, or the index
+    // it should be inserted at if it is not in the output. A new element gets the
+    // largest id so far, so it goes after all values that compare equal to it.
+    fn output_index(&self, sorted: &Vector<T>, value: &T, id: usize) -> Result<usize, usize> {
+        let mut low = 0;
+        let mut high = sorted.len();
+        while low < high {
+            let mid = low + (high - low) / 2;
+            match self.compare_at(sorted, mid, value, id) {
+                Ordering::Less => low = mid + 1,
+                Ordering::Greater => high = mid,
+                Ordering::Equal => return Ok(mid),
+            }
+        }
+        Err(low)
+    }
+
+    // Whether a value can stay at an output index without breaking the sort order.
+    fn fits_at(&self, sorted: &Vector<T>, output_index: usize, value: &T, id: usize) -> bool {
+        let fits_after_previous = output_index == 0
+            || self.compare_at(sorted, output_index - 1, value, id) == Ordering::Less;
+        let fits_before_next = output_index + 1 >= sorted.len()
+            || self.compare_at(sorted, output_index + 1, value, id) == Ordering::Greater;
+        fits_after_previous && fits_before_next
+    }
+}
+
+impl<T, F> StructuralSignalTransformer for SortedByVectorTransformer<T, F>
+where
+    T: Clone,
+    F: Fn(&T, &T) -> Ordering,
+{
+    type InputEvent = VectorEvent<T>;
+    type OutputSignal = PullSourceStructuralSignal<MutableVectorState<T>>;
+
+    fn apply_event(&mut self, event: VectorEvent<T>) {
+        let event = event.expand_batches().split_moves();
+        let mut writer = self.vector.write();
+        for diff in event.diffs.iter() {
+            match *diff {
+                VectorDiff::Replace {} => {
+                    // The sort is stable, so equal values stay in input order, which is
+                    // also the order of their ids.
+                    let mut sorted = event.snapshot.iter().enumerate().collect::<Vec<_>>();
+                    sorted.sort_by(|(_, a), (_, b)| (self.compare)(*a, *b));
+                    self.input = event.snapshot.iter().cloned().enumerate().collect();
+                    self.output_ids = sorted.iter().map(|(id, _)| *id).collect();
+                    self.next_id = sorted.len();
+                    writer.replace(sorted.into_iter().map(|(_, value)| value.clone()));
+                }
+                VectorDiff::Insert { index, .. } => {
+                    let value = diff.get_value_from_snapshot(&event.snapshot).unwrap();
+                    let id = self.next_id;
+                    self.next_id += 1;
+                    let output_index = self.output_index(&writer, value, id).unwrap_err();
+                    writer.insert(output_index, value.clone());
+                    self.output_ids.insert(output_index, id);
+                    self.input.insert(index, (id, value.clone()));
+                }
+                VectorDiff::Update { index, .. } => {
+                    let value = diff.get_value_from_snapshot(&event.snapshot).unwrap();
+                    let (id, output_index) = {
+                        let (id, previous) = &self.input[index];
+                        (*id, self.output_index(&writer, previous, *id).unwrap())
+                    };
+                    if self.fits_at(&writer, output_index, value, id) {
+                        writer.set(output_index, value.clone());
+                        self.input.set(index, (id, value.clone()));
+                    } else {
+                        writer.remove(output_index);
+                        self.output_ids.remove(output_index);
+                        let new_id = self.next_id;
+                        self.next_id += 1;
+                        let new_output_index =
+                            self.output_index(&writer, value, new_id).unwrap_err();
+                        writer.insert(new_output_index, value.clone());
+                        self.output_ids.insert(new_output_index, new_id);
+                        self.input.set(index, (new_id, value.clone()));
+                    }
+                }
+                VectorDiff::Remove { index, .. } => {
+                    let (id, previous) = self.input.remove(index);
+                    let output_index = self.output_index(&writer, &previous, id).unwrap();
+                    writer.remove(output_index);
+                    self.output_ids.remove(output_index);
+                }
+                VectorDiff::Extend { .. } | VectorDiff::Move { .. } => unreachable!(),
+                VectorDiff::Clear {} => {
+                    self.input.clear();
+                    self.output_ids.clear();
+                    writer.clear();
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn get_signal(&self) -> Self::OutputSignal {
+        self.vector.as_signal()
+    }
+}
+
+// ** DEDUP ** //
+
+pub struct DedupVectorTransformer<T>
+where
+    T: Clone + PartialEq,
+{
+    vector: MutableVector<T>,
+    input: Vector<T>,
+    // Whether each element of the input is kept in the output, which is the case when
+    // it differs from the element before it.
+    kept: Vector<bool>,
+}
+
+impl<T> DedupVectorTransformer<T>
+where
+    T: Clone + PartialEq,
+{
+    pub(crate) fn new() -> DedupVectorTransformer<T> {
+        DedupVectorTransformer {
+            vector: MutableVector::new(),
+            input: Vector::new(),
+            kept: Vector::new(),
+        }
+    }
+
+    fn output_index(&self, index: usize) -> usize {
+        self.kept.iter().take(index).filter(|kept| **kept).count()
+    }
+}
+
+// Re-checks whether the input element at `index` should be kept, now that it or the
+// element before it has changed, and brings the output in line.
+fn sync_deduped<T: Clone + PartialEq>(
+    writer: &mut MutableVectorState<T>,
+    input: &Vector<T>,
+    kept: &mut Vector<bool>,
+    index: usize,
+) {
+    if index >= input.len() {
+        return;
+    }
+    let should_keep = index == 0 || input[index] != input[index - 1];
+    let output_index = kept.iter().take(index).filter(|kept| **kept).count();
+    match (kept[index], should_keep) {
+        (true, true) => {
+            writer.set_if_changed(output_index, input[index].clone());
+        }
+        (false, true) => writer.insert(output_index, input[index].clone()),
+        (true, false) => {
+            writer.remove(output_index);
+        }
+        (false, false) => {}
+    }
+    kept.set(index, should_keep);
+}
+
+impl<T> StructuralSignalTransformer for DedupVectorTransformer<T>
+where
+    T: Clone + PartialEq,
+{
+    type InputEvent = VectorEvent<T>;
+    type OutputSignal = PullSourceStructuralSignal<MutableVectorState<T>>;
+
+    fn apply_event(&mut self, event: VectorEvent<T>) {
+        let event = event.expand_batches().split_moves();
+        let mut writer = self.vector.write();
+        for diff in event.diffs.iter() {
+            match *diff {
+                VectorDiff::Replace {} => {
+                    let input = event.snapshot.clone();
+                    self.kept = (0..input.len())
+                        .map(|index| index == 0 || input[index] != input[index - 1])
+                        .collect();
+                    self.input = input;
+                    let deduped = self
+                        .input
+                        .iter()
+                        .zip(self.kept.iter())
+                        .filter(|(_, kept)| **kept)
+                        .map(|(value, _)| value.clone());
+                    writer.replace(deduped);
+                }
+                VectorDiff::Insert { index, .. } => {
+                    let value = diff.get_value_from_snapshot(&event.snapshot).unwrap();
+                    self.input.insert(index, value.clone());
+                    self.kept.insert(index, false);
+                    sync_deduped(&mut writer, &self.input, &mut self.kept, index);
+                    sync_deduped(&mut writer, &self.input, &mut self.kept, index + 1);
+                }
+                VectorDiff::Update { index, .. } => {
+                    let value = diff.get_value_from_snapshot(&event.snapshot).unwrap();
+                    self.input.set(index, value.clone());
+                    sync_deduped(&mut writer, &self.input, &mut self.kept, index);
+                    sync_deduped(&mut writer, &self.input, &mut self.kept, index + 1);
+                }
+                VectorDiff::Remove { index, .. } => {
+                    if self.kept[index] {
+                        writer.remove(self.output_index(index));
+                    }
+                    self.input.remove(index);
+                    self.kept.remove(index);
+                    sync_deduped(&mut writer, &self.input, &mut self.kept, index);
+                }
+                VectorDiff::Extend { .. } | VectorDiff::Move { .. } => unreachable!(),
+                VectorDiff::Clear {} => {
+                    self.input.clear();
+                    self.kept.clear();
+                    writer.clear();
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn get_signal(&self) -> Self::OutputSignal {
+        self.vector.as_signal()
+    }
+}
+
+// ** IDENTIFY ** //
+
+pub struct IdentifyVectorTransformer<F, T, Id>
+where
+    T: Clone,
+    Id: Clone + Eq + Hash,
+    F: Fn(&T) -> Id,
+{
+    vector: MutableVector<(Id, T)>,
+    id_fn: F,
+}
+
+impl<F, T, Id> IdentifyVectorTransformer<F, T, Id>
+where
+    T: Clone,
+    Id: Clone + Eq + Hash,
+    F: Fn(&T) -> Id,
+{
+    pub(crate) fn new(id_fn: F) -> IdentifyVectorTransformer<F, T, Id> {
+        IdentifyVectorTransformer {
+            vector: MutableVector::new(),
+            id_fn,
+        }
+    }
+
+    #[inline]
+    fn identify(&self, value: &T) -> (Id, T) {
+        ((self.id_fn)(value), value.clone())
+    }
+}
+
+impl<F, T, Id> StructuralSignalTransformer for IdentifyVectorTransformer<F, T, Id>
+where
+    T: Clone,
+    Id: Clone + Eq + Hash,
+    F: Fn(&T) -> Id,
+{
+    type InputEvent = VectorEvent<T>;
+    type OutputSignal = PullSourceStructuralSignal<MutableVectorState<(Id, T)>>;
+
+    fn apply_event(&mut self, event: VectorEvent<T>) {
+        let event = event.expand_batches();
+        let mut writer = self.vector.write();
+        let mut diffs = event.diffs.into_iter().peekable();
+        while let Some(diff) = diffs.next() {
+            match diff {
+                VectorDiff::Replace {} => {
+                    writer.replace(event.snapshot.iter().map(|v| self.identify(v)));
+                }
+                VectorDiff::Insert {
+                    index,
+                    snapshot_index: _,
+                } => {
+                    let value = diff.get_value_from_snapshot(&event.snapshot).unwrap();
+                    writer.insert(index, self.identify(value));
+                }
+                VectorDiff::Update {
+                    index,
+                    snapshot_index: _,
+                } => {
+                    let value = diff.get_value_from_snapshot(&event.snapshot).unwrap();
+                    writer.set(index, self.identify(value));
+                }
+                VectorDiff::Remove {
+                    index,
+                    snapshot_index: _,
+                } => {
+                    // A value that is removed and then inserted again with the same id
+                    // has only moved. Its value may still have changed, so it is set too.
+                    let snapshot = &event.snapshot;
+                    let reinserted = diffs.peek().and_then(|next| match *next {
+                        VectorDiff::Insert { index: to, .. } => {
+                            let value = next.get_value_from_snapshot(snapshot).unwrap();
+                            if writer[index].0 == (self.id_fn)(value) {
+                                Some((to, value))
+                            } else {
+                                None
+                            }
+                        }
+                        _ => None,
+                    });
+                    match reinserted {
+                        Some((to, value)) => {
+                            diffs.next();
+                            writer.move_item(index, to);
+                            writer.set(to, self.identify(value));
+                        }
+                        None => {
+                            writer.remove(index);
+                        }
+                    }
+                }
+                VectorDiff::Move { from, to, .. } => writer.move_item(from, to),
+                VectorDiff::Extend { .. } => unreachable!(),
+                VectorDiff::Clear {} => {
+                    writer.clear();
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn get_signal(&self) -> Self::OutputSignal {
+        self.vector.as_signal()
+    }
+}
+
+// ** RANGE ** //
+
+pub struct RangeVectorTransformer<T>
+where
+    T: Clone,
+{
+    vector: MutableVector<T>,
+    // Mirrors the full input vector so that elements shifting into the window
+    // can be looked up as each diff is applied.
+    input: Vector<T>,
+    range: Range<usize>,
+}
+
+impl<T> RangeVectorTransformer<T>
+where
+    T: Clone,
+{
+    pub(crate) fn new(range: Range<usize>) -> RangeVectorTransformer<T> {
+        assert!(range.start <= range.end, "Range start must not be after its end");
+        RangeVectorTransformer {
+            vector: MutableVector::new(),
+            input: Vector::new(),
+            range,
+        }
+    }
+
+    fn window_of(&self, input: &Vector<T>) -> Vector<T> {
+        let end = min(self.range.end, input.len());
+        if self.range.start >= end {
+            Vector::new()
+        } else {
+            input.clone().slice(self.range.start..end)
+        }
+    }
+}
+
+impl<T> StructuralSignalTransformer for RangeVectorTransformer<T>
+where
+    T: Clone,
+{
+    type InputEvent = VectorEvent<T>;
+    type OutputSignal = PullSourceStructuralSignal<MutableVectorState<T>>;
+
+    fn apply_event(&mut self, event: VectorEvent<T>) {
+        let event = event.expand_batches().split_moves();
+        let start = self.range.start;
+        let end = self.range.end;
+        let mut writer = self.vector.write();
+        for diff in event.diffs {
+            match diff {
+                VectorDiff::Replace {} => {
+                    self.input = event.snapshot.clone();
+                    writer.replace(self.window_of(&event.snapshot).into_iter());
+                }
+                VectorDiff::Insert {
+                    index,
+                    snapshot_index: _,
+                } => {
+                    let value = diff.get_value_from_snapshot(&event.snapshot).unwrap();
+                    self.input.insert(index, value.clone());
+                    if index >= end || self.input.len() <= start {
+                        continue;
+                    }
+
+                    // Inserting before the window shifts the element just before it in.
+                    let window_index = index.saturating_sub(start);
+                    writer.insert(window_index, self.input[max(index, start)].clone());
+                    if writer.len() > end - start {
+                        writer.pop_back();
+                    }
+                }
+                VectorDiff::Update {
+                    index,
+                    snapshot_index: _,
+                } => {
+                    let value = diff.get_value_from_snapshot(&event.snapshot).unwrap();
+                    self.input.set(index, value.clone());
+                    if index >= start && index < end {
+                        writer.set(index - start, value.clone());
+                    }
+                }
+                VectorDiff::Remove {
+                    index,
+                    snapshot_index: _,
+                } => {
+                    self.input.remove(index);
+                    if index >= end || writer.is_empty() {
+                        continue;
+                    }
+
+                    // Removing before the window shifts its first element out.
+                    writer.remove(index.saturating_sub(start));
+                    if self.input.len() >= end {
+                        writer.push_back(self.input[end - 1].clone());
+                    }
+                }
+                VectorDiff::Extend { .. } | VectorDiff::Move { .. } => unreachable!(),
+                VectorDiff::Clear {} => {
+                    self.input.clear();
+                    writer.clear();
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn get_signal(&self) -> Self::OutputSignal {
+        self.vector.as_signal()
+    }
+}
+
+// ** COMBINE_WITH ** //
+
+/// A StructuralSignal that combines the elements at the same index of two vector
+/// signals into a single element.
+#[pin_project(project = CombineWithVectorSignalProj)]
+pub struct CombineWithVectorSignal<LS, RS, F, L, R, O>
+where
+    LS: StructuralSignal<Item = VectorEvent<L>>,
+    RS: StructuralSignal<Item = VectorEvent<R>>,
+    L: Clone,
+    R: Clone,
+    O: Clone,
+    F: Fn(&L, &R) -> O,
+{
+    #[pin]
+    left_signal: LS,
+    #[pin]
+    right_signal: RS,
+    #[pin]
+    combined_signal: PullSourceStructuralSignal<MutableVectorState<O>>,
+
+    // Mirrors both input vectors so that shifted elements can be re-combined.
+    left: Vector<L>,
+    right: Vector<R>,
+    vector: MutableVector<O>,
+    combine_fn: F,
+
+    left_closed: bool,
+    right_closed: bool,
+}
+
+impl<LS, RS, F, L, R, O> CombineWithVectorSignal<LS, RS, F, L, R, O>
+where
+    LS: StructuralSignal<Item = VectorEvent<L>>,
+    RS: StructuralSignal<Item = VectorEvent<R>>,
+    L: Clone,
+    R: Clone,
+    O: Clone,
+    F: Fn(&L, &R) -> O,
+{
+    pub(crate) fn new(
+        left_signal: LS,
+        right_signal: RS,
+        combine_fn: F,
+    ) -> CombineWithVectorSignal<LS, RS, F, L, R, O> {
+        let vector = MutableVector::new();
+        CombineWithVectorSignal {
+            left_signal,
+            right_signal,
+            combined_signal: vector.as_signal(),
+            left: Vector::new(),
+            right: Vector::new(),
+            vector,
+            combine_fn,
+            left_closed: false,
+            right_closed: false,
+        }
+    }
+}
+
+// Applies a single diff to a mirrored input vector, returning the range of indices
+// whose combined values are now stale, or None if every value is stale.
+fn apply_to_mirror<T: Clone>(
+    mirror: &mut Vector<T>,
+    diff: &VectorDiff,
+    snapshot: &Vector<T>,
+) -> Option<Range<usize>> {
+    match *diff {
+        VectorDiff::Replace {} => {
+            *mirror = snapshot.clone();
+            None
+        }
+        VectorDiff::Insert { index, .. } => {
+            let value = diff.get_value_from_snapshot(snapshot).unwrap();
+            mirror.insert(index, value.clone());
+            Some(index..usize::MAX)
+        }
+        VectorDiff::Update { index, .. } => {
+            let value = diff.get_value_from_snapshot(snapshot).unwrap();
+            mirror.set(index, value.clone());
+            Some(index..index + 1)
+        }
+        VectorDiff::Remove { index, .. } => {
+            mirror.remove(index);
+            Some(index..usize::MAX)
+        }
+        VectorDiff::Extend {
+            index,
+            snapshot_index,
+            count,
+        } => {
+            mirror.extend(snapshot.iter().skip(snapshot_index).take(count).cloned());
+            Some(index..usize::MAX)
+        }
+        VectorDiff::Move { from, to, .. } => {
+            let value = mirror.remove(from);
+            mirror.insert(to, value);
+            Some(min(from, to)..max(from, to) + 1)
+        }
+        VectorDiff::Clear {} => {
+            mirror.clear();
+            None
+        }
+    }
+}
+
+fn recombine<L, R, O, F>(
+    writer: &mut MutableVectorState<O>,
+    left: &Vector<L>,
+    right: &Vector<R>,
+    combine_fn: &F,
+    stale: Option<Range<usize>>,
+) where
+    L: Clone,
+    R: Clone,
+    O: Clone,
+    F: Fn(&L, &R) -> O,
+{
+    let len = min(left.len(), right.len());
+    let stale = match stale {
+        Some(stale) => stale,
+        None => {
+            if len == 0 {
+                writer.clear();
+            } else {
+                writer.replace(
+                    left.iter()
+                        .zip(right.iter())
+                        .map(|(l, r)| combine_fn(l, r)),
+                );
+            }
+            return;
+        }
+    };
+
+    for index in stale.start..min(stale.end, len) {
+        let value = combine_fn(&left[index], &right[index]);
+        if index < writer.len() {
+            writer.set(index, value);
+        } else {
+            writer.push_back(value);
+        }
+    }
+    while writer.len() > len {
+        writer.pop_back();
+    }
+}
+
+impl<LS, RS, F, L, R, O> StructuralSignal for CombineWithVectorSignal<LS, RS, F, L, R, O>
+where
+    LS: StructuralSignal<Item = VectorEvent<L>>,
+    RS: StructuralSignal<Item = VectorEvent<R>>,
+    L: Clone,
+    R: Clone,
+    O: Clone,
+    F: Fn(&L, &R) -> O,
+{
+    type Item = VectorEvent<O>;
+
+    fn poll_change(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<VectorEvent<O>>> {
+        let CombineWithVectorSignalProj {
+            mut left_signal,
+            mut right_signal,
+            combined_signal,
+            left,
+            right,
+            vector,
+            combine_fn,
+            left_closed,
+            right_closed,
+        } = self.project();
+
+        let mut writer = vector.write();
+        while !*left_closed {
+            match left_signal.as_mut().poll_change(cx) {
+                Poll::Ready(Some(event)) => {
+                    for diff in event.diffs.iter() {
+                        let stale = apply_to_mirror(left, diff, &event.snapshot);
+                        recombine(&mut writer, left, right, combine_fn, stale);
+                    }
+                }
+                Poll::Ready(None) => *left_closed = true,
+                Poll::Pending => break,
+            }
+        }
+        while !*right_closed {
+            match right_signal.as_mut().poll_change(cx) {
+                Poll::Ready(Some(event)) => {
+                    for diff in event.diffs.iter() {
+                        let stale = apply_to_mirror(right, diff, &event.snapshot);
+                        recombine(&mut writer, left, right, combine_fn, stale);
+                    }
+                }
+                Poll::Ready(None) => *right_closed = true,
+                Poll::Pending => break,
+            }
+        }
+        drop(writer);
+
+        let result = combined_signal.poll_change(cx);
+        if *left_closed && *right_closed && !result.is_ready() {
+            Poll::Ready(None)
+        } else {
+            result
+        }
+    }
+}
+
+// ** CHAIN ** //
+
+/// A StructuralSignal whose vector is the contents of one vector signal followed by the
+/// contents of another.
+#[pin_project(project = ChainVectorSignalProj)]
+pub struct ChainVectorSignal<LS, RS, T>
+where
+    LS: StructuralSignal<Item = VectorEvent<T>>,
+    RS: StructuralSignal<Item = VectorEvent<T>>,
+    T: Clone,
+{
+    #[pin]
+    left_signal: LS,
+    #[pin]
+    right_signal: RS,
+    #[pin]
+    chained_signal: PullSourceStructuralSignal<MutableVectorState<T>>,
+
+    // The right vector starts at `left_len` in the output.
+    left_len: usize,
+    right_len: usize,
+    vector: MutableVector<T>,
+
+    left_closed: bool,
+    right_closed: bool,
+}
+
+impl<LS, RS, T> ChainVectorSignal<LS, RS, T>
+where
+    LS: StructuralSignal<Item = VectorEvent<T>>,
+    RS: StructuralSignal<Item = VectorEvent<T>>,
+    T: Clone,
+{
+    pub(crate) fn new(left_signal: LS, right_signal: RS) -> ChainVectorSignal<LS, RS, T> {
+        let vector = MutableVector::new();
+        ChainVectorSignal {
+            left_signal,
+            right_signal,
+            chained_signal: vector.as_signal(),
+            left_len: 0,
+            right_len: 0,
+            vector,
+            left_closed: false,
+            right_closed: false,
+        }
+    }
+}
+
+// Swaps the `len` values at `start` in the output for `part`, rebuilding the output.
+fn replace_chained_part<T: Clone>(
+    writer: &mut MutableVectorState<T>,
+    start: usize,
+    len: &mut usize,
+    part: Vector<T>,
+) {
+    let current = writer.snapshot();
+    let mut rebuilt = current.clone().slice(0..start);
+    rebuilt.append(part.clone());
+    rebuilt.append(current.skip(start + *len));
+    *len = part.len();
+
+    if rebuilt.is_empty() {
+        writer.clear();
+    } else {
+        writer.replace(rebuilt.into_iter());
+    }
+}
+
+// Applies an event from one of the chained vectors, whose values live in the output
+// from `start` to `start + len`.
+fn apply_chained_event<T: Clone>(
+    writer: &mut MutableVectorState<T>,
+    event: VectorEvent<T>,
+    start: usize,
+    len: &mut usize,
+) {
+    let event = event.expand_batches().split_moves();
+    for diff in event.diffs.iter() {
+        match *diff {
+            VectorDiff::Replace {} => {
+                replace_chained_part(writer, start, len, event.snapshot.clone());
+            }
+            VectorDiff::Insert { index, .. } => {
+                let value = diff.get_value_from_snapshot(&event.snapshot).unwrap();
+                writer.insert(start + index, value.clone());
+                *len += 1;
+            }
+            VectorDiff::Update { index, .. } => {
+                let value = diff.get_value_from_snapshot(&event.snapshot).unwrap();
+                writer.set(start + index, value.clone());
+            }
+            VectorDiff::Remove { index, .. } => {
+                writer.remove(start + index);
+                *len -= 1;
+            }
+            VectorDiff::Clear {} => {
+                replace_chained_part(writer, start, len, Vector::new());
+            }
+            VectorDiff::Extend { .. } | VectorDiff::Move { .. } => unreachable!(),
+        }
+    }
+}
+
+impl<LS, RS, T> StructuralSignal for ChainVectorSignal<LS, RS, T>
+where
+    LS: StructuralSignal<Item = VectorEvent<T>>,
+    RS: StructuralSignal<Item = VectorEvent<T>>,
+    T: Clone,
+{
+    type Item = VectorEvent<T>;
+
+    fn poll_change(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<VectorEvent<T>>> {
+        let ChainVectorSignalProj {
+            mut left_signal,
+            mut right_signal,
+            chained_signal,
+            left_len,
+            right_len,
+            vector,
+            left_closed,
+            right_closed,
+        } = self.project();
+
+        let mut writer = vector.write();
+        while !*left_closed {
+            match left_signal.as_mut().poll_change(cx) {
+                Poll::Ready(Some(event)) => apply_chained_event(&mut writer, event, 0, left_len),
+                Poll::Ready(None) => *left_closed = true,
+                Poll::Pending => break,
+            }
+        }
+        while !*right_closed {
+            match right_signal.as_mut().poll_change(cx) {
+                Poll::Ready(Some(event)) => {
+                    apply_chained_event(&mut writer, event, *left_len, right_len)
+                }
+                Poll::Ready(None) => *right_closed = true,
+                Poll::Pending => break,
+            }
+        }
+        drop(writer);
+
+        let result = chained_signal.poll_change(cx);
+        if *left_closed && *right_closed && !result.is_ready() {
+            Poll::Ready(None)
+        } else {
+            result
+        }
+    }
+}
+
+// ** DEMUX ** //
+
+pub struct DemuxerState<S, D, T, F>
+where
+    S: StructuralSignal<Item = VectorEvent<T>>,
+    S: Unpin,
+    D: Clone + Eq + Hash,
+    T: Clone,
+    F: Fn(&T) -> D,
+{
+    input: S,
+    // Mirrors the full input vector, along with the discriminant of each element, so
+    // that input indices can be translated into indices within each output.
+    mirror: Vector<T>,
+    discriminants: Vector<D>,
+    outputs: HashMap<D, MutableVector<T>>,
+    discriminant_fn: F,
+    is_closed: bool,
+}
+
+impl<S, D, T, F> DemuxerState<S, D, T, F>
+where
+    S: StructuralSignal<Item = VectorEvent<T>>,
+    S: Unpin,
+    D: Clone + Eq + Hash,
+    T: Clone,
+    F: Fn(&T) -> D,
+{
+    fn elements_for(&self, discriminant: &D) -> Vec<T> {
+        self.mirror
+            .iter()
+            .zip(self.discriminants.iter())
+            .filter(|(_, d)| *d == discriminant)
+            .map(|(value, _)| value.clone())
+            .collect()
+    }
+
+    // Finds where the element at a given input index lives within its output.
+    fn output_index(&self, index: usize, discriminant: &D) -> usize {
+        self.discriminants
+            .iter()
+            .take(index)
+            .filter(|d| *d == discriminant)
+            .count()
+    }
+
+    fn insert_into_output(&mut self, index: usize, value: T, discriminant: D) {
+        let output_index = self.output_index(index, &discriminant);
+        if let Some(output) = self.outputs.get(&discriminant) {
+            output.write().insert(output_index, value.clone());
+        }
+        self.mirror.insert(index, value);
+        self.discriminants.insert(index, discriminant);
+    }
+
+    fn remove_from_output(&mut self, index: usize) {
+        let discriminant = self.discriminants[index].clone();
+        let output_index = self.output_index(index, &discriminant);
+        if let Some(output) = self.outputs.get(&discriminant) {
+            output.write().remove(output_index);
+        }
+        self.mirror.remove(index);
+        self.discriminants.remove(index);
+    }
+
+    fn apply_event(&mut self, event: VectorEvent<T>) {
+        let event = event.expand_batches().split_moves();
+        for diff in event.diffs.iter() {
+            match *diff {
+                VectorDiff::Replace {} => {
+                    self.discriminants = event.snapshot.iter().map(&self.discriminant_fn).collect();
+                    self.mirror = event.snapshot.clone();
+                    for (discriminant, output) in self.outputs.iter() {
+                        output
+                            .write()
+                            .replace(self.elements_for(discriminant).into_iter());
+                    }
+                }
+                VectorDiff::Insert { index, .. } => {
+                    let value = diff.get_value_from_snapshot(&event.snapshot).unwrap().clone();
+                    let discriminant = (self.discriminant_fn)(&value);
+                    self.insert_into_output(index, value, discriminant);
+                }
+                VectorDiff::Update { index, .. } => {
+                    let value = diff.get_value_from_snapshot(&event.snapshot).unwrap().clone();
+                    let discriminant = (self.discriminant_fn)(&value);
+                    if discriminant == self.discriminants[index] {
+                        let output_index = self.output_index(index, &discriminant);
+                        if let Some(output) = self.outputs.get(&discriminant) {
+                            output.write().set(output_index, value.clone());
+                        }
+                        self.mirror.set(index, value);
+                    } else {
+                        // The element changed variant, so it moves between outputs.
+                        self.remove_from_output(index);
+                        self.insert_into_output(index, value, discriminant);
+                    }
+                }
+                VectorDiff::Remove { index, .. } => {
+                    self.remove_from_output(index);
+                }
+                VectorDiff::Extend { .. } | VectorDiff::Move { .. } => unreachable!(),
+                VectorDiff::Clear {} => {
+                    self.mirror.clear();
+                    self.discriminants.clear();
+                    for output in self.outputs.values() {
+                        output.write().clear();
+                    }
+                }
+            }
+        }
+    }
+
+    fn pull_in_new_changes(&mut self, cx: &mut Context) {
+        while !self.is_closed {
+            match Pin::new(&mut self.input).poll_change(cx) {
+                Poll::Ready(Some(event)) => self.apply_event(event),
+                Poll::Ready(None) => self.is_closed = true,
+                Poll::Pending => break,
+            }
+        }
+    }
+}
+
+/// Splits a vector signal into one vector signal per discriminant. Created by
+/// `SignalVectorExt::demux`.
+pub struct Demuxer<S, D, T, F>(Arc<RwLock<DemuxerState<S, D, T, F>>>)
+where
+    S: StructuralSignal<Item = VectorEvent<T>>,
+    S: Unpin,
+    D: Clone + Eq + Hash,
+    T: Clone,
+    F: Fn(&T) -> D;
+
+impl<S, D, T, F> Demuxer<S, D, T, F>
+where
+    S: StructuralSignal<Item = VectorEvent<T>>,
+    S: Unpin,
+    D: Clone + Eq + Hash,
+    T: Clone,
+    F: Fn(&T) -> D,
+{
+    pub(crate) fn new(input: S, discriminant_fn: F) -> Demuxer<S, D, T, F> {
+        Demuxer(Arc::new(RwLock::new(DemuxerState {
+            input,
+            mirror: Vector::new(),
+            discriminants: Vector::new(),
+            outputs: HashMap::new(),
+            discriminant_fn,
+            is_closed: false,
+        })))
+    }
+
+    /// Returns a signal of every element with the given discriminant, in the same
+    /// relative order as the input. Discriminants do not have to exist in the input
+    /// yet, the signal will simply be empty until a matching element shows up.
+    pub fn get(&self, discriminant: D) -> DemuxedVectorSignal<S, D, T, F> {
+        let mut state = self.0.write();
+        let elements = state.elements_for(&discriminant);
+        let output = state.outputs.entry(discriminant).or_insert_with(|| {
+            let output = MutableVector::new();
+            output.write().replace(elements.into_iter());
+            output
+        });
+        DemuxedVectorSignal {
+            output_signal: output.as_signal(),
+            parent: self.0.clone(),
+        }
+    }
+}
+
+/// A Signal created by a Demuxer.
+pub struct DemuxedVectorSignal<S, D, T, F>
+where
+    S: StructuralSignal<Item = VectorEvent<T>>,
+    S: Unpin,
+    D: Clone + Eq + Hash,
+    T: Clone,
+    F: Fn(&T) -> D,
+{
+    output_signal: PullSourceStructuralSignal<MutableVectorState<T>>,
+    parent: Arc<RwLock<DemuxerState<S, D, T, F>>>,
+}
+
+impl<S, D, T, F> StructuralSignal for DemuxedVectorSignal<S, D, T, F>
+where
+    S: StructuralSignal<Item = VectorEvent<T>>,
+    S: Unpin,
+    D: Clone + Eq + Hash,
+    T: Clone,
+    F: Fn(&T) -> D,
+{
+    type Item = VectorEvent<T>;
+
+    fn poll_change(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<VectorEvent<T>>> {
+        let is_closed = {
+            let mut state = self.parent.write();
+            state.pull_in_new_changes(cx);
+            state.is_closed
+        };
+
+        let result = Pin::new(&mut self.output_signal).poll_change(cx);
+        if is_closed && !result.is_ready() {
+            Poll::Ready(None)
+        } else {
+            result
+        }
+    }
+}
+
+// ** INDEX_SIGNALS ** //
+
+pub struct IndexSignalsState<S, T>
+where
+    S: StructuralSignal<Item = VectorEvent<T>>,
+    S: Unpin,
+    T: Clone,
+{
+    input: S,
+    snapshot: Vector<T>,
+    // For every position currently in the vector, the version at which the value at
+    // that position last changed.
+    versions: Vector<usize>,
+    next_version: usize,
+    // Index signals waiting for a change. Only the most recent poller is registered
+    // with the input, so everyone else has to be woken when it pulls in changes.
+    wakers: Vec<Waker>,
+    is_closed: bool,
+}
+
+impl<S, T> IndexSignalsState<S, T>
+where
+    S: StructuralSignal<Item = VectorEvent<T>>,
+    S: Unpin,
+    T: Clone,
+{
+    // Marks every position from `start` to the end as changed.
+    fn touch_from(&mut self, start: usize) {
+        let version = self.next_version;
+        self.next_version += 1;
+        for index in start..self.versions.len() {
+            self.versions.set(index, version);
+        }
+    }
+
+    fn apply_event(&mut self, event: VectorEvent<T>) {
+        let event = event.expand_batches().split_moves();
+        for diff in event.diffs.iter() {
+            match *diff {
+                VectorDiff::Replace {} => {
+                    self.versions = event.snapshot.iter().map(|_| 0).collect();
+                    self.touch_from(0);
+                }
+                VectorDiff::Insert { index, .. } => {
+                    self.versions.insert(index, 0);
+                    self.touch_from(index);
+                }
+                VectorDiff::Update { index, .. } => {
+                    self.versions.set(index, self.next_version);
+                    self.next_version += 1;
+                }
+                VectorDiff::Remove { index, .. } => {
+                    self.versions.remove(index);
+                    self.touch_from(index);
+                }
+                VectorDiff::Extend { .. } | VectorDiff::Move { .. } => unreachable!(),
+                VectorDiff::Clear {} => {
+                    self.versions.clear();
+                }
+            }
+        }
+        self.snapshot = event.snapshot;
+    }
+
+    fn pull_in_new_changes(&mut self, cx: &mut Context) {
+        let mut has_new_changes = false;
+        while !self.is_closed {
+            match Pin::new(&mut self.input).poll_change(cx) {
+                Poll::Ready(Some(event)) => {
+                    self.apply_event(event);
+                    has_new_changes = true;
+                }
+                Poll::Ready(None) => {
+                    self.is_closed = true;
+                    has_new_changes = true;
+                }
+                Poll::Pending => break,
+            }
+        }
+
+        if has_new_changes {
+            for waker in self.wakers.drain(..) {
+                waker.wake();
+            }
+        }
+    }
+}
+
+/// Hands out a Signal for the value at each index of a vector signal. Created by
+/// `SignalVectorExt::index_signals`.
+pub struct IndexSignals<S, T>(Arc<RwLock<IndexSignalsState<S, T>>>)
+where
+    S: StructuralSignal<Item = VectorEvent<T>>,
+    S: Unpin,
+    T: Clone;
+
+impl<S, T> IndexSignals<S, T>
+where
+    S: StructuralSignal<Item = VectorEvent<T>>,
+    S: Unpin,
+    T: Clone,
+{
+    pub(crate) fn new(input: S) -> IndexSignals<S, T> {
+        IndexSignals(Arc::new(RwLock::new(IndexSignalsState {
+            input,
+            snapshot: Vector::new(),
+            versions: Vector::new(),
+            next_version: 0,
+            wakers: vec![],
+            is_closed: false,
+        })))
+    }
+
+    /// Returns a Signal of the value at the given index. This tracks the position, not
+    /// the element: inserting or removing a value before the index shifts a different
+    /// value into it, which the Signal emits. While the index is past the end of the
+    /// vector the Signal emits nothing. Use `identify` to follow elements instead.
+    pub fn signal_for_index(&self, index: usize) -> IndexValueSignal<S, T> {
+        IndexValueSignal {
+            index,
+            parent: self.0.clone(),
+            updated_at: None,
+        }
+    }
+}
+
+/// A Signal of the value at one index, created by `IndexSignals::signal_for_index`.
+pub struct IndexValueSignal<S, T>
+where
+    S: StructuralSignal<Item = VectorEvent<T>>,
+    S: Unpin,
+    T: Clone,
+{
+    index: usize,
+    parent: Arc<RwLock<IndexSignalsState<S, T>>>,
+    updated_at: Option<usize>,
+}
+
+impl<S, T> Signal for IndexValueSignal<S, T>
+where
+    S: StructuralSignal<Item = VectorEvent<T>>,
+    S: Unpin,
+    T: Clone,
+{
+    type Item = T;
+
+    fn poll_change(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<T>> {
+        let index = self.index;
+        let parent = self.parent.clone();
+        let mut state = parent.write();
+        state.pull_in_new_changes(cx);
+
+        let version = state.versions.get(index).copied();
+        if version.is_none() || self.updated_at == version {
+            if version.is_none() {
+                // Emit the next value to land at this index, even if it was there before.
+                self.updated_at = None;
+            }
+            if state.is_closed {
+                return Poll::Ready(None);
+            }
+            // Signals are usually polled again with the same waker while they wait, and
+            // that waker is already registered.
+            if !state.wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+                state.wakers.push(cx.waker().clone());
+            }
+            return Poll::Pending;
+        }
+        self.updated_at = version;
+        Poll::Ready(Some(state.snapshot[index].clone()))
+    }
+}
+
+// ** FOLD ** //
+
+/// A Signal of an aggregate value folded over a vector signal. Created by
+/// `SignalVectorExt::fold`.
+#[pin_project(project = VectorFoldSignalProj)]
+#[must_use = "Signals do nothing unless polled"]
+pub struct VectorFoldSignal<S, B, F> {
+    #[pin]
+    signal: S,
+    init: B,
+    fold_fn: F,
+}
+
+impl<S, B, F> VectorFoldSignal<S, B, F> {
+    pub(crate) fn new(signal: S, init: B, fold_fn: F) -> VectorFoldSignal<S, B, F> {
+        VectorFoldSignal {
+            signal,
+            init,
+            fold_fn,
+        }
+    }
+}
+
+// Computes the aggregate after an event. For now this folds the whole snapshot, but
+// this is where aggregates that can be updated from the diffs alone would plug in.
+fn fold_event<T, B, F>(event: &VectorEvent<T>, init: &B, fold_fn: &F) -> B
+where
+    T: Clone,
+    B: Clone,
+    F: Fn(B, &T) -> B,
+{
+    event.snapshot.iter().fold(init.clone(), fold_fn)
+}
+
+impl<S, T, B, F> Signal for VectorFoldSignal<S, B, F>
+where
+    T: Clone,
+    S: StructuralSignal<Item = VectorEvent<T>>,
+    B: Clone,
+    F: Fn(B, &T) -> B,
+{
+    type Item = B;
+
+    fn poll_change(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<B>> {
+        let VectorFoldSignalProj {
+            signal,
+            init,
+            fold_fn,
+        } = self.project();
+        signal
+            .poll_change(cx)
+            .map(|maybe_event| maybe_event.map(|event| fold_event(&event, init, fold_fn)))
+    }
+}
+
+// ** ON_DIFF ** //
+
+/// A Future that drives a vector signal to completion, calling a function once for every
+/// individual diff along with the snapshot of the event it came from.
+#[pin_project(project = OnDiffFutureProj)]
+#[must_use = "Futures do nothing unless polled"]
+pub struct OnDiffFuture<S, F> {
+    #[pin]
+    signal: S,
+    diff_fn: F,
+}
+
+impl<S, F> OnDiffFuture<S, F> {
+    pub(crate) fn new(signal: S, diff_fn: F) -> OnDiffFuture<S, F> {
+        OnDiffFuture { signal, diff_fn }
+    }
+}
+
+impl<S, F, T> Future for OnDiffFuture<S, F>
+where
+    T: Clone,
+    S: StructuralSignal<Item = VectorEvent<T>>,
+    F: FnMut(&VectorDiff, &Vector<T>),
+{
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+        let OnDiffFutureProj {
+            mut signal,
+            diff_fn,
+        } = self.project();
+        loop {
+            match signal.as_mut().poll_change(cx) {
+                Poll::Ready(Some(event)) => {
+                    let event = event.expand_batches();
+                    for diff in event.diffs.iter() {
+                        diff_fn(diff, &event.snapshot);
+                    }
+                }
+                Poll::Ready(None) => return Poll::Ready(()),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
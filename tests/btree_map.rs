@@ -0,0 +1,66 @@
+use im::{ordmap, vector};
+use signals_im::btree_map::{BTreeMapDiff, MutableBTreeMap, SignalBTreeMapExt};
+use signals_im::vector::VectorDiff;
+
+mod util;
+
+#[test]
+fn as_signal() {
+    let input_map = MutableBTreeMap::<u8, u8>::new();
+    input_map.write().insert(2, 2);
+
+    let mut signal = input_map.as_signal();
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(util::get_snapshots(&poll.items), vec![ordmap! {2 => 2}]);
+
+    input_map.write().insert(1, 1);
+    input_map.write().remove(&2);
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(util::get_snapshots(&poll.items), vec![ordmap! {1 => 1}]);
+    assert_eq!(
+        poll.items[0].diffs,
+        vec![
+            BTreeMapDiff::Insert { key: 1 },
+            BTreeMapDiff::Remove { key: 2 }
+        ]
+    );
+}
+
+#[test]
+fn entries_insert_at_sorted_index() {
+    let input_map = MutableBTreeMap::<u8, u8>::new();
+    input_map.write().insert(1, 10);
+    input_map.write().insert(5, 50);
+
+    let mut entries = input_map.as_signal().entries();
+    let poll = util::poll_all(&mut entries);
+    assert_eq!(
+        util::get_snapshots(&poll.items),
+        vec![vector![(1, 10), (5, 50)]]
+    );
+
+    input_map.write().insert(3, 30);
+    let poll = util::poll_all(&mut entries);
+    assert_eq!(
+        util::get_vector_diffs(&poll.items),
+        vec![VectorDiff::Insert {
+            index: 1,
+            snapshot_index: 1
+        }]
+    );
+
+    input_map.write().insert(5, 51);
+    input_map.write().remove(&1);
+    let poll = util::poll_all(&mut entries);
+    assert_eq!(
+        util::get_snapshots(&poll.items).pop().unwrap(),
+        vector![(3, 30), (5, 51)]
+    );
+
+    input_map.write().clear();
+    let poll = util::poll_all(&mut entries);
+    assert_eq!(
+        util::get_vector_diffs(&poll.items),
+        vec![VectorDiff::Clear {}]
+    );
+}
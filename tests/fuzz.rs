@@ -1,7 +1,8 @@
-use im::Vector;
+use im::{HashMap, Vector};
 use rand::random;
-use signals_im::hash_map::{MutableHashMap, SignalHashMapExt};
-use signals_im::vector::{MutableVector, SignalVectorExt};
+use signals_im::hash_map::{MapDiff, MutableHashMap, SignalHashMapExt};
+use signals_im::testing::DiffCoalescingHarness;
+use signals_im::vector::{MutableVector, SignalVectorExt, VectorDiff};
 
 mod util;
 
@@ -114,3 +115,140 @@ fn fuzz_test_vector_signal() {
         last_state = Some(last_snapshot.clone());
     }
 }
+
+#[test]
+fn fuzz_test_map_diff_coalescing() {
+    let mut harness = DiffCoalescingHarness::new(HashMap::<u8, u8>::new());
+    for i in 0..200 {
+        for _ in 0..i {
+            let key = random::<u8>() % 16;
+            let opt = random::<f32>();
+            if opt < 0.5 {
+                let was_present = harness.state_mut().insert(key, random()).is_some();
+                harness.add_diff(if was_present {
+                    MapDiff::Update { key }
+                } else {
+                    MapDiff::Insert { key }
+                });
+            } else if opt < 0.95 {
+                if harness.state_mut().remove(&key).is_some() {
+                    harness.add_diff(MapDiff::Remove { key });
+                }
+            } else if !harness.state_mut().is_empty() {
+                harness.state_mut().clear();
+                harness.add_diff(MapDiff::Clear {});
+            }
+        }
+        harness.assert_coalesced_diffs_replay();
+    }
+}
+
+// Returns where the value at `index` ends up when the value at `from` is moved to `to`.
+fn moved_index(index: usize, from: usize, to: usize) -> usize {
+    if index == from {
+        to
+    } else if from < to && index > from && index <= to {
+        index - 1
+    } else if to < from && index >= to && index < from {
+        index + 1
+    } else {
+        index
+    }
+}
+
+#[test]
+fn fuzz_test_vector_diff_coalescing() {
+    let mut harness = DiffCoalescingHarness::new(Vector::<u8>::new());
+    for i in 0..200 {
+        // Like MutableVector, removes after a Move fall back to a Replace until the
+        // Move has been pulled.
+        let mut move_pending = false;
+        for _ in 0..i {
+            let len = harness.state_mut().len();
+            let opt = random::<f32>();
+            if opt < 0.4 {
+                let index = random::<usize>() % (len + 1);
+                harness.state_mut().insert(index, random());
+                harness.update_keys(|i| if *i >= index { *i + 1 } else { *i });
+                harness.add_diff(VectorDiff::Insert { index, snapshot_index: index });
+            } else if opt < 0.7 && len > 0 {
+                let index = random::<usize>() % len;
+                harness.state_mut().remove(index);
+                if move_pending {
+                    move_pending = false;
+                    harness.add_diff(VectorDiff::Replace {});
+                    continue;
+                }
+                harness.add_diff(VectorDiff::Remove { index, snapshot_index: index });
+                harness.update_keys(|i| if *i > index { *i - 1 } else { *i });
+            } else if opt < 0.85 && len > 1 {
+                let from = random::<usize>() % len;
+                let to = random::<usize>() % len;
+                if from == to {
+                    continue;
+                }
+                let value = harness.state_mut().remove(from);
+                harness.state_mut().insert(to, value);
+                harness.update_keys(|i| moved_index(*i, from, to));
+                harness.add_diff(VectorDiff::Move {
+                    from,
+                    to,
+                    snapshot_from: from,
+                    snapshot_to: to,
+                });
+                move_pending = true;
+            } else if len > 0 {
+                let index = random::<usize>() % len;
+                harness.state_mut().set(index, random());
+                harness.add_diff(VectorDiff::Update { index, snapshot_index: index });
+            }
+        }
+        harness.assert_coalesced_diffs_replay();
+    }
+}
+
+#[test]
+fn fuzz_test_map_diffs_are_deterministic() {
+    // Each mutation is a key and the value to insert, or None to remove the key.
+    let mutations: Vec<(u8, Option<u8>)> = (0..2000)
+        .map(|_| (random::<u8>() % 32, if random::<bool>() { Some(random()) } else { None }))
+        .collect();
+
+    let run = |mutations: &Vec<(u8, Option<u8>)>| {
+        let input_map = MutableHashMap::<u8, u8>::new();
+        let mut signal = input_map.as_signal();
+        util::poll_all(&mut signal);
+        let mut harness = DiffCoalescingHarness::new(HashMap::<u8, u8>::new());
+
+        let mut pulled_diffs = vec![];
+        let mut pull_source_snapshots = vec![];
+        for batch in mutations.chunks(50) {
+            for (key, maybe_value) in batch {
+                match maybe_value {
+                    Some(value) => {
+                        input_map.write().insert(*key, *value);
+                        let was_present = harness.state_mut().insert(*key, *value).is_some();
+                        harness.add_diff(if was_present {
+                            MapDiff::Update { key: *key }
+                        } else {
+                            MapDiff::Insert { key: *key }
+                        });
+                    }
+                    None => {
+                        input_map.write().remove(key);
+                        if harness.state_mut().remove(key).is_some() {
+                            harness.add_diff(MapDiff::Remove { key: *key });
+                        }
+                    }
+                }
+            }
+            pull_source_snapshots.push(harness.pull_source_snapshot());
+            harness.assert_coalesced_diffs_replay();
+            let poll = util::poll_all(&mut signal);
+            pulled_diffs.push(util::get_hash_map_diffs(&poll.items));
+        }
+        (pulled_diffs, pull_source_snapshots)
+    };
+
+    assert_eq!(run(&mutations), run(&mutations));
+}
@@ -1,6 +1,26 @@
-use im::hashmap;
-use signals_im::hash_map::MapDiff;
-use signals_im::hash_map::{MutableHashMap, SignalHashMapExt};
+use futures::channel::mpsc;
+use futures::task::{noop_waker_ref, waker, ArcWake};
+use futures_signals::signal::Signal;
+use futures_signals::signal_map::MutableBTreeMap;
+use futures_signals::signal_vec::{SignalVec, VecDiff};
+use im::{hashmap, vector};
+use signals_im::hash_map::{
+    FilterHashMapTransformer, HashMapEvent, MapDiff, MapValuesOptions, TryMapHashMapTransformer,
+};
+use signals_im::hash_map::{diff_maps, signal_map_to_structural, MutableHashMap, SignalHashMapExt};
+use signals_im::testing::DiffTarget;
+use signals_im::vector::{SignalVectorExt, VectorDiff};
+use signals_im::{
+    ChannelStructuralSignal, FallibleStructuralSignalTransformer, StructuralSignal,
+    StructuralSignalExt, StructuralSignalTransformer,
+};
+use std::hash::{Hash, Hasher};
+use std::iter::FromIterator;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
 
 mod util;
 
@@ -49,3 +69,1174 @@ fn map_values() {
         ]
     );
 }
+
+#[test]
+fn filter_applies_every_diff_in_an_event() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    input_map.write().insert(1, 1);
+    input_map.write().insert(3, 3);
+
+    let mut odds_only = input_map.as_signal().filter(|v| v % 2 == 1);
+    util::poll_all(&mut odds_only);
+
+    input_map.write().insert(5, 5);
+    input_map.write().insert(3, 4);
+    input_map.write().insert(7, 7);
+    let poll = util::poll_all(&mut odds_only);
+    assert_eq!(
+        *util::get_snapshots(&poll.items).last().unwrap(),
+        hashmap! {1 => 1, 5 => 5, 7 => 7}
+    );
+    assert_eq!(
+        util::get_hash_map_diffs(&poll.items),
+        vec![
+            MapDiff::Insert { key: 5 },
+            MapDiff::Remove { key: 3 },
+            MapDiff::Insert { key: 7 },
+        ]
+    );
+}
+
+#[test]
+fn try_into_inner() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    input_map.write().insert(1, 1);
+
+    let signal = input_map.as_signal();
+    let input_map = input_map.try_into_inner().err().unwrap();
+    assert_eq!(input_map.read().snapshot(), hashmap! {1 => 1});
+
+    drop(signal);
+    assert_eq!(input_map.try_into_inner().ok().unwrap(), hashmap! {1 => 1});
+}
+
+#[test]
+fn map_values_forwards_clear() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    input_map.write().insert(1, 1);
+    input_map.write().insert(2, 2);
+
+    let mut multiplied = input_map.as_signal().map_values(|v| v * 2);
+    util::poll_all(&mut multiplied);
+
+    input_map.write().clear();
+    let poll_1 = util::poll_all(&mut multiplied);
+    assert_eq!(
+        *util::get_snapshots(&poll_1.items).last().unwrap(),
+        hashmap! {}
+    );
+    assert_eq!(
+        util::get_hash_map_diffs(&poll_1.items),
+        vec![MapDiff::Clear {}]
+    );
+
+    input_map.write().insert(3, 3);
+    input_map.write().clear();
+    input_map.write().insert(4, 4);
+    let poll_2 = util::poll_all(&mut multiplied);
+    assert_eq!(
+        *util::get_snapshots(&poll_2.items).last().unwrap(),
+        hashmap! {4 => 8}
+    );
+    assert_eq!(
+        util::get_hash_map_diffs(&poll_2.items),
+        vec![MapDiff::Insert { key: 4 }]
+    );
+}
+
+#[test]
+fn filter_forwards_clear() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    input_map.write().insert(1, 1);
+    input_map.write().insert(2, 2);
+
+    let mut odds_only = input_map.as_signal().filter(|v| v % 2 == 1);
+    util::poll_all(&mut odds_only);
+
+    input_map.write().clear();
+    input_map.write().insert(3, 3);
+    input_map.write().insert(5, 5);
+    let poll = util::poll_all(&mut odds_only);
+    assert_eq!(
+        *util::get_snapshots(&poll.items).last().unwrap(),
+        hashmap! {3 => 3, 5 => 5}
+    );
+    assert_eq!(
+        util::get_hash_map_diffs(&poll.items),
+        vec![
+            MapDiff::Clear {},
+            MapDiff::Insert { key: 3 },
+            MapDiff::Insert { key: 5 },
+        ]
+    );
+}
+
+#[test]
+fn map_values_mut_counts_calls_in_diff_order() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    input_map.write().insert(1, 10);
+
+    let mut calls = 0;
+    let mut numbered = input_map.as_signal().map_values_mut(move |v| {
+        calls += 1;
+        (calls, *v)
+    });
+    let poll = util::poll_all(&mut numbered);
+    assert_eq!(*util::get_snapshots(&poll.items).last().unwrap(), hashmap! {1 => (1, 10)});
+
+    input_map.write().insert(3, 30);
+    input_map.write().insert(2, 20);
+    input_map.write().insert(1, 11);
+    let poll = util::poll_all(&mut numbered);
+    assert_eq!(
+        *util::get_snapshots(&poll.items).last().unwrap(),
+        hashmap! {1 => (4, 11), 2 => (3, 20), 3 => (2, 30)}
+    );
+}
+
+#[test]
+fn filter_mut_with_budget() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    let mut budget = 2;
+    let mut limited = input_map.as_signal().filter_mut(move |_v| {
+        budget -= 1;
+        budget >= 0
+    });
+    util::poll_all(&mut limited);
+
+    input_map.write().insert(1, 1);
+    input_map.write().insert(2, 2);
+    input_map.write().insert(3, 3);
+    let poll = util::poll_all(&mut limited);
+    assert_eq!(*util::get_snapshots(&poll.items).last().unwrap(), hashmap! {1 => 1, 2 => 2});
+}
+
+#[test]
+fn filter_hand_crafted_events() {
+    let mut transformer = FilterHashMapTransformer::new(|v: &u8| v % 2 == 1);
+    transformer.apply_event(HashMapEvent::new(
+        hashmap! {1 => 1, 2 => 2},
+        vec![MapDiff::Replace {}],
+    ));
+    assert_eq!(transformer.get_signal().snapshot().unwrap(), hashmap! {1 => 1});
+
+    transformer.apply_event(HashMapEvent::new(
+        hashmap! {1 => 2, 2 => 2, 3 => 3},
+        vec![MapDiff::Update { key: 1 }, MapDiff::Insert { key: 3 }],
+    ));
+    assert_eq!(transformer.get_signal().snapshot().unwrap(), hashmap! {3 => 3});
+}
+
+#[test]
+fn granular_signal_initial_event() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    input_map.write().insert(1, 1);
+    input_map.write().insert(2, 2);
+    input_map.write().insert(3, 3);
+
+    let mut signal = input_map.reader().as_signal_granular();
+    let poll_1 = util::poll_all(&mut signal);
+    let mut diffs = util::get_hash_map_diffs(&poll_1.items);
+    diffs.sort_by_key(|diff| match diff {
+        MapDiff::Insert { key } => *key,
+        _ => panic!("Expected only inserts"),
+    });
+    assert_eq!(
+        diffs,
+        vec![
+            MapDiff::Insert { key: 1 },
+            MapDiff::Insert { key: 2 },
+            MapDiff::Insert { key: 3 },
+        ]
+    );
+
+    input_map.write().insert(2, 4);
+    let poll_2 = util::poll_all(&mut signal);
+    assert_eq!(
+        util::get_hash_map_diffs(&poll_2.items),
+        vec![MapDiff::Update { key: 2 }]
+    );
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct CollidingKey(u8);
+
+impl Hash for CollidingKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        0u8.hash(state);
+    }
+}
+
+#[test]
+fn entries_with_colliding_hashes() {
+    let input_map = MutableHashMap::<CollidingKey, u8>::new();
+    input_map.write().insert(CollidingKey(2), 2);
+    input_map.write().insert(CollidingKey(0), 0);
+
+    let mut entries = input_map.as_signal().entries();
+    util::poll_all(&mut entries);
+
+    input_map.write().insert(CollidingKey(1), 1);
+    input_map.write().insert(CollidingKey(3), 3);
+    input_map.write().insert(CollidingKey(2), 4);
+    input_map.write().remove(&CollidingKey(0));
+    let poll = util::poll_all(&mut entries);
+    assert_eq!(
+        *util::get_snapshots(&poll.items).last().unwrap(),
+        vector![(CollidingKey(1), 1), (CollidingKey(2), 4), (CollidingKey(3), 3)]
+    );
+}
+
+#[test]
+fn entries_to_signal_vec() {
+    let input_map = MutableHashMap::<CollidingKey, u8>::new();
+    input_map.write().insert(CollidingKey(1), 1);
+    input_map.write().insert(CollidingKey(3), 3);
+
+    // Colliding keys are ordered by key, which makes the entry indices predictable.
+    let mut signal_vec = input_map.as_signal().entries().to_signal_vec();
+    let mut cx = Context::from_waker(noop_waker_ref());
+    assert_eq!(
+        Pin::new(&mut signal_vec).poll_vec_change(&mut cx),
+        Poll::Ready(Some(VecDiff::Replace {
+            values: vec![(CollidingKey(1), 1), (CollidingKey(3), 3)]
+        }))
+    );
+
+    input_map.write().insert(CollidingKey(2), 2);
+    assert_eq!(
+        Pin::new(&mut signal_vec).poll_vec_change(&mut cx),
+        Poll::Ready(Some(VecDiff::InsertAt { index: 1, value: (CollidingKey(2), 2) }))
+    );
+    assert_eq!(Pin::new(&mut signal_vec).poll_vec_change(&mut cx), Poll::Pending);
+
+    input_map.write().insert(CollidingKey(3), 30);
+    assert_eq!(
+        Pin::new(&mut signal_vec).poll_vec_change(&mut cx),
+        Poll::Ready(Some(VecDiff::UpdateAt { index: 2, value: (CollidingKey(3), 30) }))
+    );
+
+    input_map.write().remove(&CollidingKey(1));
+    assert_eq!(
+        Pin::new(&mut signal_vec).poll_vec_change(&mut cx),
+        Poll::Ready(Some(VecDiff::RemoveAt { index: 0 }))
+    );
+    assert_eq!(Pin::new(&mut signal_vec).poll_vec_change(&mut cx), Poll::Pending);
+}
+
+#[test]
+fn try_map_values_reports_error() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    input_map.write().insert(1, 1);
+    input_map.write().insert(2, 2);
+
+    let mut divided = input_map
+        .as_signal()
+        .try_map_values(|v| 10u8.checked_div(*v).ok_or("divide by zero"));
+    let poll_1 = util::poll_all(&mut divided);
+    assert!(!poll_1.is_done);
+    assert_eq!(poll_1.items.len(), 1);
+    assert_eq!(
+        poll_1.items[0].as_ref().unwrap().snapshot,
+        hashmap! {1 => 10, 2 => 5}
+    );
+
+    input_map.write().insert(3, 0);
+    let poll_2 = util::poll_all(&mut divided);
+    assert!(poll_2.is_done);
+    assert_eq!(poll_2.items, vec![Err("divide by zero")]);
+}
+
+#[test]
+fn try_map_values_error_leaves_state_untouched() {
+    let mut transformer =
+        TryMapHashMapTransformer::new(|v: &u8| 10u8.checked_div(*v).ok_or("divide by zero"));
+    transformer
+        .apply_event(HashMapEvent::new(
+            hashmap! {1 => 1},
+            vec![MapDiff::Replace {}],
+        ))
+        .unwrap();
+
+    let result = transformer.apply_event(HashMapEvent::new(
+        hashmap! {1 => 1, 2 => 2, 3 => 0},
+        vec![MapDiff::Insert { key: 2 }, MapDiff::Insert { key: 3 }],
+    ));
+    assert_eq!(result, Err("divide by zero"));
+    assert_eq!(transformer.get_signal().snapshot().unwrap(), hashmap! {1 => 10});
+}
+
+#[test]
+fn modify_or_default() {
+    let input_map = MutableHashMap::<u8, Vec<u8>>::new();
+    let mut signal = input_map.as_signal();
+    util::poll_all(&mut signal);
+
+    input_map.write().modify_or_default(1, |v| v.push(1));
+    let poll_1 = util::poll_all(&mut signal);
+    assert_eq!(
+        util::get_hash_map_diffs(&poll_1.items),
+        vec![MapDiff::Insert { key: 1 }]
+    );
+
+    input_map.write().modify_or_default(1, |v| v.push(2));
+    input_map.write().modify_or_default(1, |v| v.push(3));
+    let poll_2 = util::poll_all(&mut signal);
+    assert_eq!(
+        *util::get_snapshots(&poll_2.items).last().unwrap(),
+        hashmap! {1 => vec![1, 2, 3]}
+    );
+    assert_eq!(
+        util::get_hash_map_diffs(&poll_2.items),
+        vec![MapDiff::Update { key: 1 }]
+    );
+}
+
+struct CountingWaker(AtomicUsize);
+
+impl ArcWake for CountingWaker {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn map_values_yield_budget() {
+    let (sender, receiver) = mpsc::unbounded();
+    let mut snapshot = hashmap! {};
+    for i in 0..10u8 {
+        snapshot.insert(i, i);
+        sender
+            .unbounded_send(HashMapEvent::new(snapshot.clone(), vec![MapDiff::Insert { key: i }]))
+            .unwrap();
+    }
+
+    let map_count = AtomicUsize::new(0);
+    let mut multiplied = ChannelStructuralSignal::new(receiver)
+        .map_values(|v| {
+            map_count.fetch_add(1, Ordering::SeqCst);
+            v * 2
+        })
+        .with_yield_budget(4);
+
+    let counting_waker = Arc::new(CountingWaker(AtomicUsize::new(0)));
+    let waker = waker(counting_waker.clone());
+    let mut cx = Context::from_waker(&waker);
+
+    assert!(Pin::new(&mut multiplied).poll_change(&mut cx).is_pending());
+    assert_eq!(map_count.load(Ordering::SeqCst), 4);
+    assert!(Pin::new(&mut multiplied).poll_change(&mut cx).is_pending());
+    assert_eq!(map_count.load(Ordering::SeqCst), 8);
+    assert_eq!(counting_waker.0.load(Ordering::SeqCst), 2);
+
+    match Pin::new(&mut multiplied).poll_change(&mut cx) {
+        Poll::Ready(Some(event)) => assert_eq!(event.snapshot.len(), 10),
+        _ => panic!("Expected the remaining events to be applied"),
+    }
+    assert_eq!(map_count.load(Ordering::SeqCst), 10);
+}
+
+#[test]
+fn diagnostic_counts() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    input_map.write().insert(1, 1);
+    assert_eq!(input_map.listening_signal_count(), 0);
+    assert_eq!(input_map.pending_diff_count(), 0);
+
+    // Signals only start listening once they are first polled.
+    let mut signal = input_map.as_signal();
+    input_map.write().insert(2, 2);
+    assert_eq!(input_map.pending_diff_count(), 0);
+
+    util::poll_all(&mut signal);
+    assert_eq!(input_map.listening_signal_count(), 1);
+
+    input_map.write().insert(3, 3);
+    input_map.write().insert(3, 4);
+    input_map.write().insert(4, 4);
+    assert_eq!(input_map.pending_diff_count(), 2);
+    assert_eq!(input_map.keyed_diff_count(), 2);
+
+    util::poll_all(&mut signal);
+    assert_eq!(input_map.keyed_diff_count(), 0);
+}
+
+#[test]
+fn replace_returning_and_take() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    input_map.write().insert(1, 1);
+    let mut signal = input_map.as_signal();
+    util::poll_all(&mut signal);
+
+    let previous = input_map
+        .write()
+        .replace_returning(vec![(2, 2), (3, 3)].into_iter());
+    assert_eq!(previous, hashmap! {1 => 1});
+    let poll_1 = util::poll_all(&mut signal);
+    assert_eq!(
+        *util::get_snapshots(&poll_1.items).last().unwrap(),
+        hashmap! {2 => 2, 3 => 3}
+    );
+    assert_eq!(util::get_hash_map_diffs(&poll_1.items), vec![MapDiff::Replace {}]);
+
+    assert_eq!(input_map.write().take(), hashmap! {2 => 2, 3 => 3});
+    let poll_2 = util::poll_all(&mut signal);
+    assert_eq!(util::get_hash_map_diffs(&poll_2.items), vec![MapDiff::Clear {}]);
+
+    assert_eq!(input_map.write().take(), hashmap! {});
+    assert!(util::poll_all(&mut signal).items.is_empty());
+}
+
+#[test]
+fn insert_if_absent() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    input_map.write().insert(1, 1);
+    let mut signal = input_map.as_signal();
+    util::poll_all(&mut signal);
+
+    assert!(!input_map.write().insert_if_absent(1, 2));
+    assert!(util::poll_all(&mut signal).items.is_empty());
+    assert_eq!(input_map.read().get(&1), Some(&1));
+
+    assert!(input_map.write().insert_if_absent(2, 2));
+    let poll_1 = util::poll_all(&mut signal);
+    assert_eq!(util::get_hash_map_diffs(&poll_1.items), vec![MapDiff::Insert { key: 2 }]);
+
+    let inserted_count = input_map
+        .write()
+        .insert_many_if_absent(vec![(2, 3), (3, 3)].into_iter());
+    assert_eq!(inserted_count, 1);
+    let poll_2 = util::poll_all(&mut signal);
+    assert_eq!(
+        *util::get_snapshots(&poll_2.items).last().unwrap(),
+        hashmap! {1 => 1, 2 => 2, 3 => 3}
+    );
+    assert_eq!(util::get_hash_map_diffs(&poll_2.items), vec![MapDiff::Insert { key: 3 }]);
+}
+
+#[test]
+fn rename_key() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    input_map.write().insert(1, 10);
+    input_map.write().insert(2, 20);
+    let mut signal = input_map.as_signal();
+    util::poll_all(&mut signal);
+
+    assert!(input_map.write().rename_key(&1, 3));
+    let poll_1 = util::poll_all(&mut signal);
+    assert_eq!(
+        *util::get_snapshots(&poll_1.items).last().unwrap(),
+        hashmap! {2 => 20, 3 => 10}
+    );
+    assert_eq!(
+        util::get_hash_map_diffs(&poll_1.items),
+        vec![MapDiff::Remove { key: 1 }, MapDiff::Insert { key: 3 }]
+    );
+
+    assert!(!input_map.write().rename_key(&1, 4));
+    assert!(util::poll_all(&mut signal).items.is_empty());
+    assert_eq!(input_map.read().snapshot(), hashmap! {2 => 20, 3 => 10});
+}
+
+#[test]
+fn rename_key_to_itself() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    input_map.write().insert(0, 10);
+    let mut signal = input_map.as_signal().entries();
+    util::poll_all(&mut signal);
+
+    assert!(input_map.write().rename_key(&0, 0));
+    assert!(util::poll_all(&mut signal).items.is_empty());
+    assert!(!input_map.write().rename_key(&1, 1));
+    assert!(util::poll_all(&mut signal).items.is_empty());
+    assert_eq!(input_map.read().snapshot(), hashmap! {0 => 10});
+}
+
+#[test]
+fn retain_removes_failing_keys() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    input_map.write().insert(1, 1);
+    input_map.write().insert(2, 2);
+    input_map.write().insert(3, 3);
+    let mut signal = input_map.as_signal();
+    let mut key_2 = input_map.as_signal().get_signal_for_key(2);
+    let mut cx = Context::from_waker(noop_waker_ref());
+    util::poll_all(&mut signal);
+    assert_eq!(Signal::poll_change(Pin::new(&mut key_2), &mut cx), Poll::Ready(Some(Some(2))));
+
+    input_map.write().retain(|_k, v| v % 2 == 0);
+    let poll = util::poll_all(&mut signal);
+    let diffs = util::get_hash_map_diffs(&poll.items);
+    assert_eq!(diffs.len(), 2);
+    assert!(diffs.contains(&MapDiff::Remove { key: 1 }));
+    assert!(diffs.contains(&MapDiff::Remove { key: 3 }));
+    assert_eq!(*util::get_snapshots(&poll.items).last().unwrap(), hashmap! {2 => 2});
+    assert_eq!(Signal::poll_change(Pin::new(&mut key_2), &mut cx), Poll::Pending);
+
+    // Nothing fails the predicate, so nothing is emitted.
+    input_map.write().retain(|_k, _v| true);
+    assert!(util::poll_all(&mut signal).items.is_empty());
+}
+
+#[test]
+fn snapshot_changed_since() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    input_map.write().insert(1, 1);
+    let snapshot = input_map.read().snapshot();
+    assert!(!input_map.read().snapshot_changed_since(&snapshot));
+    assert!(!input_map.read().snapshot_changed_since(&snapshot.clone()));
+
+    input_map.write().insert(2, 2);
+    assert!(input_map.read().snapshot_changed_since(&snapshot));
+}
+
+#[test]
+fn entry_signals_forget_removed_keys() {
+    let input_map = MutableHashMap::<u16, u16>::new();
+    input_map.write().insert(0, 0);
+    let entry_signals = input_map.as_signal().entries_signal_map();
+    let mut value_0 = entry_signals.signal_for_key(0);
+    let mut cx = Context::from_waker(noop_waker_ref());
+    assert_eq!(Signal::poll_change(Pin::new(&mut value_0), &mut cx), Poll::Ready(Some(0)));
+
+    for key in 1..1000 {
+        input_map.write().insert(key, key);
+        assert_eq!(Signal::poll_change(Pin::new(&mut value_0), &mut cx), Poll::Pending);
+        input_map.write().remove(&key);
+        assert_eq!(Signal::poll_change(Pin::new(&mut value_0), &mut cx), Poll::Pending);
+        assert_eq!(entry_signals.tracked_key_count(), 1);
+    }
+}
+
+#[test]
+fn from_iter() {
+    let input_map = MutableHashMap::from_iter(vec![(1, 1), (2, 2)]);
+    assert_eq!(input_map.read().snapshot(), hashmap! {1 => 1, 2 => 2});
+
+    let collected: MutableHashMap<u8, u8> = (1..3).map(|k| (k, k * 10)).collect();
+    let mut signal = collected.as_signal();
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(util::get_snapshots(&poll.items), vec![hashmap! {1 => 10, 2 => 20}]);
+    assert_eq!(util::get_hash_map_diffs(&poll.items), vec![MapDiff::Replace {}]);
+}
+
+#[test]
+fn dirty_signal_ticks_once_per_batch() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    let mut dirty = input_map.dirty_signal();
+    let mut cx = Context::from_waker(noop_waker_ref());
+
+    assert_eq!(Signal::poll_change(Pin::new(&mut dirty), &mut cx), Poll::Ready(Some(())));
+    assert_eq!(Signal::poll_change(Pin::new(&mut dirty), &mut cx), Poll::Pending);
+
+    input_map.write().insert(1, 1);
+    input_map.write().insert(2, 2);
+    input_map.write().remove(&1);
+    assert_eq!(Signal::poll_change(Pin::new(&mut dirty), &mut cx), Poll::Ready(Some(())));
+    assert_eq!(Signal::poll_change(Pin::new(&mut dirty), &mut cx), Poll::Pending);
+    assert_eq!(input_map.read().snapshot(), hashmap! {2 => 2});
+}
+
+#[test]
+fn map_values_panic_does_not_tear_event() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    input_map.write().insert(1, 1);
+    let mut multiplied = input_map.as_signal().map_values(|v| {
+        if *v == 3 {
+            panic!("Cannot map 3");
+        }
+        v * 2
+    });
+    util::poll_all(&mut multiplied);
+
+    // The panic happens on the second diff of the event, after the first was mapped.
+    input_map.write().insert(2, 2);
+    input_map.write().insert(3, 3);
+    let result = catch_unwind(AssertUnwindSafe(|| util::poll_all(&mut multiplied)));
+    assert!(result.is_err());
+
+    // The failed event was never applied, so the next event catches the output up to
+    // the input with a full Replace, rather than applying only its own diffs.
+    input_map.write().remove(&3);
+    input_map.write().insert(4, 4);
+    let poll = util::poll_all(&mut multiplied);
+    assert_eq!(util::get_hash_map_diffs(&poll.items), vec![MapDiff::Replace {}]);
+    assert_eq!(
+        *util::get_snapshots(&poll.items).last().unwrap(),
+        hashmap! {1 => 2, 2 => 4, 4 => 8}
+    );
+
+    // After that, events are applied as usual.
+    input_map.write().insert(5, 5);
+    let poll = util::poll_all(&mut multiplied);
+    assert_eq!(util::get_hash_map_diffs(&poll.items), vec![MapDiff::Insert { key: 5 }]);
+}
+
+#[test]
+fn entry_signals_complete_on_remove() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    input_map.write().insert(1, 1);
+    input_map.write().insert(2, 2);
+
+    let entry_signals = input_map.as_signal().entries_signal_map();
+    let mut value_1 = entry_signals.signal_for_key(1);
+    let mut value_2 = entry_signals.signal_for_key(2);
+    let mut cx = Context::from_waker(noop_waker_ref());
+    assert_eq!(Signal::poll_change(Pin::new(&mut value_1), &mut cx), Poll::Ready(Some(1)));
+    assert_eq!(Signal::poll_change(Pin::new(&mut value_1), &mut cx), Poll::Pending);
+    assert_eq!(Signal::poll_change(Pin::new(&mut value_2), &mut cx), Poll::Ready(Some(2)));
+
+    input_map.write().insert(1, 10);
+    assert_eq!(Signal::poll_change(Pin::new(&mut value_1), &mut cx), Poll::Ready(Some(10)));
+    assert_eq!(Signal::poll_change(Pin::new(&mut value_2), &mut cx), Poll::Pending);
+
+    input_map.write().remove(&1);
+    assert_eq!(Signal::poll_change(Pin::new(&mut value_1), &mut cx), Poll::Ready(None));
+    assert_eq!(Signal::poll_change(Pin::new(&mut value_2), &mut cx), Poll::Pending);
+
+    // Keys that are not in the map complete right away.
+    let mut value_3 = entry_signals.signal_for_key(3);
+    assert_eq!(Signal::poll_change(Pin::new(&mut value_3), &mut cx), Poll::Ready(None));
+}
+
+#[test]
+fn replace_empty_with_empty_is_noop() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    let mut signal = input_map.as_signal();
+    util::poll_all(&mut signal);
+
+    input_map.write().replace(vec![].into_iter());
+    assert!(util::poll_all(&mut signal).items.is_empty());
+
+    input_map.write().insert(1, 1);
+    util::poll_all(&mut signal);
+    input_map.write().replace(vec![].into_iter());
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(util::get_hash_map_diffs(&poll.items), vec![MapDiff::Replace {}]);
+    assert_eq!(*util::get_snapshots(&poll.items).last().unwrap(), hashmap! {});
+}
+
+#[test]
+fn coalesce_diffs() {
+    assert_eq!(
+        signals_im::coalesce_diffs(vec![MapDiff::Insert { key: 'a' }, MapDiff::Remove { key: 'a' }]),
+        vec![]
+    );
+    assert_eq!(
+        signals_im::coalesce_diffs(vec![MapDiff::Insert { key: 'a' }, MapDiff::Update { key: 'a' }]),
+        vec![MapDiff::Insert { key: 'a' }]
+    );
+    assert_eq!(
+        signals_im::coalesce_diffs(vec![MapDiff::Remove { key: 'a' }, MapDiff::Insert { key: 'a' }]),
+        vec![MapDiff::Update { key: 'a' }]
+    );
+    assert_eq!(
+        signals_im::coalesce_diffs(vec![
+            MapDiff::Remove { key: 'a' },
+            MapDiff::Insert { key: 'a' },
+            MapDiff::Remove { key: 'a' },
+        ]),
+        vec![MapDiff::Remove { key: 'a' }]
+    );
+
+    let event = HashMapEvent::new(
+        hashmap! {'b' => 2},
+        vec![
+            MapDiff::Insert { key: 'a' },
+            MapDiff::Insert { key: 'b' },
+            MapDiff::Remove { key: 'a' },
+        ],
+    );
+    assert_eq!(event.coalesced().diffs, vec![MapDiff::Insert { key: 'b' }]);
+}
+
+#[test]
+fn to_sorted() {
+    let input_map = MutableHashMap::<u8, char>::new();
+    input_map.write().insert(5, 'e');
+    input_map.write().insert(1, 'a');
+
+    let mut sorted = input_map.as_signal().to_sorted();
+    let poll_1 = util::poll_all(&mut sorted);
+    assert_eq!(*util::get_snapshots(&poll_1.items).last().unwrap(), vector![(1, 'a'), (5, 'e')]);
+
+    input_map.write().insert(3, 'c');
+    input_map.write().insert(9, 'i');
+    input_map.write().insert(0, '_');
+    input_map.write().insert(5, 'E');
+    input_map.write().remove(&1);
+    let poll_2 = util::poll_all(&mut sorted);
+    assert_eq!(
+        *util::get_snapshots(&poll_2.items).last().unwrap(),
+        vector![(0, '_'), (3, 'c'), (5, 'E'), (9, 'i')]
+    );
+}
+
+#[test]
+fn signals_activate_on_first_poll() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+
+    // Signals that are created and dropped without being polled are never counted.
+    drop(input_map.as_signal());
+    let mut signal = input_map.as_signal();
+    input_map.write().insert(1, 1);
+    assert_eq!(input_map.listening_signal_count(), 0);
+    assert_eq!(input_map.pending_diff_count(), 0);
+
+    // The first poll describes everything that happened before it as a full replace.
+    let poll_1 = util::poll_all(&mut signal);
+    assert_eq!(input_map.listening_signal_count(), 1);
+    assert_eq!(util::get_hash_map_diffs(&poll_1.items), vec![MapDiff::Replace {}]);
+    assert_eq!(*util::get_snapshots(&poll_1.items).last().unwrap(), hashmap! {1 => 1});
+
+    // Only changes made after the first poll are tracked and coalesced.
+    input_map.write().insert(2, 2);
+    input_map.write().insert(2, 3);
+    assert_eq!(input_map.pending_diff_count(), 1);
+    let poll_2 = util::poll_all(&mut signal);
+    assert_eq!(util::get_hash_map_diffs(&poll_2.items), vec![MapDiff::Insert { key: 2 }]);
+}
+
+#[test]
+fn keys() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    input_map.write().insert(1, 1);
+    input_map.write().insert(2, 2);
+
+    let mut keys = input_map.as_signal().keys();
+    let mut entries = input_map.as_signal().entries();
+    util::poll_all(&mut keys);
+
+    // Updates leave the keys alone.
+    input_map.write().insert(1, 10);
+    assert!(util::poll_all(&mut keys).items.is_empty());
+
+    input_map.write().insert(3, 3);
+    input_map.write().remove(&2);
+    let poll = util::poll_all(&mut keys);
+    let entries_snapshot = util::get_snapshots(&util::poll_all(&mut entries).items).pop().unwrap();
+    assert_eq!(
+        *util::get_snapshots(&poll.items).last().unwrap(),
+        entries_snapshot.into_iter().map(|(k, _)| k).collect()
+    );
+    assert_eq!(util::get_snapshots(&poll.items).last().unwrap().len(), 2);
+}
+
+#[test]
+fn cloned_signals_are_independent() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    input_map.write().insert(1, 1);
+    let mut signal = input_map.as_signal();
+    util::poll_all(&mut signal);
+
+    let mut cloned = signal.clone();
+    input_map.write().insert(2, 2);
+    let poll_1 = util::poll_all(&mut signal);
+    assert_eq!(util::get_hash_map_diffs(&poll_1.items), vec![MapDiff::Insert { key: 2 }]);
+
+    // The clone starts from a full replace, then tracks changes on its own.
+    let poll_2 = util::poll_all(&mut cloned);
+    assert_eq!(util::get_hash_map_diffs(&poll_2.items), vec![MapDiff::Replace {}]);
+    assert_eq!(*util::get_snapshots(&poll_2.items).last().unwrap(), hashmap! {1 => 1, 2 => 2});
+
+    input_map.write().insert(3, 3);
+    let poll_3 = util::poll_all(&mut cloned);
+    let poll_4 = util::poll_all(&mut signal);
+    assert_eq!(util::get_hash_map_diffs(&poll_3.items), vec![MapDiff::Insert { key: 3 }]);
+    assert_eq!(util::get_hash_map_diffs(&poll_4.items), vec![MapDiff::Insert { key: 3 }]);
+}
+
+#[test]
+fn values_update_in_place() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    input_map.write().insert(1, 10);
+    input_map.write().insert(2, 20);
+    input_map.write().insert(3, 30);
+
+    let mut values = input_map.as_signal().values();
+    let mut entries = input_map.as_signal().entries();
+    util::poll_all(&mut values);
+    util::poll_all(&mut entries);
+
+    input_map.write().insert(2, 21);
+    let poll = util::poll_all(&mut values);
+    let diffs = util::get_vector_diffs(&poll.items);
+    assert_eq!(diffs.len(), 1);
+    assert!(matches!(diffs[0], VectorDiff::Update { .. }));
+
+    input_map.write().remove(&1);
+    input_map.write().insert(4, 40);
+    let values_snapshot = util::get_snapshots(&util::poll_all(&mut values).items).pop().unwrap();
+    let entries_snapshot = util::get_snapshots(&util::poll_all(&mut entries).items).pop().unwrap();
+    assert_eq!(values_snapshot, entries_snapshot.into_iter().map(|(_, v)| v).collect());
+}
+
+#[test]
+fn len_signal_skips_unchanged_lengths() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    let mut lengths = input_map.as_signal().len_signal();
+    let mut cx = Context::from_waker(noop_waker_ref());
+    assert_eq!(Signal::poll_change(Pin::new(&mut lengths), &mut cx), Poll::Ready(Some(0)));
+
+    input_map.write().insert(1, 1);
+    input_map.write().insert(2, 2);
+    assert_eq!(Signal::poll_change(Pin::new(&mut lengths), &mut cx), Poll::Ready(Some(2)));
+
+    // Updates, and removes that are cancelled out by inserts, leave the length alone.
+    input_map.write().insert(1, 10);
+    assert_eq!(Signal::poll_change(Pin::new(&mut lengths), &mut cx), Poll::Pending);
+    input_map.write().remove(&1);
+    input_map.write().insert(3, 3);
+    assert_eq!(Signal::poll_change(Pin::new(&mut lengths), &mut cx), Poll::Pending);
+
+    input_map.write().clear();
+    assert_eq!(Signal::poll_change(Pin::new(&mut lengths), &mut cx), Poll::Ready(Some(0)));
+
+    input_map.write().replace(vec![(1, 1)].into_iter());
+    assert_eq!(Signal::poll_change(Pin::new(&mut lengths), &mut cx), Poll::Ready(Some(1)));
+}
+
+#[test]
+fn merge_in_with_max() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    input_map.write().replace(vec![(1, 5), (2, 1)].into_iter());
+    let mut signal = input_map.as_signal();
+    util::poll_all(&mut signal);
+
+    input_map
+        .write()
+        .merge_in(&hashmap! {1 => 3, 2 => 4, 3 => 7}, |a, b| *a.max(b));
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(
+        *util::get_snapshots(&poll.items).last().unwrap(),
+        hashmap! {1 => 5, 2 => 4, 3 => 7}
+    );
+
+    let mut diffs = util::get_hash_map_diffs(&poll.items);
+    diffs.sort_by_key(|diff| match diff {
+        MapDiff::Insert { key } | MapDiff::Update { key } | MapDiff::Remove { key } => *key,
+        _ => 0,
+    });
+    assert_eq!(
+        diffs,
+        vec![
+            MapDiff::Update { key: 1 },
+            MapDiff::Update { key: 2 },
+            MapDiff::Insert { key: 3 },
+        ]
+    );
+}
+
+#[test]
+fn map_keys_last_write_wins() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    input_map.write().insert(1, 1);
+    let mut signal = input_map.as_signal().map_keys(|k| k % 10);
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(*util::get_snapshots(&poll.items).last().unwrap(), hashmap! {1 => 1});
+
+    input_map.write().insert(11, 2);
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(*util::get_snapshots(&poll.items).last().unwrap(), hashmap! {1 => 2});
+
+    input_map.write().insert(1, 3);
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(*util::get_snapshots(&poll.items).last().unwrap(), hashmap! {1 => 3});
+
+    input_map.write().remove(&1);
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(*util::get_snapshots(&poll.items).last().unwrap(), hashmap! {1 => 2});
+
+    input_map.write().remove(&11);
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(*util::get_snapshots(&poll.items).last().unwrap(), hashmap! {});
+}
+
+#[test]
+fn filter_map_parses_and_drops() {
+    let input_map = MutableHashMap::<u8, &str>::new();
+    input_map.write().insert(1, "1");
+    input_map.write().insert(2, "two");
+    let mut signal = input_map.as_signal().filter_map(|v| v.parse::<u8>().ok());
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(*util::get_snapshots(&poll.items).last().unwrap(), hashmap! {1 => 1});
+
+    input_map.write().insert(2, "2");
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(util::get_hash_map_diffs(&poll.items), vec![MapDiff::Insert { key: 2 }]);
+    assert_eq!(*util::get_snapshots(&poll.items).last().unwrap(), hashmap! {1 => 1, 2 => 2});
+
+    input_map.write().insert(1, "one");
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(util::get_hash_map_diffs(&poll.items), vec![MapDiff::Remove { key: 1 }]);
+    assert_eq!(*util::get_snapshots(&poll.items).last().unwrap(), hashmap! {2 => 2});
+
+    // Dropping an entry that was never in the output emits nothing.
+    input_map.write().insert(3, "three");
+    let poll = util::poll_all(&mut signal);
+    assert!(util::get_hash_map_diffs(&poll.items).is_empty());
+}
+
+#[test]
+fn signal_map_to_structural_matches_btree_map() {
+    let input_map = MutableBTreeMap::<u8, u8>::new();
+    input_map.lock_mut().insert(1, 1);
+    input_map.lock_mut().insert(2, 2);
+    let mut signal = signal_map_to_structural(input_map.signal_map());
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(*util::get_snapshots(&poll.items).last().unwrap(), hashmap! {1 => 1, 2 => 2});
+
+    {
+        let mut lock = input_map.lock_mut();
+        lock.insert(3, 3);
+        lock.insert(1, 10);
+        lock.remove(&2);
+    }
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(*util::get_snapshots(&poll.items).last().unwrap(), hashmap! {1 => 10, 3 => 3});
+
+    input_map.lock_mut().clear();
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(*util::get_snapshots(&poll.items).last().unwrap(), hashmap! {});
+}
+
+// Converts a hash map event back into `futures_signals` terms by applying it to a
+// MutableBTreeMap.
+fn apply_to_btree_map(output: &MutableBTreeMap<u8, u8>, event: &HashMapEvent<u8, u8>) {
+    let mut lock = output.lock_mut();
+    for diff in event.diffs.iter() {
+        match diff {
+            MapDiff::Replace {} => {
+                lock.clear();
+                for (key, value) in event.snapshot.iter() {
+                    lock.insert(*key, *value);
+                }
+            }
+            MapDiff::Insert { key } | MapDiff::Update { key } => {
+                lock.insert(*key, event.snapshot[key]);
+            }
+            MapDiff::Remove { key } => {
+                lock.remove(key);
+            }
+            MapDiff::Clear {} => lock.clear(),
+        }
+    }
+}
+
+#[test]
+fn signal_map_to_structural_round_trips() {
+    let input_map = MutableBTreeMap::<u8, u8>::new();
+    let output_map = MutableBTreeMap::<u8, u8>::new();
+    input_map.lock_mut().insert(1, 1);
+    input_map.lock_mut().insert(2, 2);
+    let mut signal = signal_map_to_structural(input_map.signal_map());
+
+    let mut round_trip = || {
+        for event in util::poll_all(&mut signal).items.iter() {
+            apply_to_btree_map(&output_map, event);
+        }
+        assert_eq!(*output_map.lock_ref(), *input_map.lock_ref());
+    };
+    round_trip();
+
+    {
+        let mut lock = input_map.lock_mut();
+        lock.insert(3, 3);
+        lock.insert(1, 10);
+        lock.remove(&2);
+    }
+    round_trip();
+
+    input_map.lock_mut().clear();
+    input_map.lock_mut().insert(4, 4);
+    round_trip();
+}
+
+#[test]
+fn diff_maps_replays_onto_old() {
+    let old = hashmap! {1 => 1, 2 => 2, 3 => 3};
+    let new = hashmap! {1 => 1, 2 => 20, 4 => 4};
+    let diffs = diff_maps(&old, &new);
+    assert_eq!(diffs.len(), 3);
+    assert!(diffs.contains(&MapDiff::Remove { key: 3 }));
+    assert!(diffs.contains(&MapDiff::Update { key: 2 }));
+    assert!(diffs.contains(&MapDiff::Insert { key: 4 }));
+
+    let mut replayed = old.clone();
+    for diff in diffs.iter() {
+        replayed.apply_diff(diff, &new);
+    }
+    assert_eq!(replayed, new);
+
+    assert!(diff_maps(&new, &new).is_empty());
+    assert_eq!(diff_maps(&old, &hashmap! {}), vec![MapDiff::Clear {}]);
+}
+
+#[test]
+fn map_values_with_dedupe() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    input_map.write().insert(1, 1);
+    let options = MapValuesOptions { dedupe: true, ..Default::default() };
+    let mut deduped = input_map.as_signal().map_values_with(options, |v| v % 2);
+    let mut plain = input_map.as_signal().map_values_with(Default::default(), |v| v % 2);
+    util::poll_all(&mut deduped);
+    util::poll_all(&mut plain);
+
+    input_map.write().insert(1, 3);
+    assert!(util::poll_all(&mut deduped).items.is_empty());
+    let poll = util::poll_all(&mut plain);
+    assert_eq!(util::get_hash_map_diffs(&poll.items), vec![MapDiff::Update { key: 1 }]);
+
+    input_map.write().insert(1, 4);
+    let poll = util::poll_all(&mut deduped);
+    assert_eq!(util::get_hash_map_diffs(&poll.items), vec![MapDiff::Update { key: 1 }]);
+    assert_eq!(*util::get_snapshots(&poll.items).last().unwrap(), hashmap! {1 => 0});
+}
+
+#[test]
+fn map_values_with_memoize() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    input_map.write().replace(vec![(1, 10), (2, 20)].into_iter());
+    let map_count = Arc::new(AtomicUsize::new(0));
+    let counter = map_count.clone();
+    let options = MapValuesOptions { memoize: true, ..Default::default() };
+    let mut signal = input_map.as_signal().map_values_with(options, move |v| {
+        counter.fetch_add(1, Ordering::SeqCst);
+        v + 1
+    });
+    util::poll_all(&mut signal);
+    assert_eq!(map_count.load(Ordering::SeqCst), 2);
+
+    // Only the input value that was not seen before is mapped.
+    input_map.write().replace(vec![(1, 20), (2, 10), (3, 30)].into_iter());
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(
+        *util::get_snapshots(&poll.items).last().unwrap(),
+        hashmap! {1 => 21, 2 => 11, 3 => 31}
+    );
+    assert_eq!(map_count.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn map_values_with_granular_replace() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    input_map.write().replace(vec![(1, 1), (2, 2)].into_iter());
+    let options = MapValuesOptions { granular_replace: true, dedupe: true, ..Default::default() };
+    let mut granular = input_map.as_signal().map_values_with(options, |v| v * 10);
+    let mut coarse = input_map.as_signal().map_values_with(Default::default(), |v| v * 10);
+    util::poll_all(&mut granular);
+    util::poll_all(&mut coarse);
+
+    input_map.write().replace(vec![(2, 2), (3, 3)].into_iter());
+    let poll = util::poll_all(&mut granular);
+    assert_eq!(
+        util::get_hash_map_diffs(&poll.items),
+        vec![MapDiff::Remove { key: 1 }, MapDiff::Insert { key: 3 }]
+    );
+    assert_eq!(*util::get_snapshots(&poll.items).last().unwrap(), hashmap! {2 => 20, 3 => 30});
+    let poll = util::poll_all(&mut coarse);
+    assert_eq!(util::get_hash_map_diffs(&poll.items), vec![MapDiff::Replace {}]);
+}
+
+#[test]
+fn entries_replace_emits_per_entry_diffs() {
+    let input_map = MutableHashMap::<CollidingKey, u8>::new();
+    let old_entries = vec![(CollidingKey(1), 1), (CollidingKey(2), 2), (CollidingKey(3), 3)];
+    input_map.write().replace(old_entries.into_iter());
+
+    let mut entries = input_map.as_signal().entries();
+    util::poll_all(&mut entries);
+
+    let new_entries = vec![(CollidingKey(2), 20), (CollidingKey(3), 3), (CollidingKey(4), 4)];
+    input_map.write().replace(new_entries.into_iter());
+    let poll = util::poll_all(&mut entries);
+    // Every kept key is updated, even if its value is unchanged.
+    assert_eq!(
+        util::get_vector_diffs(&poll.items),
+        vec![
+            VectorDiff::Remove { index: 0, snapshot_index: 0 },
+            VectorDiff::Update { index: 0, snapshot_index: 0 },
+            VectorDiff::Update { index: 1, snapshot_index: 1 },
+            VectorDiff::Insert { index: 2, snapshot_index: 2 },
+        ]
+    );
+    assert_eq!(
+        *util::get_snapshots(&poll.items).last().unwrap(),
+        vector![(CollidingKey(2), 20), (CollidingKey(3), 3), (CollidingKey(4), 4)]
+    );
+}
+
+#[test]
+fn merge_falls_back_to_other_side() {
+    let left_map = MutableHashMap::<u8, u8>::new();
+    left_map.write().insert(1, 1);
+    left_map.write().insert(2, 2);
+    let right_map = MutableHashMap::<u8, u8>::new();
+    right_map.write().insert(2, 20);
+    right_map.write().insert(3, 30);
+
+    let mut merged = left_map
+        .as_signal()
+        .merge(right_map.as_signal(), |l, r| l + r);
+    let poll_1 = util::poll_all(&mut merged);
+    assert_eq!(
+        util::get_snapshots(&poll_1.items).pop().unwrap(),
+        hashmap! {1 => 1, 2 => 22, 3 => 30}
+    );
+
+    // Removing a shared key from one side falls back to the other side's value.
+    left_map.write().remove(&2);
+    let poll_2 = util::poll_all(&mut merged);
+    assert_eq!(
+        util::get_hash_map_diffs(&poll_2.items),
+        vec![MapDiff::Update { key: 2 }]
+    );
+    assert_eq!(
+        util::get_snapshots(&poll_2.items).pop().unwrap(),
+        hashmap! {1 => 1, 2 => 20, 3 => 30}
+    );
+
+    right_map.write().remove(&3);
+    left_map.write().insert(4, 4);
+    let poll_3 = util::poll_all(&mut merged);
+    assert_eq!(
+        util::get_snapshots(&poll_3.items).pop().unwrap(),
+        hashmap! {1 => 1, 2 => 20, 4 => 4}
+    );
+
+    // Replacing one side with equal values emits nothing.
+    right_map.write().replace(vec![(2, 20)].into_iter());
+    assert!(util::poll_all(&mut merged).items.is_empty());
+}
+
+#[test]
+fn batch_produces_one_event() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    let mut signal = input_map.as_signal();
+    util::poll_all(&mut signal);
+
+    input_map.batch(|| {
+        input_map.write().insert(1, 1);
+        input_map.batch(|| {
+            input_map.write().insert(2, 2);
+        });
+        // Polling between writes inside a batch gets nothing.
+        assert!(util::poll_all(&mut signal).items.is_empty());
+        input_map.write().remove(&1);
+    });
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(poll.items.len(), 1);
+    assert_eq!(poll.items[0].snapshot, hashmap! {2 => 2});
+    assert_eq!(poll.items[0].diffs, vec![MapDiff::Insert { key: 2 }]);
+}
+
+#[test]
+fn compares_current_contents() {
+    let first = MutableHashMap::from_iter(vec![(1, 1)]);
+    let second = MutableHashMap::new();
+    assert_ne!(first, second);
+
+    second.write().insert(1, 1);
+    assert_eq!(first, second);
+    assert_eq!(first, hashmap! {1 => 1});
+}
@@ -0,0 +1,75 @@
+use futures::task::noop_waker_ref;
+use futures_signals::signal::Signal;
+use im::hashset;
+use signals_im::hash_set::{MutableHashSet, SetDiff, SignalHashSetExt};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+mod util;
+
+#[test]
+fn as_signal() {
+    let input_set = MutableHashSet::<u8>::new();
+    input_set.write().insert(1);
+
+    let mut signal = input_set.as_signal();
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(util::get_snapshots(&poll.items), vec![hashset! {1}]);
+
+    input_set.write().insert(2);
+    input_set.write().remove(&1);
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(util::get_snapshots(&poll.items), vec![hashset! {2}]);
+    assert_eq!(poll.items[0].diffs, vec![SetDiff::Insert(2), SetDiff::Remove(1)]);
+}
+
+#[test]
+fn insert_then_remove_cancels_out() {
+    let input_set = MutableHashSet::<u8>::new();
+    input_set.write().insert(1);
+
+    let mut signal = input_set.as_signal();
+    util::poll_all(&mut signal);
+
+    input_set.write().insert(2);
+    input_set.write().remove(&2);
+    input_set.write().remove(&1);
+    input_set.write().insert(1);
+    assert!(util::poll_all(&mut signal).items.is_empty());
+}
+
+#[test]
+fn duplicate_insert_emits_nothing() {
+    let input_set = MutableHashSet::<u8>::new();
+    input_set.write().insert(1);
+
+    let mut signal = input_set.as_signal();
+    util::poll_all(&mut signal);
+
+    assert!(!input_set.write().insert(1));
+    assert!(!input_set.write().remove(&2));
+    assert!(util::poll_all(&mut signal).items.is_empty());
+}
+
+#[test]
+fn contains_signal() {
+    let input_set = MutableHashSet::<u8>::new();
+    let mut contains = input_set.as_signal().contains_signal(1);
+    let mut cx = Context::from_waker(noop_waker_ref());
+
+    assert_eq!(Signal::poll_change(Pin::new(&mut contains), &mut cx), Poll::Ready(Some(false)));
+
+    // Changes to other elements do not fire.
+    input_set.write().insert(2);
+    assert_eq!(Signal::poll_change(Pin::new(&mut contains), &mut cx), Poll::Pending);
+
+    input_set.write().insert(1);
+    assert_eq!(Signal::poll_change(Pin::new(&mut contains), &mut cx), Poll::Ready(Some(true)));
+
+    // A replace that keeps the element does not fire either.
+    input_set.write().replace(vec![1, 3].into_iter());
+    assert_eq!(Signal::poll_change(Pin::new(&mut contains), &mut cx), Poll::Pending);
+
+    input_set.write().clear();
+    assert_eq!(Signal::poll_change(Pin::new(&mut contains), &mut cx), Poll::Ready(Some(false)));
+}
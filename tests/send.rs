@@ -0,0 +1,79 @@
+use futures::channel::{mpsc, oneshot};
+use futures::executor::{block_on, ThreadPool};
+use futures::future::poll_fn;
+use im::{hashmap, vector};
+use signals_im::hash_map::{HashMapEvent, MapDiff, MutableHashMap, SignalHashMapExt};
+use signals_im::vector::{MutableVector, SignalVectorExt};
+use signals_im::{ChannelStructuralSignal, StructuralSignal, StructuralSignalExt};
+use std::thread;
+
+fn assert_send<T: Send + 'static>(value: T) -> T {
+    value
+}
+
+#[test]
+fn signals_are_send() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    assert_send(input_map.as_signal());
+    assert_send(input_map.reader());
+    assert_send(input_map.as_signal().map_values(|v| v * 2));
+    assert_send(input_map.as_signal().entries().broadcast().get_signal());
+
+    let input_vec = MutableVector::<u8>::new();
+    assert_send(input_vec.as_signal());
+    assert_send(input_vec.as_signal().map(|v| v * 2).broadcast());
+}
+
+#[test]
+fn spawn_signal_driving_task() {
+    let pool = ThreadPool::new().unwrap();
+    let input_map = MutableHashMap::<u8, u8>::new();
+    input_map.write().insert(1, 1);
+    input_map.write().insert(2, 2);
+
+    let broadcaster = input_map.as_signal().map_values(|v| v * 2).broadcast();
+    let signal = broadcaster.get_signal();
+    let (sender, receiver) = oneshot::channel();
+    pool.spawn_ok(async move {
+        let mut signal = Box::pin(signal);
+        let event = poll_fn(|cx| signal.as_mut().poll_change(cx)).await;
+        sender.send(event.map(|event| event.snapshot)).unwrap();
+    });
+    assert_eq!(block_on(receiver).unwrap(), Some(hashmap! {1 => 2, 2 => 4}));
+
+    let input_vec = MutableVector::<u8>::new();
+    input_vec.write().push_back(1);
+    let signal = input_vec.as_signal().map(|v| v + 1);
+    let (sender, receiver) = oneshot::channel();
+    pool.spawn_ok(async move {
+        let mut signal = Box::pin(signal);
+        let event = poll_fn(|cx| signal.as_mut().poll_change(cx)).await;
+        sender.send(event.map(|event| event.snapshot)).unwrap();
+    });
+    assert_eq!(block_on(receiver).unwrap(), Some(vector![2]));
+}
+
+#[test]
+fn pipe_events_to_worker_thread() {
+    let pool = ThreadPool::new().unwrap();
+    let (event_sender, event_receiver) = mpsc::unbounded();
+    for i in 1..=3u8 {
+        event_sender
+            .unbounded_send(HashMapEvent::new(hashmap! {i => i}, vec![MapDiff::Replace {}]))
+            .unwrap();
+    }
+    drop(event_sender);
+
+    // The sink owns the std sender, so the channel closes once the signal ends.
+    let (sender, receiver) = std::sync::mpsc::channel();
+    pool.spawn_ok(
+        ChannelStructuralSignal::new(event_receiver)
+            .pipe_to(move |event: HashMapEvent<u8, u8>| sender.send(event.snapshot).unwrap()),
+    );
+
+    let worker = thread::spawn(move || receiver.iter().collect::<Vec<_>>());
+    assert_eq!(
+        worker.join().unwrap(),
+        vec![hashmap! {1 => 1}, hashmap! {2 => 2}, hashmap! {3 => 3}]
+    );
+}
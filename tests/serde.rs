@@ -0,0 +1,28 @@
+#![cfg(feature = "serde")]
+
+use im::{hashmap, vector};
+use signals_im::hash_map::{HashMapEvent, MapDiff};
+use signals_im::vector::{VectorDiff, VectorEvent};
+
+#[test]
+fn vector_event_round_trip() {
+    let event = VectorEvent::new(
+        vector![1, 2, 3],
+        vec![
+            VectorDiff::Insert { index: 2, snapshot_index: 2 },
+            VectorDiff::Move { from: 0, to: 1, snapshot_from: 0, snapshot_to: 1 },
+        ],
+    );
+    let json = serde_json::to_string(&event).unwrap();
+    assert_eq!(serde_json::from_str::<VectorEvent<u8>>(&json).unwrap(), event);
+}
+
+#[test]
+fn hash_map_event_round_trip() {
+    let event = HashMapEvent::new(
+        hashmap! {"a".to_string() => 1, "b".to_string() => 2},
+        vec![MapDiff::Insert { key: "b".to_string() }, MapDiff::Clear {}],
+    );
+    let json = serde_json::to_string(&event).unwrap();
+    assert_eq!(serde_json::from_str::<HashMapEvent<String, u8>>(&json).unwrap(), event);
+}
@@ -1,7 +1,22 @@
-use signals_im::hash_map::{MutableHashMap, SignalHashMapExt};
-use signals_im::vector::MutableVector;
-use signals_im::StructuralSignalExt;
+use futures::channel::{mpsc, oneshot};
+use futures::task::{waker, ArcWake};
+use futures::FutureExt;
 use im::{hashmap, vector};
+use signals_im::hash_map::{HashMapEvent, MapDiff, MutableHashMap, SignalHashMapExt};
+use signals_im::vector::{MutableVector, SignalVectorExt, VectorDiff};
+use signals_im::{
+    select_all, structural_from_fn, BroadcastedStructuralSignal, ChannelStructuralSignal,
+    StructuralSignal, StructuralSignalExt,
+};
+use std::cell::{Cell, RefCell};
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+mod util;
 
 #[test]
 fn broadcast_hash_map() {
@@ -33,4 +48,691 @@ fn broadcast_vector() {
     input_map.write().insert(0, 0);
     assert_eq!(broadcaster.get_signal().snapshot().unwrap(), vector![0, 1, 1, 2]);
     assert_eq!(broadcaster.get_signal().snapshot().unwrap(), vector![0, 1, 1, 2]);
-}
\ No newline at end of file
+}
+
+#[test]
+fn take_ready_events_from_hash_map() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    let mut signal = input_map.as_signal();
+
+    input_map.write().insert(1, 1);
+    let events_1 = (&mut signal).take_ready_events(2);
+    assert_eq!(events_1.len(), 1);
+    assert_eq!(events_1[0].snapshot, hashmap!{1 => 1});
+
+    input_map.write().insert(2, 2);
+    input_map.write().insert(3, 3);
+    let events_2 = (&mut signal).take_ready_events(2);
+    assert_eq!(events_2.len(), 1);
+    assert_eq!(events_2[0].diffs, vec![MapDiff::Insert { key: 2 }, MapDiff::Insert { key: 3 }]);
+}
+
+#[test]
+fn take_ready_events_stops_at_n() {
+    let (sender, receiver) = mpsc::unbounded();
+    for i in 0..3u8 {
+        sender.unbounded_send(i).unwrap();
+    }
+
+    let mut signal = ChannelStructuralSignal::new(receiver);
+    assert_eq!((&mut signal).take_ready_events(2), vec![0, 1]);
+    assert_eq!((&mut signal).take_ready_events(2), vec![2]);
+}
+
+#[test]
+fn take_events_waits_for_n_events() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    input_map.write().insert(1, 1);
+    let signal = input_map.as_signal();
+
+    let producer = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(10));
+        input_map.write().insert(2, 2);
+    });
+    let events = signal.take_events(2);
+    producer.join().unwrap();
+    assert_eq!(util::get_snapshots(&events), vec![hashmap!{1 => 1}, hashmap!{1 => 1, 2 => 2}]);
+    assert_eq!(util::get_hash_map_diffs(&events[1..].to_vec()), vec![MapDiff::Insert { key: 2 }]);
+}
+
+#[test]
+fn take_events_stops_when_signal_ends() {
+    let (sender, receiver) = mpsc::unbounded();
+    sender.unbounded_send(0u8).unwrap();
+    drop(sender);
+    assert_eq!(ChannelStructuralSignal::new(receiver).take_events(2), vec![0]);
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct TaggedEvent<E> {
+    tag: &'static str,
+    event: E,
+}
+
+#[test]
+fn map_event_wraps_events() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    let mut signal = input_map.as_signal().map_event(|event| TaggedEvent {
+        tag: "numbers",
+        event,
+    });
+
+    input_map.write().insert(1, 1);
+    let events_1 = (&mut signal).take_ready_events(2);
+    assert_eq!(events_1.len(), 1);
+    assert_eq!(events_1[0].tag, "numbers");
+    assert_eq!(events_1[0].event.snapshot, hashmap!{1 => 1});
+
+    input_map.write().remove(&1);
+    let events_2 = (&mut signal).take_ready_events(2);
+    assert_eq!(events_2[0].event.diffs, vec![MapDiff::Remove { key: 1 }]);
+}
+
+#[test]
+fn filter_events_drops_empty_events() {
+    let (sender, receiver) = mpsc::unbounded();
+    sender.unbounded_send(HashMapEvent::new(hashmap!{}, vec![])).unwrap();
+    sender
+        .unbounded_send(HashMapEvent::new(hashmap!{1 => 1}, vec![MapDiff::Insert { key: 1 }]))
+        .unwrap();
+    sender.unbounded_send(HashMapEvent::new(hashmap!{1 => 1}, vec![])).unwrap();
+    sender
+        .unbounded_send(HashMapEvent::new(hashmap!{}, vec![MapDiff::Remove { key: 1 }]))
+        .unwrap();
+
+    let signal = ChannelStructuralSignal::new(receiver)
+        .filter_events(|event: &HashMapEvent<u8, u8>| !event.diffs.is_empty());
+    let events = signal.take_ready_events(4);
+    assert_eq!(
+        events.into_iter().map(|event| event.diffs).collect::<Vec<_>>(),
+        vec![vec![MapDiff::Insert { key: 1 }], vec![MapDiff::Remove { key: 1 }]]
+    );
+}
+
+#[test]
+fn merge_ready_hash_map_events() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    let mut input_signal = input_map.as_signal();
+    util::poll_all(&mut input_signal);
+
+    let (sender, receiver) = mpsc::unbounded();
+    input_map.write().insert(1, 1);
+    input_map.write().insert(2, 2);
+    sender.unbounded_send(util::poll_all(&mut input_signal).items.remove(0)).unwrap();
+    input_map.write().insert(1, 3);
+    sender.unbounded_send(util::poll_all(&mut input_signal).items.remove(0)).unwrap();
+    input_map.write().insert(3, 3);
+    input_map.write().remove(&2);
+    sender.unbounded_send(util::poll_all(&mut input_signal).items.remove(0)).unwrap();
+
+    let mut merged = ChannelStructuralSignal::new(receiver).merge_ready();
+    let poll = util::poll_all(&mut merged);
+    assert_eq!(poll.items.len(), 1);
+    assert_eq!(poll.items[0].snapshot, hashmap!{1 => 3, 3 => 3});
+    assert_eq!(
+        poll.items[0].diffs,
+        vec![MapDiff::Insert { key: 1 }, MapDiff::Insert { key: 3 }]
+    );
+}
+
+#[test]
+fn merge_ready_vector_events() {
+    let input_vec = MutableVector::<u8>::new();
+    input_vec.write().replace(vec![1, 2, 3].into_iter());
+    let mut input_signal = input_vec.as_signal();
+
+    let (sender, receiver) = mpsc::unbounded();
+    sender.unbounded_send(util::poll_all(&mut input_signal).items.remove(0)).unwrap();
+    let mut output = ChannelStructuralSignal::new(receiver).merge_ready().map(|v| *v);
+    util::poll_all(&mut output);
+
+    input_vec.write().push_front(4);
+    input_vec.write().push_back(5);
+    sender.unbounded_send(util::poll_all(&mut input_signal).items.remove(0)).unwrap();
+    input_vec.write().insert(1, 6);
+    input_vec.write().set(5, 7);
+    sender.unbounded_send(util::poll_all(&mut input_signal).items.remove(0)).unwrap();
+    input_vec.write().remove(0);
+    sender.unbounded_send(util::poll_all(&mut input_signal).items.remove(0)).unwrap();
+
+    let poll = util::poll_all(&mut output);
+    assert_eq!(poll.items.len(), 1);
+    assert_eq!(poll.items[0].snapshot, vector![6, 1, 2, 3, 7]);
+    assert_eq!(poll.items[0].snapshot, input_vec.read().snapshot());
+}
+
+#[test]
+fn debounce_waits_for_quiet_period() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    let mut input_signal = input_map.as_signal();
+    util::poll_all(&mut input_signal);
+
+    let timer_done = Rc::new(Cell::new(false));
+    let sleep_count = Rc::new(Cell::new(0));
+    let make_sleep = {
+        let timer_done = timer_done.clone();
+        let sleep_count = sleep_count.clone();
+        move |_: Duration| {
+            sleep_count.set(sleep_count.get() + 1);
+            let timer_done = timer_done.clone();
+            futures::future::poll_fn(move |_| {
+                if timer_done.get() {
+                    Poll::Ready(())
+                } else {
+                    Poll::Pending
+                }
+            })
+        }
+    };
+
+    let (sender, receiver) = mpsc::unbounded();
+    let mut debounced =
+        ChannelStructuralSignal::new(receiver).debounce(Duration::from_millis(10), make_sleep);
+
+    input_map.write().insert(1, 1);
+    sender.unbounded_send(util::poll_all(&mut input_signal).items.remove(0)).unwrap();
+    assert!(util::poll_all(&mut debounced).items.is_empty());
+
+    // A second event restarts the timer.
+    input_map.write().insert(2, 2);
+    sender.unbounded_send(util::poll_all(&mut input_signal).items.remove(0)).unwrap();
+    assert!(util::poll_all(&mut debounced).items.is_empty());
+    assert_eq!(sleep_count.get(), 2);
+
+    timer_done.set(true);
+    let poll = util::poll_all(&mut debounced);
+    assert_eq!(poll.items.len(), 1);
+    assert_eq!(poll.items[0].snapshot, hashmap! {1 => 1, 2 => 2});
+    assert_eq!(
+        poll.items[0].diffs,
+        vec![MapDiff::Insert { key: 1 }, MapDiff::Insert { key: 2 }]
+    );
+
+    drop(sender);
+    assert!(util::poll_all(&mut debounced).is_done);
+}
+
+#[test]
+fn with_initial_precedes_source_events() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    input_map.write().insert(1, 1);
+
+    let initial = HashMapEvent::new(hashmap!{}, vec![MapDiff::Clear {}]);
+    let mut signal = input_map.as_signal().with_initial(initial.clone());
+    let poll_1 = util::poll_all(&mut signal);
+    assert_eq!(poll_1.items.len(), 2);
+    assert_eq!(poll_1.items[0], initial);
+    assert_eq!(poll_1.items[1].snapshot, hashmap!{1 => 1});
+
+    input_map.write().insert(2, 2);
+    let poll_2 = util::poll_all(&mut signal);
+    assert_eq!(util::get_hash_map_diffs(&poll_2.items), vec![MapDiff::Insert { key: 2 }]);
+}
+
+#[test]
+fn cached_transform_survives_resubscription() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    input_map.write().insert(1, 1);
+    input_map.write().insert(2, 2);
+
+    let map_count = Rc::new(Cell::new(0));
+    let counter = map_count.clone();
+    let cache = input_map
+        .as_signal()
+        .map_values(move |v| {
+            counter.set(counter.get() + 1);
+            v * 2
+        })
+        .cached_transform();
+
+    let mut signal_1 = cache.get_signal();
+    let poll_1 = util::poll_all(&mut signal_1);
+    assert_eq!(*util::get_snapshots(&poll_1.items).last().unwrap(), hashmap!{1 => 2, 2 => 4});
+    assert_eq!(map_count.get(), 2);
+    drop(signal_1);
+
+    // Resubscribing reuses the cached output rather than re-running the transform.
+    let mut signal_2 = cache.get_signal();
+    let poll_2 = util::poll_all(&mut signal_2);
+    assert_eq!(*util::get_snapshots(&poll_2.items).last().unwrap(), hashmap!{1 => 2, 2 => 4});
+    assert_eq!(map_count.get(), 2);
+    drop(signal_2);
+
+    // Changes made while nobody is subscribed only re-run the transform for what changed.
+    input_map.write().insert(3, 3);
+    let mut signal_3 = cache.get_signal();
+    let poll_3 = util::poll_all(&mut signal_3);
+    assert_eq!(
+        *util::get_snapshots(&poll_3.items).last().unwrap(),
+        hashmap!{1 => 2, 2 => 4, 3 => 6}
+    );
+    assert_eq!(map_count.get(), 3);
+}
+
+struct CountingWaker(AtomicUsize);
+
+impl ArcWake for CountingWaker {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn cached_transform_wakes_every_subscriber() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    let cache = input_map.as_signal().map_values(|v| v * 2).cached_transform();
+    let mut signal_1 = cache.get_signal();
+    let mut signal_2 = cache.get_signal();
+
+    let counting_waker_1 = Arc::new(CountingWaker(AtomicUsize::new(0)));
+    let waker_1 = waker(counting_waker_1.clone());
+    let mut cx_1 = Context::from_waker(&waker_1);
+    let counting_waker_2 = Arc::new(CountingWaker(AtomicUsize::new(0)));
+    let waker_2 = waker(counting_waker_2.clone());
+    let mut cx_2 = Context::from_waker(&waker_2);
+
+    assert!(Pin::new(&mut signal_1).poll_change(&mut cx_1).is_ready());
+    assert!(Pin::new(&mut signal_1).poll_change(&mut cx_1).is_pending());
+    assert!(Pin::new(&mut signal_2).poll_change(&mut cx_2).is_ready());
+    assert!(Pin::new(&mut signal_2).poll_change(&mut cx_2).is_pending());
+
+    // A change wakes both signals, even though the second one polled the input last.
+    input_map.write().insert(1, 1);
+    assert_eq!(counting_waker_1.0.load(Ordering::SeqCst), 1);
+    assert_eq!(counting_waker_2.0.load(Ordering::SeqCst), 1);
+    match Pin::new(&mut signal_1).poll_change(&mut cx_1) {
+        Poll::Ready(Some(event)) => assert_eq!(event.snapshot, hashmap!{1 => 2}),
+        _ => panic!("Expected the first signal to receive the change"),
+    }
+    assert!(Pin::new(&mut signal_1).poll_change(&mut cx_1).is_pending());
+
+    // The first signal is still woken after the last one to poll is dropped.
+    assert!(Pin::new(&mut signal_2).poll_change(&mut cx_2).is_ready());
+    drop(signal_2);
+    let wake_count = counting_waker_1.0.load(Ordering::SeqCst);
+    input_map.write().insert(2, 2);
+    assert!(counting_waker_1.0.load(Ordering::SeqCst) > wake_count);
+    match Pin::new(&mut signal_1).poll_change(&mut cx_1) {
+        Poll::Ready(Some(event)) => assert_eq!(event.snapshot, hashmap!{1 => 2, 2 => 4}),
+        _ => panic!("Expected the first signal to receive the change"),
+    }
+}
+
+#[derive(Debug)]
+struct CloneCountedEvent(Rc<Cell<usize>>);
+
+impl Clone for CloneCountedEvent {
+    fn clone(&self) -> Self {
+        self.0.set(self.0.get() + 1);
+        CloneCountedEvent(self.0.clone())
+    }
+}
+
+#[test]
+fn broadcast_only_clones_for_subscribers() {
+    let clone_count = Rc::new(Cell::new(0));
+    let (sender, receiver) = mpsc::unbounded();
+    let broadcaster = ChannelStructuralSignal::new(receiver).broadcast();
+    assert!(!broadcaster.has_subscribers());
+
+    // Pull an event in while nobody is subscribed, through a receiver the broadcaster
+    // does not know about. The event is kept as the most recent one but never cloned.
+    let (_, parent) = broadcaster.get_signal_without_replay().into_parts();
+    let (_unregistered_sender, unregistered_receiver) = mpsc::unbounded();
+    let mut unregistered = BroadcastedStructuralSignal::from_parts(unregistered_receiver, parent);
+    sender.unbounded_send(CloneCountedEvent(clone_count.clone())).unwrap();
+    assert!(util::poll_all(&mut unregistered).items.is_empty());
+    assert!(!broadcaster.has_subscribers());
+    assert_eq!(clone_count.get(), 0);
+    drop(unregistered);
+
+    let mut signal = broadcaster.get_signal_without_replay();
+    assert!(broadcaster.has_subscribers());
+    sender.unbounded_send(CloneCountedEvent(clone_count.clone())).unwrap();
+    assert_eq!(util::poll_all(&mut signal).items.len(), 1);
+    assert_eq!(clone_count.get(), 1);
+
+    drop(signal);
+    assert!(!broadcaster.has_subscribers());
+}
+
+#[test]
+fn take_until_ends_after_trigger() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    let (trigger, triggered) = oneshot::channel::<()>();
+    let mut signal = input_map
+        .as_signal()
+        .take_until(triggered.map(|_| ()));
+
+    input_map.write().insert(1, 1);
+    let poll_1 = util::poll_all(&mut signal);
+    assert_eq!(*util::get_snapshots(&poll_1.items).last().unwrap(), hashmap!{1 => 1});
+    assert!(!poll_1.is_done);
+
+    trigger.send(()).unwrap();
+    input_map.write().insert(2, 2);
+    let poll_2 = util::poll_all(&mut signal);
+    assert!(poll_2.items.is_empty());
+    assert!(poll_2.is_done);
+}
+
+#[test]
+fn fold_to_counts_ready_events() {
+    let (sender, receiver) = mpsc::unbounded();
+    for i in 0..3u8 {
+        sender.unbounded_send(i).unwrap();
+    }
+
+    let mut signal = ChannelStructuralSignal::new(receiver);
+    assert_eq!((&mut signal).fold_to(0, |count, _| *count += 1), 3);
+
+    // Only events that are ready get folded, so a Signal with nothing new folds to init.
+    assert_eq!((&mut signal).fold_to(0, |count, _| *count += 1), 0);
+
+    sender.unbounded_send(3).unwrap();
+    assert_eq!(signal.fold_to(vec![], |seen, i| seen.push(i)), vec![3]);
+}
+
+#[test]
+fn broadcast_try_next_drains_buffered_events() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    let broadcaster = input_map.as_signal().broadcast();
+    let mut driver = broadcaster.get_signal();
+    let mut subscriber = broadcaster.get_signal();
+    assert!(subscriber.try_next().is_none());
+
+    // Polling one signal pulls events in for every subscriber.
+    util::poll_all(&mut driver);
+    input_map.write().insert(1, 1);
+    util::poll_all(&mut driver);
+
+    assert_eq!(subscriber.try_next().unwrap().diffs, vec![MapDiff::Replace {}]);
+    assert_eq!(subscriber.try_next().unwrap().diffs, vec![MapDiff::Insert { key: 1 }]);
+    assert!(subscriber.try_next().is_none());
+}
+
+#[test]
+fn dedupe_by_snapshot_length() {
+    let (sender, receiver) = mpsc::unbounded();
+    for snapshot in vec![
+        hashmap! {1 => 1},
+        hashmap! {1 => 2},
+        hashmap! {1 => 2, 2 => 2},
+        hashmap! {1 => 3, 2 => 2},
+        hashmap! {2 => 2},
+    ] {
+        sender.unbounded_send(HashMapEvent::new(snapshot, vec![MapDiff::Replace {}])).unwrap();
+    }
+
+    let mut signal = ChannelStructuralSignal::new(receiver)
+        .dedupe_by(|prev: &HashMapEvent<u8, u8>, current| prev.snapshot.len() == current.snapshot.len());
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(
+        util::get_snapshots(&poll.items),
+        vec![hashmap! {1 => 1}, hashmap! {1 => 2, 2 => 2}, hashmap! {2 => 2}]
+    );
+}
+
+#[test]
+fn broadcast_subscriber_from_parts() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    let broadcaster = input_map.as_signal().broadcast();
+    let mut subscriber = broadcaster.get_signal();
+    util::poll_all(&mut subscriber);
+
+    let (receiver, parent) = subscriber.into_parts();
+    let mut subscriber = BroadcastedStructuralSignal::from_parts(receiver, parent);
+    input_map.write().insert(1, 1);
+    let poll = util::poll_all(&mut subscriber);
+    assert_eq!(util::get_hash_map_diffs(&poll.items), vec![MapDiff::Insert { key: 1 }]);
+    assert_eq!(*util::get_snapshots(&poll.items).last().unwrap(), hashmap! {1 => 1});
+}
+
+#[test]
+fn structural_from_fn_emits_then_ends() {
+    let mut events = vec![
+        HashMapEvent::new(hashmap! {1 => 1}, vec![MapDiff::Replace {}]),
+        HashMapEvent::new(hashmap! {1 => 1, 2 => 2}, vec![MapDiff::Insert { key: 2 }]),
+    ]
+    .into_iter();
+    let mut signal = structural_from_fn(move |_| Poll::Ready(events.next()));
+
+    let poll = util::poll_all(&mut signal);
+    assert!(poll.is_done);
+    assert_eq!(
+        util::get_snapshots(&poll.items),
+        vec![hashmap! {1 => 1}, hashmap! {1 => 1, 2 => 2}]
+    );
+}
+
+#[test]
+fn broadcast_subscriber_count() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    let broadcaster = input_map.as_signal().broadcast();
+    assert_eq!(broadcaster.subscriber_count(), 0);
+
+    let mut driver = broadcaster.get_signal();
+    let subscriber_1 = broadcaster.get_signal();
+    let subscriber_2 = broadcaster.get_signal();
+    assert_eq!(broadcaster.subscriber_count(), 3);
+
+    drop(subscriber_1);
+    input_map.write().insert(1, 1);
+    util::poll_all(&mut driver);
+    assert_eq!(broadcaster.subscriber_count(), 2);
+
+    drop(subscriber_2);
+    drop(driver);
+    assert_eq!(broadcaster.subscriber_count(), 0);
+}
+
+#[test]
+fn one_diff_per_event() {
+    let input_vec = MutableVector::<u8>::new();
+    let mut signal = input_vec.as_signal().one_diff_per_event();
+    util::poll_all(&mut signal);
+
+    input_vec.write().push_back(1);
+    input_vec.write().push_front(0);
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(poll.items.len(), 2);
+    assert_eq!(
+        util::get_vector_diffs(&poll.items),
+        vec![
+            VectorDiff::Insert { index: 0, snapshot_index: 1 },
+            VectorDiff::Insert { index: 0, snapshot_index: 0 },
+        ]
+    );
+    // Both events carry the final snapshot.
+    assert_eq!(util::get_snapshots(&poll.items), vec![vector![0, 1], vector![0, 1]]);
+}
+
+#[test]
+fn into_blocking_iter_waits_for_other_thread() {
+    let (sender, receiver) = mpsc::unbounded();
+    let producer = std::thread::spawn(move || {
+        let mut map = hashmap! {};
+        for i in 0..3u8 {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            map.insert(i, i);
+            sender
+                .unbounded_send(HashMapEvent::new(map.clone(), vec![MapDiff::Insert { key: i }]))
+                .unwrap();
+        }
+    });
+
+    let snapshots: Vec<_> = ChannelStructuralSignal::new(receiver)
+        .into_blocking_iter()
+        .map(|event: HashMapEvent<u8, u8>| event.snapshot)
+        .collect();
+    producer.join().unwrap();
+    assert_eq!(
+        snapshots,
+        vec![hashmap! {0 => 0}, hashmap! {0 => 0, 1 => 1}, hashmap! {0 => 0, 1 => 1, 2 => 2}]
+    );
+}
+
+#[test]
+fn into_blocking_iter_wakes_on_mutable_changes() {
+    let input_vec = MutableVector::<u8>::new();
+    let mut events = input_vec.as_signal().into_blocking_iter();
+    assert_eq!(events.next().unwrap().snapshot, vector![]);
+
+    // Changes from another thread wake the blocked iterator, however they are batched.
+    let producer = std::thread::spawn(move || {
+        for i in 0..3u8 {
+            std::thread::sleep(Duration::from_millis(10));
+            input_vec.write().push_back(i);
+        }
+    });
+    let last = events.find(|event| event.snapshot.len() == 3).unwrap();
+    producer.join().unwrap();
+    assert_eq!(last.snapshot, vector![0, 1, 2]);
+}
+
+#[test]
+fn assert_invariant_holds() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    let mut signal = input_map
+        .as_signal()
+        .assert_invariant(|event| assert!(event.snapshot.len() < 3));
+    util::poll_all(&mut signal);
+
+    input_map.write().insert(1, 1);
+    input_map.write().insert(2, 2);
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(*util::get_snapshots(&poll.items).last().unwrap(), hashmap! {1 => 1, 2 => 2});
+}
+
+#[test]
+#[should_panic]
+fn assert_invariant_violated() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    let mut signal = input_map
+        .as_signal()
+        .assert_invariant(|event| assert!(event.snapshot.len() < 3));
+    util::poll_all(&mut signal);
+
+    input_map.write().replace(vec![(1, 1), (2, 2), (3, 3)].into_iter());
+    util::poll_all(&mut signal);
+}
+
+#[test]
+fn broadcast_signal_without_replay() {
+    let input_map = MutableHashMap::<u8, u8>::new();
+    input_map.write().insert(1, 1);
+    let broadcaster = input_map.as_signal().broadcast();
+    let mut driver = broadcaster.get_signal();
+    util::poll_all(&mut driver);
+
+    let mut replayed = broadcaster.get_signal();
+    let mut not_replayed = broadcaster.get_signal_without_replay();
+    let poll = util::poll_all(&mut replayed);
+    assert_eq!(util::get_snapshots(&poll.items), vec![hashmap! {1 => 1}]);
+    assert!(util::poll_all(&mut not_replayed).items.is_empty());
+
+    input_map.write().insert(2, 2);
+    let poll = util::poll_all(&mut not_replayed);
+    assert_eq!(util::get_snapshots(&poll.items), vec![hashmap! {1 => 1, 2 => 2}]);
+}
+
+#[test]
+fn broadcast_diamond() {
+    let input_vec = MutableVector::<u8>::new();
+    input_vec.write().replace(vec![1, 2].into_iter());
+    let broadcaster = input_vec.as_signal().broadcast();
+    let mut totals = broadcaster
+        .get_signal()
+        .combine_with(broadcaster.get_signal().map(|v| v * 10), |a, b| a + b);
+    let poll = util::poll_all(&mut totals);
+    assert_eq!(*util::get_snapshots(&poll.items).last().unwrap(), vector![11, 22]);
+
+    input_vec.write().push_back(3);
+    let poll = util::poll_all(&mut totals);
+    assert_eq!(*util::get_snapshots(&poll.items).last().unwrap(), vector![11, 22, 33]);
+}
+
+#[test]
+fn broadcast_cycle_does_not_deadlock() {
+    type Feedback = Box<dyn StructuralSignal<Item = HashMapEvent<u8, u8>> + Unpin>;
+    let input_map = MutableHashMap::<u8, u8>::new();
+    input_map.write().insert(1, 1);
+    let mut input = input_map.as_signal();
+
+    // The upstream of the broadcaster polls one of the broadcaster's own signals.
+    let feedback: Rc<RefCell<Option<Feedback>>> = Rc::new(RefCell::new(None));
+    let upstream_feedback = feedback.clone();
+    let upstream = structural_from_fn(move |cx| {
+        if let Some(signal) = upstream_feedback.borrow_mut().as_mut() {
+            let _ = Pin::new(&mut **signal).poll_change(cx);
+        }
+        Pin::new(&mut input).poll_change(cx)
+    });
+    let broadcaster = upstream.broadcast();
+    *feedback.borrow_mut() = Some(Box::new(broadcaster.get_signal()));
+
+    let mut signal = broadcaster.get_signal();
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(util::get_snapshots(&poll.items), vec![hashmap! {1 => 1}]);
+
+    input_map.write().insert(2, 2);
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(util::get_snapshots(&poll.items), vec![hashmap! {1 => 1, 2 => 2}]);
+}
+
+#[test]
+fn select_all_observes_every_source() {
+    let shards: Vec<MutableHashMap<u8, u8>> = (0..3).map(|_| MutableHashMap::new()).collect();
+    let mut merged = select_all(shards.iter().map(|shard| shard.as_signal()).collect());
+    util::poll_all(&mut merged);
+
+    for (index, shard) in shards.iter().enumerate() {
+        shard.write().insert(index as u8, index as u8);
+    }
+    shards[1].write().insert(10, 10);
+    let poll = util::poll_all(&mut merged);
+    let snapshots = util::get_snapshots(&poll.items);
+    assert_eq!(snapshots.len(), 3);
+    assert!(snapshots.contains(&hashmap! {0 => 0}));
+    assert!(snapshots.contains(&hashmap! {1 => 1, 10 => 10}));
+    assert!(snapshots.contains(&hashmap! {2 => 2}));
+    assert!(!poll.is_done);
+}
+
+#[test]
+fn snapshot_async_waits_for_first_event() {
+    let (sender, receiver) = mpsc::unbounded();
+    let mut snapshot = ChannelStructuralSignal::new(receiver).snapshot_async();
+    let mut cx = std::task::Context::from_waker(futures::task::noop_waker_ref());
+    assert_eq!(snapshot.poll_unpin(&mut cx), Poll::Pending);
+
+    sender
+        .unbounded_send(HashMapEvent::new(hashmap! {1 => 1}, vec![MapDiff::Replace {}]))
+        .unwrap();
+    let insert = HashMapEvent::new(hashmap! {1 => 1, 2 => 2}, vec![MapDiff::Insert { key: 2 }]);
+    sender.unbounded_send(insert).unwrap();
+    assert_eq!(snapshot.poll_unpin(&mut cx), Poll::Ready(Some(hashmap! {1 => 1, 2 => 2})));
+
+    // Resolves to None if the Signal ends without an event.
+    let (sender, receiver) = mpsc::unbounded::<HashMapEvent<u8, u8>>();
+    drop(sender);
+    let snapshot = ChannelStructuralSignal::new(receiver).snapshot_async();
+    assert_eq!(futures::executor::block_on(snapshot), None);
+}
+
+#[test]
+fn to_stream_ends_with_signal() {
+    use futures::StreamExt;
+
+    let (sender, receiver) = mpsc::unbounded();
+    for i in 0..4u8 {
+        sender.unbounded_send(i).unwrap();
+    }
+    drop(sender);
+
+    let evens = ChannelStructuralSignal::new(receiver)
+        .to_stream()
+        .filter(|i| futures::future::ready(i % 2 == 0))
+        .collect::<Vec<u8>>();
+    assert_eq!(futures::executor::block_on(evens), vec![0, 2]);
+}
@@ -2,6 +2,7 @@ use futures_executor::block_on;
 use futures_util::future::poll_fn;
 use pin_utils::pin_mut;
 use signals_im::hash_map::{HashMapEvent, MapDiff};
+use signals_im::vector::{VectorDiff, VectorEvent};
 use signals_im::StructuralSignal;
 use signals_im::SnapshottableEvent;
 use std::hash::Hash;
@@ -69,3 +70,14 @@ where
         .flatten()
         .collect()
 }
+
+/// Extracts a list of diffs from a list of VectorEvents.
+pub fn get_vector_diffs<T>(events: &[VectorEvent<T>]) -> Vec<VectorDiff>
+where
+    T: Clone,
+{
+    events
+        .iter()
+        .flat_map(|event| event.diffs.clone())
+        .collect()
+}
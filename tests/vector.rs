@@ -0,0 +1,1520 @@
+use futures::channel::mpsc;
+use futures::executor::LocalPool;
+use futures::task::{noop_waker_ref, LocalSpawnExt};
+use futures::FutureExt;
+use futures_signals::signal::{Mutable, Signal};
+use futures_signals::signal_vec::{MutableVec, SignalVec, VecDiff};
+use im::vector;
+use signals_im::testing::DiffTarget;
+use signals_im::vector::{
+    diff_vectors, signal_vec_to_structural, MutableVector, MutableVectorState, SignalVectorExt,
+    VectorDiff, VectorDiffWithValue, VectorEvent,
+};
+use signals_im::ChannelStructuralSignal;
+use std::cell::RefCell;
+use std::iter::FromIterator;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll};
+
+mod util;
+
+#[test]
+fn range_insert_before_window() {
+    let input_vec = MutableVector::<u8>::new();
+    input_vec.write().replace(vec![0, 1, 2, 3, 4, 5].into_iter());
+
+    let mut window = input_vec.as_signal().get_signal_for_range(2..4);
+    let poll_1 = util::poll_all(&mut window);
+    assert_eq!(*util::get_snapshots(&poll_1.items).last().unwrap(), vector![2, 3]);
+
+    input_vec.write().insert(0, 9);
+    let poll_2 = util::poll_all(&mut window);
+    assert_eq!(*util::get_snapshots(&poll_2.items).last().unwrap(), vector![1, 2]);
+
+    input_vec.write().remove(0);
+    input_vec.write().remove(0);
+    let poll_3 = util::poll_all(&mut window);
+    assert_eq!(*util::get_snapshots(&poll_3.items).last().unwrap(), vector![3, 4]);
+}
+
+#[test]
+fn range_insert_within_window() {
+    let input_vec = MutableVector::<u8>::new();
+    input_vec.write().replace(vec![0, 1, 2, 3, 4, 5].into_iter());
+
+    let mut window = input_vec.as_signal().get_signal_for_range(2..4);
+    util::poll_all(&mut window);
+
+    input_vec.write().insert(3, 9);
+    let poll_1 = util::poll_all(&mut window);
+    assert_eq!(*util::get_snapshots(&poll_1.items).last().unwrap(), vector![2, 9]);
+
+    input_vec.write().set(2, 7);
+    let poll_2 = util::poll_all(&mut window);
+    assert_eq!(*util::get_snapshots(&poll_2.items).last().unwrap(), vector![7, 9]);
+
+    input_vec.write().remove(2);
+    let poll_3 = util::poll_all(&mut window);
+    assert_eq!(*util::get_snapshots(&poll_3.items).last().unwrap(), vector![9, 3]);
+}
+
+#[test]
+fn range_insert_after_window() {
+    let input_vec = MutableVector::<u8>::new();
+    input_vec.write().replace(vec![0, 1, 2, 3, 4, 5].into_iter());
+
+    let mut window = input_vec.as_signal().get_signal_for_range(2..4);
+    util::poll_all(&mut window);
+
+    input_vec.write().insert(4, 9);
+    input_vec.write().push_back(9);
+    input_vec.write().set(5, 8);
+    let poll = util::poll_all(&mut window);
+    assert!(poll.items.is_empty());
+}
+
+#[test]
+fn range_grows_with_short_input() {
+    let input_vec = MutableVector::<u8>::new();
+    input_vec.write().push_back(0);
+
+    let mut window = input_vec.as_signal().get_signal_for_range(1..3);
+    let poll_1 = util::poll_all(&mut window);
+    assert_eq!(*util::get_snapshots(&poll_1.items).last().unwrap(), vector![]);
+
+    input_vec.write().push_back(1);
+    input_vec.write().push_front(2);
+    input_vec.write().push_back(3);
+    let poll_2 = util::poll_all(&mut window);
+    assert_eq!(*util::get_snapshots(&poll_2.items).last().unwrap(), vector![0, 1]);
+}
+
+#[test]
+#[should_panic]
+fn range_rejects_inverted_range() {
+    let input_vec = MutableVector::<u8>::new();
+    #[allow(clippy::reversed_empty_ranges)]
+    let _window = input_vec.as_signal().get_signal_for_range(3..1);
+}
+
+#[test]
+fn try_into_inner_unique_owner() {
+    let input_vec = MutableVector::<u8>::new();
+    input_vec.write().push_back(1);
+    input_vec.write().push_back(2);
+
+    // Dropped signals release their hold on the shared state.
+    drop(input_vec.as_signal());
+    assert_eq!(input_vec.try_into_inner().ok().unwrap(), vector![1, 2]);
+}
+
+#[test]
+fn try_into_inner_shared_owner() {
+    let input_vec = MutableVector::<u8>::new();
+    input_vec.write().push_back(1);
+
+    let reader = input_vec.reader();
+    let input_vec = input_vec.try_into_inner().err().unwrap();
+    assert_eq!(input_vec.read().snapshot(), vector![1]);
+
+    drop(reader);
+    assert_eq!(input_vec.try_into_inner().ok().unwrap(), vector![1]);
+}
+
+#[test]
+fn map_with_index() {
+    let input_vec = MutableVector::<u8>::new();
+    input_vec.write().push_back(5);
+    input_vec.write().push_back(6);
+
+    let mut indexed = input_vec.as_signal().map_with_index(|i, v| (i, *v));
+    let poll_1 = util::poll_all(&mut indexed);
+    assert_eq!(
+        *util::get_snapshots(&poll_1.items).last().unwrap(),
+        vector![(0, 5), (1, 6)]
+    );
+
+    input_vec.write().push_front(4);
+    let poll_2 = util::poll_all(&mut indexed);
+    assert_eq!(
+        *util::get_snapshots(&poll_2.items).last().unwrap(),
+        vector![(0, 4), (1, 5), (2, 6)]
+    );
+    assert_eq!(
+        util::get_vector_diffs(&poll_2.items),
+        vec![
+            VectorDiff::Insert { index: 0, snapshot_index: 0 },
+            VectorDiff::Update { index: 1, snapshot_index: 1 },
+            VectorDiff::Update { index: 2, snapshot_index: 2 },
+        ]
+    );
+
+    input_vec.write().remove(1);
+    let poll_3 = util::poll_all(&mut indexed);
+    assert_eq!(
+        *util::get_snapshots(&poll_3.items).last().unwrap(),
+        vector![(0, 4), (1, 6)]
+    );
+}
+
+#[test]
+fn granular_signal_initial_event() {
+    let input_vec = MutableVector::<u8>::new();
+    input_vec.write().replace(vec![1, 2, 3].into_iter());
+
+    let mut signal = input_vec.as_signal_granular().map(|v| v * 2);
+    let poll_1 = util::poll_all(&mut signal);
+    assert_eq!(*util::get_snapshots(&poll_1.items).last().unwrap(), vector![2, 4, 6]);
+
+    let mut signal = input_vec.as_signal_granular();
+    let poll_2 = util::poll_all(&mut signal);
+    assert_eq!(
+        util::get_vector_diffs(&poll_2.items),
+        vec![
+            VectorDiff::Insert { index: 0, snapshot_index: 0 },
+            VectorDiff::Insert { index: 1, snapshot_index: 1 },
+            VectorDiff::Insert { index: 2, snapshot_index: 2 },
+        ]
+    );
+
+    input_vec.write().clear();
+    let poll_3 = util::poll_all(&mut signal);
+    assert_eq!(util::get_vector_diffs(&poll_3.items), vec![VectorDiff::Clear {}]);
+
+    // An empty vector has nothing to insert, so it starts with a Replace.
+    let mut signal = input_vec.as_signal_granular();
+    let poll_4 = util::poll_all(&mut signal);
+    assert_eq!(util::get_vector_diffs(&poll_4.items), vec![VectorDiff::Replace {}]);
+}
+
+#[test]
+fn set_contents_from() {
+    let input_vec = MutableVector::<u8>::new();
+    input_vec.write().push_back(1);
+    let reader = input_vec.reader();
+    let mut signal = input_vec.as_signal();
+    util::poll_all(&mut signal);
+
+    let other_vec = MutableVector::<u8>::new();
+    other_vec.write().replace(vec![2, 3].into_iter());
+    input_vec.set_contents_from(&other_vec);
+
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(*util::get_snapshots(&poll.items).last().unwrap(), vector![2, 3]);
+    assert_eq!(util::get_vector_diffs(&poll.items), vec![VectorDiff::Replace {}]);
+    assert_eq!(reader.read().snapshot(), vector![2, 3]);
+
+    // Later changes to the other vector are not linked.
+    other_vec.write().push_back(4);
+    assert!(util::poll_all(&mut signal).items.is_empty());
+
+    // Copying an empty vector into an empty vector is a no-op, just like `replace`.
+    let empty_vec = MutableVector::<u8>::new();
+    let mut empty_signal = empty_vec.as_signal();
+    util::poll_all(&mut empty_signal);
+    empty_vec.set_contents_from(&MutableVector::new());
+    assert!(util::poll_all(&mut empty_signal).items.is_empty());
+}
+
+#[test]
+fn set_if_changed() {
+    let input_vec = MutableVector::<u8>::new();
+    input_vec.write().replace(vec![1, 2, 3].into_iter());
+    let mut signal = input_vec.as_signal();
+    util::poll_all(&mut signal);
+
+    assert_eq!(input_vec.write().set_if_changed(1, 2), None);
+    assert!(util::poll_all(&mut signal).items.is_empty());
+
+    assert_eq!(input_vec.write().set_if_changed(1, 4), Some(2));
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(*util::get_snapshots(&poll.items).last().unwrap(), vector![1, 4, 3]);
+    assert_eq!(
+        util::get_vector_diffs(&poll.items),
+        vec![VectorDiff::Update { index: 1, snapshot_index: 1 }]
+    );
+}
+
+#[test]
+fn from_mutable_signal() {
+    let mutable = Mutable::new(vector![1u8, 2]);
+    let (output_vec, future) = MutableVector::from_mutable_signal(mutable.signal_cloned());
+    let mut signal = output_vec.as_signal();
+    util::poll_all(&mut signal);
+
+    let mut pool = LocalPool::new();
+    pool.spawner().spawn_local(future).unwrap();
+    pool.run_until_stalled();
+    assert_eq!(output_vec.read().snapshot(), vector![1, 2]);
+
+    mutable.set(vector![1, 3, 4]);
+    pool.run_until_stalled();
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(*util::get_snapshots(&poll.items).last().unwrap(), vector![1, 3, 4]);
+    assert_eq!(util::get_vector_diffs(&poll.items), vec![VectorDiff::Replace {}]);
+}
+
+#[test]
+fn from_mutable_signal_diffed() {
+    let mutable = Mutable::new(vector![1u8, 2]);
+    let (output_vec, future) = MutableVector::from_mutable_signal_diffed(mutable.signal_cloned());
+    let mut signal = output_vec.as_signal();
+
+    let mut pool = LocalPool::new();
+    pool.spawner().spawn_local(future).unwrap();
+    pool.run_until_stalled();
+    util::poll_all(&mut signal);
+
+    mutable.set(vector![1, 3, 4]);
+    pool.run_until_stalled();
+    let poll_1 = util::poll_all(&mut signal);
+    assert_eq!(*util::get_snapshots(&poll_1.items).last().unwrap(), vector![1, 3, 4]);
+    assert_eq!(
+        util::get_vector_diffs(&poll_1.items),
+        vec![
+            VectorDiff::Update { index: 1, snapshot_index: 1 },
+            VectorDiff::Insert { index: 2, snapshot_index: 2 },
+        ]
+    );
+
+    mutable.set(vector![1]);
+    pool.run_until_stalled();
+    let poll_2 = util::poll_all(&mut signal);
+    assert_eq!(*util::get_snapshots(&poll_2.items).last().unwrap(), vector![1]);
+}
+
+#[test]
+fn identify_tracks_ids() {
+    let input_vec = MutableVector::<(u8, &str)>::new();
+    input_vec.write().replace(vec![(1, "a"), (2, "b"), (3, "c")].into_iter());
+
+    let mut identified = input_vec.as_signal().identify(|v| v.0);
+    util::poll_all(&mut identified);
+
+    // Removing an element and inserting it again is reported as a move.
+    let moved = input_vec.write().remove(0);
+    input_vec.write().push_back(moved);
+    let poll = util::poll_all(&mut identified);
+    assert_eq!(
+        *util::get_snapshots(&poll.items).last().unwrap(),
+        vector![(2, (2, "b")), (3, (3, "c")), (1, (1, "a"))]
+    );
+    assert_eq!(
+        util::get_vector_diffs(&poll.items),
+        vec![
+            VectorDiff::Move { from: 0, to: 2, snapshot_from: 0, snapshot_to: 2 },
+            VectorDiff::Update { index: 2, snapshot_index: 2 },
+        ]
+    );
+
+    // Inserting a different id after a remove is not a move.
+    input_vec.write().remove(0);
+    input_vec.write().push_back((4, "d"));
+    let poll = util::poll_all(&mut identified);
+    assert_eq!(
+        util::get_vector_diffs(&poll.items),
+        vec![
+            VectorDiff::Remove { index: 0, snapshot_index: 0 },
+            VectorDiff::Insert { index: 2, snapshot_index: 2 },
+        ]
+    );
+}
+
+#[test]
+fn chain_offsets_right_diffs() {
+    let left_vec = MutableVector::<u8>::new();
+    left_vec.write().replace(vec![1, 2].into_iter());
+    let right_vec = MutableVector::<u8>::new();
+    right_vec.write().replace(vec![10, 20].into_iter());
+
+    let mut chained = left_vec.as_signal().chain(right_vec.as_signal());
+    let poll_1 = util::poll_all(&mut chained);
+    assert_eq!(*util::get_snapshots(&poll_1.items).last().unwrap(), vector![1, 2, 10, 20]);
+
+    right_vec.write().set(1, 21);
+    let poll_2 = util::poll_all(&mut chained);
+    assert_eq!(
+        util::get_vector_diffs(&poll_2.items),
+        vec![VectorDiff::Update { index: 3, snapshot_index: 3 }]
+    );
+
+    // Growing the left side moves the boundary.
+    left_vec.write().push_front(0);
+    let poll_3 = util::poll_all(&mut chained);
+    assert_eq!(
+        util::get_vector_diffs(&poll_3.items),
+        vec![VectorDiff::Insert { index: 0, snapshot_index: 0 }]
+    );
+    right_vec.write().push_back(30);
+    let poll_4 = util::poll_all(&mut chained);
+    assert_eq!(
+        *util::get_snapshots(&poll_4.items).last().unwrap(),
+        vector![0, 1, 2, 10, 21, 30]
+    );
+
+    left_vec.write().clear();
+    let poll_5 = util::poll_all(&mut chained);
+    assert_eq!(*util::get_snapshots(&poll_5.items).last().unwrap(), vector![10, 21, 30]);
+
+    right_vec.write().replace(vec![7].into_iter());
+    left_vec.write().push_back(3);
+    let poll_6 = util::poll_all(&mut chained);
+    assert_eq!(*util::get_snapshots(&poll_6.items).last().unwrap(), vector![3, 7]);
+}
+
+#[test]
+fn sorted_by_inserts_at_sorted_index() {
+    let input_vec = MutableVector::<u8>::new();
+    input_vec.write().replace(vec![5, 1, 3].into_iter());
+
+    let mut sorted = input_vec.as_signal().sorted_by(|a, b| a.cmp(b));
+    let poll_1 = util::poll_all(&mut sorted);
+    assert_eq!(*util::get_snapshots(&poll_1.items).last().unwrap(), vector![1, 3, 5]);
+
+    input_vec.write().push_front(4);
+    let poll_2 = util::poll_all(&mut sorted);
+    assert_eq!(
+        util::get_vector_diffs(&poll_2.items),
+        vec![VectorDiff::Insert { index: 2, snapshot_index: 2 }]
+    );
+
+    // An update that keeps the sort order is set in place.
+    input_vec.write().set(1, 6);
+    let poll_3 = util::poll_all(&mut sorted);
+    assert_eq!(
+        util::get_vector_diffs(&poll_3.items),
+        vec![VectorDiff::Update { index: 3, snapshot_index: 3 }]
+    );
+
+    // An update that changes the sort order moves the value.
+    input_vec.write().set(0, 7);
+    let poll_4 = util::poll_all(&mut sorted);
+    assert_eq!(*util::get_snapshots(&poll_4.items).last().unwrap(), vector![1, 3, 6, 7]);
+
+    input_vec.write().remove(0);
+    let poll_5 = util::poll_all(&mut sorted);
+    assert_eq!(*util::get_snapshots(&poll_5.items).last().unwrap(), vector![1, 3, 6]);
+}
+
+#[test]
+fn sorted_by_keeps_equal_values_distinct() {
+    let input_vec = MutableVector::<(u8, char)>::new();
+    input_vec.write().replace(vec![(1, 'a'), (0, 'b'), (1, 'c')].into_iter());
+
+    let mut sorted = input_vec.as_signal().sorted_by(|a, b| a.0.cmp(&b.0));
+    util::poll_all(&mut sorted);
+
+    // Removing one of two equal values removes that exact one.
+    input_vec.write().remove(2);
+    let poll = util::poll_all(&mut sorted);
+    assert_eq!(
+        *util::get_snapshots(&poll.items).last().unwrap(),
+        vector![(0, 'b'), (1, 'a')]
+    );
+}
+
+#[test]
+fn dedup_collapses_neighbors() {
+    let input_vec = MutableVector::<u8>::new();
+    input_vec.write().replace(vec![1, 1, 2, 3].into_iter());
+
+    let mut deduped = input_vec.as_signal().dedup();
+    let poll_1 = util::poll_all(&mut deduped);
+    assert_eq!(*util::get_snapshots(&poll_1.items).last().unwrap(), vector![1, 2, 3]);
+
+    // An update that matches the value before it removes it from the output.
+    input_vec.write().set(2, 1);
+    let poll_2 = util::poll_all(&mut deduped);
+    assert_eq!(
+        util::get_vector_diffs(&poll_2.items),
+        vec![VectorDiff::Remove { index: 1, snapshot_index: 1 }]
+    );
+    assert_eq!(*util::get_snapshots(&poll_2.items).last().unwrap(), vector![1, 3]);
+
+    // Duplicates of the value before them emit nothing.
+    input_vec.write().insert(1, 1);
+    assert!(util::poll_all(&mut deduped).items.is_empty());
+
+    // Splitting a run brings the hidden duplicate back.
+    input_vec.write().set(1, 5);
+    let poll_3 = util::poll_all(&mut deduped);
+    assert_eq!(*util::get_snapshots(&poll_3.items).last().unwrap(), vector![1, 5, 1, 3]);
+
+    // Removing the value between two equal values collapses them.
+    input_vec.write().remove(1);
+    let poll_4 = util::poll_all(&mut deduped);
+    assert_eq!(*util::get_snapshots(&poll_4.items).last().unwrap(), vector![1, 3]);
+}
+
+#[test]
+fn combine_with_sums_parallel_vectors() {
+    let left_vec = MutableVector::<u8>::new();
+    left_vec.write().replace(vec![1, 2, 3].into_iter());
+    let right_vec = MutableVector::<u8>::new();
+    right_vec.write().replace(vec![10, 20, 30, 40].into_iter());
+
+    let mut summed = left_vec
+        .as_signal()
+        .combine_with(right_vec.as_signal(), |l, r| l + r);
+    let poll_1 = util::poll_all(&mut summed);
+    assert_eq!(*util::get_snapshots(&poll_1.items).last().unwrap(), vector![11, 22, 33]);
+
+    left_vec.write().set(1, 5);
+    let poll_2 = util::poll_all(&mut summed);
+    assert_eq!(*util::get_snapshots(&poll_2.items).last().unwrap(), vector![11, 25, 33]);
+    assert_eq!(
+        util::get_vector_diffs(&poll_2.items),
+        vec![VectorDiff::Update { index: 1, snapshot_index: 1 }]
+    );
+
+    // Growing the shorter side extends the output.
+    left_vec.write().push_back(4);
+    let poll_3 = util::poll_all(&mut summed);
+    assert_eq!(
+        *util::get_snapshots(&poll_3.items).last().unwrap(),
+        vector![11, 25, 33, 44]
+    );
+
+    // Removing from one side shifts which elements are paired up.
+    right_vec.write().remove(0);
+    let poll_4 = util::poll_all(&mut summed);
+    assert_eq!(*util::get_snapshots(&poll_4.items).last().unwrap(), vector![21, 35, 43]);
+
+    left_vec.write().clear();
+    let poll_5 = util::poll_all(&mut summed);
+    assert_eq!(*util::get_snapshots(&poll_5.items).last().unwrap(), vector![]);
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Shape {
+    Circle(u8),
+    Square(u8),
+}
+
+#[test]
+fn demux_enum_variants() {
+    let input_vec = MutableVector::<Shape>::new();
+    input_vec
+        .write()
+        .replace(vec![Shape::Circle(1), Shape::Square(2), Shape::Circle(3)].into_iter());
+
+    let demuxer = input_vec
+        .as_signal()
+        .demux(|shape| matches!(shape, Shape::Circle(_)));
+    let mut circles = demuxer.get(true);
+    let mut squares = demuxer.get(false);
+    let poll_1 = util::poll_all(&mut circles);
+    assert_eq!(
+        *util::get_snapshots(&poll_1.items).last().unwrap(),
+        vector![Shape::Circle(1), Shape::Circle(3)]
+    );
+    let poll_2 = util::poll_all(&mut squares);
+    assert_eq!(*util::get_snapshots(&poll_2.items).last().unwrap(), vector![Shape::Square(2)]);
+
+    input_vec.write().insert(1, Shape::Circle(4));
+    input_vec.write().push_front(Shape::Square(5));
+    let poll_3 = util::poll_all(&mut circles);
+    assert_eq!(
+        *util::get_snapshots(&poll_3.items).last().unwrap(),
+        vector![Shape::Circle(1), Shape::Circle(4), Shape::Circle(3)]
+    );
+    assert_eq!(
+        util::get_vector_diffs(&poll_3.items),
+        vec![VectorDiff::Insert { index: 1, snapshot_index: 1 }]
+    );
+    let poll_4 = util::poll_all(&mut squares);
+    assert_eq!(
+        *util::get_snapshots(&poll_4.items).last().unwrap(),
+        vector![Shape::Square(5), Shape::Square(2)]
+    );
+
+    // Changing an element's variant moves it between outputs.
+    input_vec.write().set(1, Shape::Square(1));
+    let poll_5 = util::poll_all(&mut circles);
+    assert_eq!(
+        *util::get_snapshots(&poll_5.items).last().unwrap(),
+        vector![Shape::Circle(4), Shape::Circle(3)]
+    );
+    let poll_6 = util::poll_all(&mut squares);
+    assert_eq!(
+        *util::get_snapshots(&poll_6.items).last().unwrap(),
+        vector![Shape::Square(5), Shape::Square(1), Shape::Square(2)]
+    );
+}
+
+#[test]
+fn diffs_with_values_round_trip() {
+    let input_vec = MutableVector::<u8>::new();
+    input_vec.write().replace(vec![1, 2, 3].into_iter());
+    let mut signal = input_vec.as_signal();
+    let poll_1 = util::poll_all(&mut signal);
+    assert_eq!(
+        poll_1.items[0].diffs_with_values(),
+        vec![VectorDiffWithValue::Replace { values: vector![1, 2, 3] }]
+    );
+
+    let previous_snapshot = input_vec.read().snapshot();
+    input_vec.write().push_back(4);
+    input_vec.write().insert(0, 5);
+    input_vec.write().remove(2);
+    let poll_2 = util::poll_all(&mut signal);
+    let event = poll_2.items.last().unwrap();
+    let diffs_with_values = event.diffs_with_values();
+
+    // Replaying the value-carrying diffs does not need the new snapshot.
+    let mut replayed = previous_snapshot.clone();
+    for diff in diffs_with_values.iter() {
+        diff.apply_to(&mut replayed);
+    }
+    assert_eq!(replayed, vector![5, 1, 3, 4]);
+
+    let rebuilt = VectorEvent::from_diffs_with_values(previous_snapshot, diffs_with_values.clone());
+    assert_eq!(rebuilt.snapshot, event.snapshot);
+    assert_eq!(rebuilt.diffs_with_values(), diffs_with_values);
+}
+
+#[test]
+fn prepend_many() {
+    let input_vec = MutableVector::<u8>::new();
+    input_vec.write().push_back(200);
+    let mut signal = input_vec.as_signal();
+    util::poll_all(&mut signal);
+
+    input_vec.write().set(0, 250);
+    input_vec.write().push_back(201);
+    input_vec.write().prepend(0..100);
+    let poll = util::poll_all(&mut signal);
+    let snapshot = util::get_snapshots(&poll.items).pop().unwrap();
+    assert_eq!(snapshot.len(), 102);
+    assert_eq!(snapshot[0], 0);
+    assert_eq!(snapshot[99], 99);
+    assert_eq!(snapshot[100], 250);
+    assert_eq!(snapshot[101], 201);
+
+    // Each pending diff is shifted by the number of prepended items exactly once, and
+    // the prepended items are inserted in order without shifting each other.
+    let mut expected_diffs = vec![
+        VectorDiff::Update { index: 0, snapshot_index: 100 },
+        VectorDiff::Insert { index: 1, snapshot_index: 101 },
+    ];
+    expected_diffs
+        .extend((0..100).map(|index| VectorDiff::Insert { index, snapshot_index: index }));
+    assert_eq!(util::get_vector_diffs(&poll.items), expected_diffs);
+
+    // Prepending nothing does not shift or emit anything.
+    input_vec.write().prepend(vec![]);
+    assert!(util::poll_all(&mut signal).items.is_empty());
+}
+
+#[test]
+fn map_only_maps_new_values() {
+    let input_vec = MutableVector::<u8>::new();
+    input_vec.write().replace(vec![1, 2].into_iter());
+
+    let mapped_values = Rc::new(RefCell::new(vec![]));
+    let recorder = mapped_values.clone();
+    let mut mapped = input_vec.as_signal().map(move |v| {
+        recorder.borrow_mut().push(*v);
+        v * 10
+    });
+    util::poll_all(&mut mapped);
+    assert_eq!(*mapped_values.borrow(), vec![1, 2]);
+
+    // Inserting before existing elements shifts them without re-mapping them, and the
+    // shifted snapshot indices still point at the inserted values.
+    input_vec.write().insert(0, 3);
+    input_vec.write().insert(1, 4);
+    input_vec.write().push_front(5);
+    let poll = util::poll_all(&mut mapped);
+    assert_eq!(
+        *util::get_snapshots(&poll.items).last().unwrap(),
+        vector![50, 30, 40, 10, 20]
+    );
+    assert_eq!(*mapped_values.borrow(), vec![1, 2, 3, 4, 5]);
+
+    input_vec.write().remove(1);
+    input_vec.write().set(0, 6);
+    let poll = util::poll_all(&mut mapped);
+    assert_eq!(*util::get_snapshots(&poll.items).last().unwrap(), vector![60, 40, 10, 20]);
+    assert_eq!(*mapped_values.borrow(), vec![1, 2, 3, 4, 5, 6]);
+}
+
+#[test]
+fn remove_cancels_insert_shifted_onto_removed_index() {
+    let input_vec = MutableVector::<u8>::new();
+    input_vec.write().push_back(1);
+    let mut signal = input_vec.as_signal();
+    util::poll_all(&mut signal);
+
+    // The inserted value shifts onto the index of the first removal, and the second
+    // removal must still cancel it out.
+    input_vec.write().push_back(2);
+    input_vec.write().remove(0);
+    input_vec.write().remove(0);
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(
+        util::get_vector_diffs(&poll.items),
+        vec![VectorDiff::Remove { index: 0, snapshot_index: 0 }]
+    );
+    assert_eq!(*util::get_snapshots(&poll.items).last().unwrap(), vector![]);
+}
+
+#[test]
+fn replace_empty_with_empty_is_noop() {
+    let input_vec = MutableVector::<u8>::new();
+    let mut signal = input_vec.as_signal();
+    util::poll_all(&mut signal);
+
+    input_vec.write().replace(vec![].into_iter());
+    assert!(util::poll_all(&mut signal).items.is_empty());
+
+    input_vec.write().push_back(1);
+    util::poll_all(&mut signal);
+    input_vec.write().replace(vec![].into_iter());
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(util::get_vector_diffs(&poll.items), vec![VectorDiff::Replace {}]);
+    assert_eq!(*util::get_snapshots(&poll.items).last().unwrap(), vector![]);
+}
+
+#[test]
+fn on_diff_fires_once_per_diff() {
+    let input_vec = MutableVector::<u8>::new();
+    let seen = Rc::new(RefCell::new(vec![]));
+    let seen_clone = seen.clone();
+    let mut future = input_vec.as_signal().on_diff(move |diff, snapshot| {
+        let value = diff.get_value_from_snapshot(snapshot).cloned();
+        seen_clone.borrow_mut().push((diff.clone(), value));
+    });
+    let mut cx = Context::from_waker(noop_waker_ref());
+    assert_eq!(future.poll_unpin(&mut cx), Poll::Pending);
+    seen.borrow_mut().clear();
+
+    input_vec.write().push_back(1);
+    input_vec.write().push_back(3);
+    input_vec.write().insert(1, 2);
+    input_vec.write().set(0, 0);
+    assert_eq!(future.poll_unpin(&mut cx), Poll::Pending);
+    assert_eq!(
+        *seen.borrow(),
+        vec![
+            (VectorDiff::Insert { index: 0, snapshot_index: 0 }, Some(0)),
+            (VectorDiff::Insert { index: 1, snapshot_index: 2 }, Some(3)),
+            (VectorDiff::Insert { index: 1, snapshot_index: 1 }, Some(2)),
+        ]
+    );
+}
+
+#[test]
+fn replace_common_prefix_suffix() {
+    let input_vec = MutableVector::<u8>::new();
+    input_vec.write().replace(vec![1, 2, 3].into_iter());
+    let mut signal = input_vec.as_signal();
+    util::poll_all(&mut signal);
+
+    // Pure append
+    input_vec.write().replace_common_prefix_suffix(vec![1, 2, 3, 4, 5]);
+    let poll_1 = util::poll_all(&mut signal);
+    assert_eq!(*util::get_snapshots(&poll_1.items).last().unwrap(), vector![1, 2, 3, 4, 5]);
+    assert_eq!(
+        util::get_vector_diffs(&poll_1.items),
+        vec![
+            VectorDiff::Insert { index: 3, snapshot_index: 3 },
+            VectorDiff::Insert { index: 4, snapshot_index: 4 },
+        ]
+    );
+
+    // Pure prepend
+    input_vec.write().replace_common_prefix_suffix(vec![0, 1, 2, 3, 4, 5]);
+    let poll_2 = util::poll_all(&mut signal);
+    assert_eq!(*util::get_snapshots(&poll_2.items).last().unwrap(), vector![0, 1, 2, 3, 4, 5]);
+    assert_eq!(
+        util::get_vector_diffs(&poll_2.items),
+        vec![VectorDiff::Insert { index: 0, snapshot_index: 0 }]
+    );
+
+    // Single middle change
+    input_vec.write().replace_common_prefix_suffix(vec![0, 1, 9, 3, 4, 5]);
+    let poll_3 = util::poll_all(&mut signal);
+    assert_eq!(*util::get_snapshots(&poll_3.items).last().unwrap(), vector![0, 1, 9, 3, 4, 5]);
+    assert_eq!(
+        util::get_vector_diffs(&poll_3.items),
+        vec![
+            VectorDiff::Insert { index: 2, snapshot_index: 2 },
+            VectorDiff::Remove { index: 3, snapshot_index: 3 },
+        ]
+    );
+
+    // Shrinking the middle
+    input_vec.write().replace_common_prefix_suffix(vec![0, 7, 5]);
+    let poll_4 = util::poll_all(&mut signal);
+    assert_eq!(*util::get_snapshots(&poll_4.items).last().unwrap(), vector![0, 7, 5]);
+    assert_eq!(
+        util::get_vector_diffs(&poll_4.items),
+        vec![
+            VectorDiff::Insert { index: 1, snapshot_index: 1 },
+            VectorDiff::Remove { index: 2, snapshot_index: 2 },
+            VectorDiff::Remove { index: 2, snapshot_index: 2 },
+            VectorDiff::Remove { index: 2, snapshot_index: 2 },
+            VectorDiff::Remove { index: 2, snapshot_index: 2 },
+        ]
+    );
+}
+
+#[test]
+fn filter() {
+    let input_vec = MutableVector::<u8>::new();
+    input_vec.write().replace(vec![1, 2, 3, 4].into_iter());
+
+    let mut evens = input_vec.as_signal().filter(|v| v % 2 == 0);
+    let poll_1 = util::poll_all(&mut evens);
+    assert_eq!(*util::get_snapshots(&poll_1.items).last().unwrap(), vector![2, 4]);
+
+    // Inserts and removes only reach the output when the value passes.
+    input_vec.write().insert(0, 5);
+    input_vec.write().insert(2, 8);
+    let poll_2 = util::poll_all(&mut evens);
+    assert_eq!(*util::get_snapshots(&poll_2.items).last().unwrap(), vector![8, 2, 4]);
+    assert_eq!(
+        util::get_vector_diffs(&poll_2.items),
+        vec![VectorDiff::Insert { index: 0, snapshot_index: 0 }]
+    );
+
+    // An update that flips a value from passing to failing removes it.
+    input_vec.write().set(3, 7);
+    let poll_3 = util::poll_all(&mut evens);
+    assert_eq!(*util::get_snapshots(&poll_3.items).last().unwrap(), vector![8, 4]);
+    assert_eq!(
+        util::get_vector_diffs(&poll_3.items),
+        vec![VectorDiff::Remove { index: 1, snapshot_index: 1 }]
+    );
+
+    // An update that flips a value from failing to passing inserts it.
+    input_vec.write().set(0, 6);
+    input_vec.write().set(4, 0);
+    let poll_4 = util::poll_all(&mut evens);
+    assert_eq!(*util::get_snapshots(&poll_4.items).last().unwrap(), vector![6, 8, 0, 4]);
+    assert_eq!(
+        util::get_vector_diffs(&poll_4.items),
+        vec![
+            VectorDiff::Insert { index: 0, snapshot_index: 0 },
+            VectorDiff::Insert { index: 2, snapshot_index: 2 },
+        ]
+    );
+}
+
+#[test]
+fn to_signal_vec() {
+    let input_vec = MutableVector::<u8>::new();
+    input_vec.write().replace(vec![1, 2].into_iter());
+    let mut signal_vec = input_vec.as_signal().to_signal_vec();
+    let mut cx = Context::from_waker(noop_waker_ref());
+    assert_eq!(
+        Pin::new(&mut signal_vec).poll_vec_change(&mut cx),
+        Poll::Ready(Some(VecDiff::Replace { values: vec![1, 2] }))
+    );
+    assert_eq!(Pin::new(&mut signal_vec).poll_vec_change(&mut cx), Poll::Pending);
+
+    input_vec.write().push_back(3);
+    input_vec.write().insert(0, 0);
+    input_vec.write().remove(1);
+    assert_eq!(
+        Pin::new(&mut signal_vec).poll_vec_change(&mut cx),
+        Poll::Ready(Some(VecDiff::Push { value: 3 }))
+    );
+    assert_eq!(
+        Pin::new(&mut signal_vec).poll_vec_change(&mut cx),
+        Poll::Ready(Some(VecDiff::InsertAt { index: 0, value: 0 }))
+    );
+    assert_eq!(
+        Pin::new(&mut signal_vec).poll_vec_change(&mut cx),
+        Poll::Ready(Some(VecDiff::RemoveAt { index: 1 }))
+    );
+    assert_eq!(Pin::new(&mut signal_vec).poll_vec_change(&mut cx), Poll::Pending);
+}
+
+#[test]
+fn windows_map_moving_sum() {
+    let input_vec = MutableVector::<u32>::new();
+    input_vec.write().replace(vec![1, 2, 3, 4].into_iter());
+
+    let mut sums = input_vec.as_signal().windows_map(2, |w| w.iter().sum::<u32>());
+    let poll_1 = util::poll_all(&mut sums);
+    assert_eq!(*util::get_snapshots(&poll_1.items).last().unwrap(), vector![3, 5, 7]);
+
+    // A middle update touches both windows that contain it.
+    input_vec.write().set(1, 10);
+    let poll_2 = util::poll_all(&mut sums);
+    assert_eq!(*util::get_snapshots(&poll_2.items).last().unwrap(), vector![11, 13, 7]);
+    assert_eq!(
+        util::get_vector_diffs(&poll_2.items),
+        vec![
+            VectorDiff::Update { index: 0, snapshot_index: 0 },
+            VectorDiff::Update { index: 1, snapshot_index: 1 },
+        ]
+    );
+
+    input_vec.write().insert(2, 100);
+    input_vec.write().push_front(5);
+    input_vec.write().push_back(6);
+    let poll_3 = util::poll_all(&mut sums);
+    assert_eq!(
+        *util::get_snapshots(&poll_3.items).last().unwrap(),
+        vector![6, 11, 110, 103, 7, 10]
+    );
+
+    input_vec.write().remove(3);
+    input_vec.write().pop_back();
+    let poll_4 = util::poll_all(&mut sums);
+    assert_eq!(*util::get_snapshots(&poll_4.items).last().unwrap(), vector![6, 11, 13, 7]);
+
+    // Inputs shorter than the window have no windows at all.
+    input_vec.write().replace(vec![1].into_iter());
+    let poll_5 = util::poll_all(&mut sums);
+    assert_eq!(*util::get_snapshots(&poll_5.items).last().unwrap(), vector![]);
+}
+
+fn apply_vec_diff(values: &mut Vec<u8>, diff: VecDiff<u8>) {
+    match diff {
+        VecDiff::Replace { values: new_values } => *values = new_values,
+        VecDiff::InsertAt { index, value } => values.insert(index, value),
+        VecDiff::UpdateAt { index, value } => values[index] = value,
+        VecDiff::RemoveAt { index } => {
+            values.remove(index);
+        }
+        VecDiff::Move { old_index, new_index } => {
+            let value = values.remove(old_index);
+            values.insert(new_index, value);
+        }
+        VecDiff::Push { value } => values.push(value),
+        VecDiff::Pop {} => {
+            values.pop();
+        }
+        VecDiff::Clear {} => values.clear(),
+    }
+}
+
+#[test]
+fn to_signal_vec_hand_crafted_events() {
+    let (sender, receiver) = mpsc::unbounded();
+    let mut signal_vec = ChannelStructuralSignal::new(receiver).to_signal_vec();
+    let mut cx = Context::from_waker(noop_waker_ref());
+    let mut values = vec![];
+
+    let events = vec![
+        VectorEvent::new(vector![1, 2, 3], vec![VectorDiff::Replace {}]),
+        // A Replace followed by more diffs in the same event.
+        VectorEvent::new(
+            vector![2, 3],
+            vec![VectorDiff::Replace {}, VectorDiff::Remove { index: 0, snapshot_index: 0 }],
+        ),
+        // Events without diffs are skipped.
+        VectorEvent::new(vector![2, 3], vec![]),
+        VectorEvent::new(
+            vector![3, 4],
+            vec![
+                VectorDiff::Insert { index: 2, snapshot_index: 1 },
+                VectorDiff::Remove { index: 0, snapshot_index: 0 },
+            ],
+        ),
+        // Diffs before a Clear are wiped out by it.
+        VectorEvent::new(
+            vector![7],
+            vec![
+                VectorDiff::Remove { index: 0, snapshot_index: 0 },
+                VectorDiff::Clear {},
+                VectorDiff::Insert { index: 0, snapshot_index: 0 },
+            ],
+        ),
+    ];
+    for event in events {
+        let snapshot = event.snapshot.clone();
+        sender.unbounded_send(event).unwrap();
+        while let Poll::Ready(Some(diff)) = Pin::new(&mut signal_vec).poll_vec_change(&mut cx) {
+            apply_vec_diff(&mut values, diff);
+        }
+        assert_eq!(values, snapshot.into_iter().collect::<Vec<u8>>());
+    }
+}
+
+#[test]
+fn len_signal_skips_updates() {
+    let input_vec = MutableVector::<u8>::new();
+    let mut lengths = input_vec.as_signal().len_signal();
+    let mut cx = Context::from_waker(noop_waker_ref());
+    assert_eq!(Pin::new(&mut lengths).poll_change(&mut cx), Poll::Ready(Some(0)));
+
+    input_vec.write().push_back(1);
+    input_vec.write().push_back(2);
+    assert_eq!(Pin::new(&mut lengths).poll_change(&mut cx), Poll::Ready(Some(2)));
+
+    input_vec.write().set(0, 5);
+    assert_eq!(Pin::new(&mut lengths).poll_change(&mut cx), Poll::Pending);
+
+    input_vec.write().remove(0);
+    assert_eq!(Pin::new(&mut lengths).poll_change(&mut cx), Poll::Ready(Some(1)));
+
+    input_vec.write().clear();
+    assert_eq!(Pin::new(&mut lengths).poll_change(&mut cx), Poll::Ready(Some(0)));
+
+    input_vec.write().replace(vec![1, 2, 3].into_iter());
+    assert_eq!(Pin::new(&mut lengths).poll_change(&mut cx), Poll::Ready(Some(3)));
+}
+
+#[test]
+fn fold_sum() {
+    let input_vec = MutableVector::<u32>::new();
+    input_vec.write().replace(vec![1, 2, 3].into_iter());
+    let mut sums = input_vec.as_signal().fold(0, |sum, v| sum + v);
+    let mut cx = Context::from_waker(noop_waker_ref());
+    assert_eq!(Pin::new(&mut sums).poll_change(&mut cx), Poll::Ready(Some(6)));
+    assert_eq!(Pin::new(&mut sums).poll_change(&mut cx), Poll::Pending);
+
+    input_vec.write().push_back(4);
+    assert_eq!(Pin::new(&mut sums).poll_change(&mut cx), Poll::Ready(Some(10)));
+
+    input_vec.write().set(0, 11);
+    assert_eq!(Pin::new(&mut sums).poll_change(&mut cx), Poll::Ready(Some(20)));
+
+    input_vec.write().remove(1);
+    assert_eq!(Pin::new(&mut sums).poll_change(&mut cx), Poll::Ready(Some(18)));
+
+    input_vec.write().clear();
+    assert_eq!(Pin::new(&mut sums).poll_change(&mut cx), Poll::Ready(Some(0)));
+}
+
+#[test]
+fn signal_vec_to_structural_matches_mutable_vec() {
+    let input_vec = MutableVec::<u8>::new_with_values(vec![1, 2, 3]);
+    let mut signal = signal_vec_to_structural(input_vec.signal_vec());
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(*util::get_snapshots(&poll.items).last().unwrap(), vector![1, 2, 3]);
+
+    {
+        let mut lock = input_vec.lock_mut();
+        lock.push(4);
+        lock.insert(0, 0);
+        lock.set(2, 20);
+        lock.remove(1);
+        lock.move_from_to(0, 3);
+        lock.pop();
+    }
+    let poll = util::poll_all(&mut signal);
+    let expected: im::Vector<u8> = input_vec.lock_ref().iter().cloned().collect();
+    assert_eq!(*util::get_snapshots(&poll.items).last().unwrap(), expected);
+
+    input_vec.lock_mut().clear();
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(*util::get_snapshots(&poll.items).last().unwrap(), vector![]);
+}
+
+#[test]
+fn enumerate_updates_trailing_rows() {
+    let input_vec = MutableVector::<char>::new();
+    input_vec.write().replace(vec!['a', 'b', 'd', 'e'].into_iter());
+    let mut rows = input_vec.as_signal().enumerate();
+    util::poll_all(&mut rows);
+
+    input_vec.write().insert(2, 'c');
+    let poll = util::poll_all(&mut rows);
+    assert_eq!(
+        *util::get_snapshots(&poll.items).last().unwrap(),
+        vector![(0, 'a'), (1, 'b'), (2, 'c'), (3, 'd'), (4, 'e')]
+    );
+    // Rows before the insert are left alone.
+    assert_eq!(
+        util::get_vector_diffs(&poll.items),
+        vec![
+            VectorDiff::Insert { index: 2, snapshot_index: 2 },
+            VectorDiff::Update { index: 3, snapshot_index: 3 },
+            VectorDiff::Update { index: 4, snapshot_index: 4 },
+        ]
+    );
+
+    input_vec.write().remove(0);
+    let poll = util::poll_all(&mut rows);
+    assert_eq!(
+        *util::get_snapshots(&poll.items).last().unwrap(),
+        vector![(0, 'b'), (1, 'c'), (2, 'd'), (3, 'e')]
+    );
+}
+
+#[test]
+fn extend_emits_one_batched_diff() {
+    let input_vec = MutableVector::<u8>::new();
+    input_vec.write().push_back(0);
+    let mut signal = input_vec.as_signal();
+    util::poll_all(&mut signal);
+
+    // The update is folded into the batch, and the insert before it shifts it along.
+    input_vec.write().extend(vec![1, 2, 3]);
+    input_vec.write().set(1, 10);
+    input_vec.write().push_front(9);
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(*util::get_snapshots(&poll.items).last().unwrap(), vector![9, 0, 10, 2, 3]);
+    assert_eq!(
+        util::get_vector_diffs(&poll.items),
+        vec![
+            VectorDiff::Extend { index: 1, snapshot_index: 2, count: 3 },
+            VectorDiff::Insert { index: 0, snapshot_index: 0 },
+        ]
+    );
+
+    // Once every signal has pulled a batch, changes inside it are emitted as usual.
+    input_vec.write().extend(vec![4, 5]);
+    util::poll_all(&mut signal);
+    input_vec.write().remove(5);
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(
+        util::get_vector_diffs(&poll.items),
+        vec![VectorDiff::Remove { index: 5, snapshot_index: 5 }]
+    );
+
+    // Inserting inside a pending batch would split it up, so that becomes a Replace.
+    input_vec.write().extend(vec![6, 7]);
+    input_vec.write().insert(7, 8);
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(util::get_vector_diffs(&poll.items), vec![VectorDiff::Replace {}]);
+    assert_eq!(
+        *util::get_snapshots(&poll.items).last().unwrap(),
+        vector![9, 0, 10, 2, 3, 5, 6, 8, 7]
+    );
+}
+
+#[test]
+fn extend_is_expanded_downstream() {
+    let input_vec = MutableVector::<u8>::new();
+    input_vec.write().push_back(1);
+    let mut mapped = input_vec.as_signal().map(|v| v * 10);
+    let mut signal_vec = input_vec.as_signal().to_signal_vec();
+    let mut cx = Context::from_waker(noop_waker_ref());
+    util::poll_all(&mut mapped);
+    assert_eq!(
+        Pin::new(&mut signal_vec).poll_vec_change(&mut cx),
+        Poll::Ready(Some(VecDiff::Replace { values: vec![1] }))
+    );
+
+    input_vec.write().extend(vec![2, 3]);
+    let poll = util::poll_all(&mut mapped);
+    assert_eq!(*util::get_snapshots(&poll.items).last().unwrap(), vector![10, 20, 30]);
+    assert_eq!(
+        util::get_vector_diffs(&poll.items),
+        vec![
+            VectorDiff::Insert { index: 1, snapshot_index: 1 },
+            VectorDiff::Insert { index: 2, snapshot_index: 2 },
+        ]
+    );
+
+    assert_eq!(
+        Pin::new(&mut signal_vec).poll_vec_change(&mut cx),
+        Poll::Ready(Some(VecDiff::Push { value: 2 }))
+    );
+    assert_eq!(
+        Pin::new(&mut signal_vec).poll_vec_change(&mut cx),
+        Poll::Ready(Some(VecDiff::Push { value: 3 }))
+    );
+    assert_eq!(Pin::new(&mut signal_vec).poll_vec_change(&mut cx), Poll::Pending);
+}
+
+#[test]
+fn move_item_emits_move_diffs() {
+    let input_vec = MutableVector::<char>::new();
+    input_vec.write().replace(vec!['a', 'b', 'c', 'd'].into_iter());
+    let mut signal = input_vec.as_signal();
+    let mut mapped = input_vec.as_signal().map(|c| c.to_ascii_uppercase());
+    let mut signal_vec = input_vec.as_signal().to_signal_vec();
+    let mut cx = Context::from_waker(noop_waker_ref());
+    util::poll_all(&mut signal);
+    util::poll_all(&mut mapped);
+    assert!(Pin::new(&mut signal_vec).poll_vec_change(&mut cx).is_ready());
+
+    input_vec.write().move_item(0, 2);
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(*util::get_snapshots(&poll.items).last().unwrap(), vector!['b', 'c', 'a', 'd']);
+    assert_eq!(
+        util::get_vector_diffs(&poll.items),
+        vec![VectorDiff::Move { from: 0, to: 2, snapshot_from: 0, snapshot_to: 2 }]
+    );
+    let poll = util::poll_all(&mut mapped);
+    assert_eq!(*util::get_snapshots(&poll.items).last().unwrap(), vector!['B', 'C', 'A', 'D']);
+    assert_eq!(
+        Pin::new(&mut signal_vec).poll_vec_change(&mut cx),
+        Poll::Ready(Some(VecDiff::Move { old_index: 0, new_index: 2 }))
+    );
+
+    // Moves are never merged, even when a move is followed by its inverse.
+    input_vec.write().move_item(1, 3);
+    input_vec.write().move_item(3, 1);
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(*util::get_snapshots(&poll.items).last().unwrap(), vector!['b', 'c', 'a', 'd']);
+    assert_eq!(
+        util::get_vector_diffs(&poll.items),
+        vec![
+            VectorDiff::Move { from: 1, to: 3, snapshot_from: 2, snapshot_to: 1 },
+            VectorDiff::Move { from: 3, to: 1, snapshot_from: 3, snapshot_to: 1 },
+        ]
+    );
+    util::poll_all(&mut mapped);
+    Pin::new(&mut signal_vec).poll_vec_change(&mut cx);
+    Pin::new(&mut signal_vec).poll_vec_change(&mut cx);
+
+    input_vec.write().swap(0, 3);
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(*util::get_snapshots(&poll.items).last().unwrap(), vector!['d', 'c', 'a', 'b']);
+    let poll = util::poll_all(&mut mapped);
+    assert_eq!(*util::get_snapshots(&poll.items).last().unwrap(), vector!['D', 'C', 'A', 'B']);
+    assert_eq!(
+        Pin::new(&mut signal_vec).poll_vec_change(&mut cx),
+        Poll::Ready(Some(VecDiff::Move { old_index: 3, new_index: 0 }))
+    );
+    assert_eq!(
+        Pin::new(&mut signal_vec).poll_vec_change(&mut cx),
+        Poll::Ready(Some(VecDiff::Move { old_index: 1, new_index: 3 }))
+    );
+}
+
+#[test]
+fn move_item_after_other_moves() {
+    let input_vec = MutableVector::<u8>::new();
+    input_vec.write().replace(vec![75, 2, 201, 154].into_iter());
+    let mut signal = input_vec.as_signal();
+    util::poll_all(&mut signal);
+
+    input_vec.write().move_item(3, 3);
+    input_vec.write().move_item(2, 3);
+    input_vec.write().move_item(2, 0);
+    input_vec.write().move_item(3, 2);
+    input_vec.write().push_back(201);
+    let poll = util::poll_all(&mut signal);
+    let snapshot = util::get_snapshots(&poll.items).last().unwrap().clone();
+    assert_eq!(snapshot, vector![154, 75, 201, 2, 201]);
+
+    let mut replayed = vector![75, 2, 201, 154];
+    for diff in util::get_vector_diffs(&poll.items) {
+        replayed.apply_diff(&diff, &snapshot);
+    }
+    assert_eq!(replayed, snapshot);
+}
+
+#[test]
+fn remove_after_move_is_replace() {
+    let input_vec = MutableVector::<u8>::new();
+    input_vec.write().replace(vec![1, 2, 3].into_iter());
+    let mut signal = input_vec.as_signal();
+    util::poll_all(&mut signal);
+
+    input_vec.write().insert(2, 4);
+    input_vec.write().move_item(3, 0);
+    input_vec.write().remove(3);
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(util::get_vector_diffs(&poll.items), vec![VectorDiff::Replace {}]);
+    assert_eq!(*util::get_snapshots(&poll.items).last().unwrap(), vector![3, 1, 2]);
+
+    // Once the move has been pulled, removes are granular again.
+    input_vec.write().remove(0);
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(
+        util::get_vector_diffs(&poll.items),
+        vec![VectorDiff::Remove { index: 0, snapshot_index: 0 }]
+    );
+}
+
+// Replays Move diffs onto a copy of the original values, failing on any other diff.
+fn apply_moves<T: Clone>(original: &im::Vector<T>, diffs: Vec<VectorDiff>) -> im::Vector<T> {
+    let mut values = original.clone();
+    for diff in diffs {
+        match diff {
+            VectorDiff::Move { from, to, .. } => {
+                let value = values.remove(from);
+                values.insert(to, value);
+            }
+            _ => panic!("Expected only Move diffs"),
+        }
+    }
+    values
+}
+
+#[test]
+fn reverse_emits_moves() {
+    let input_vec = MutableVector::<u8>::new();
+    input_vec.write().replace(vec![1, 2, 3, 4].into_iter());
+    let mut signal = input_vec.as_signal();
+    util::poll_all(&mut signal);
+
+    input_vec.write().reverse();
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(*util::get_snapshots(&poll.items).last().unwrap(), vector![4, 3, 2, 1]);
+    let diffs = util::get_vector_diffs(&poll.items);
+    assert_eq!(diffs.len(), 3);
+    assert_eq!(apply_moves(&vector![1, 2, 3, 4], diffs), vector![4, 3, 2, 1]);
+}
+
+#[test]
+fn rotate_and_sort_emit_minimal_moves() {
+    let input_vec = MutableVector::<u8>::new();
+    input_vec.write().replace(vec![1, 2, 3, 4].into_iter());
+    let mut signal = input_vec.as_signal();
+    util::poll_all(&mut signal);
+
+    input_vec.write().rotate_left(1);
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(
+        util::get_vector_diffs(&poll.items),
+        vec![VectorDiff::Move { from: 0, to: 3, snapshot_from: 0, snapshot_to: 3 }]
+    );
+
+    input_vec.write().rotate_right(1);
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(
+        util::get_vector_diffs(&poll.items),
+        vec![VectorDiff::Move { from: 3, to: 0, snapshot_from: 3, snapshot_to: 0 }]
+    );
+
+    input_vec.write().replace(vec![3, 1, 2, 5, 4].into_iter());
+    util::poll_all(&mut signal);
+    input_vec.write().sort();
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(*util::get_snapshots(&poll.items).last().unwrap(), vector![1, 2, 3, 4, 5]);
+    let diffs = util::get_vector_diffs(&poll.items);
+    assert_eq!(diffs.len(), 2);
+    assert_eq!(apply_moves(&vector![3, 1, 2, 5, 4], diffs), vector![1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn reorders_mixed_with_other_changes_stay_in_sync() {
+    let input_vec = MutableVector::<u8>::new();
+    let mut mapped = input_vec.as_signal().map(|v| *v as u16 * 10);
+    let mut signal_vec = input_vec.as_signal().to_signal_vec();
+    let mut cx = Context::from_waker(noop_waker_ref());
+    let mut values = vec![];
+
+    // Each round makes several changes before a single poll.
+    let rounds: Vec<Box<dyn Fn(&mut MutableVectorState<u8>)>> = vec![
+        Box::new(|state| {
+            state.sort();
+            state.swap(3, 0);
+            state.reverse();
+        }),
+        Box::new(|state| {
+            state.rotate_left(4);
+            state.insert(1, 4);
+            state.sort_by(|a, b| b.cmp(a));
+        }),
+        Box::new(|state| {
+            state.reverse();
+            state.rotate_right(1);
+            state.rotate_right(2);
+        }),
+        Box::new(|state| {
+            state.push_front(0);
+            state.swap(1, 4);
+            state.rotate_left(2);
+            state.set(1, 6);
+            state.push_back(7);
+        }),
+    ];
+    for round in rounds {
+        input_vec.write().replace(vec![5, 3, 8, 1, 9, 2].into_iter());
+        util::poll_all(&mut mapped);
+        while let Poll::Ready(Some(diff)) = Pin::new(&mut signal_vec).poll_vec_change(&mut cx) {
+            apply_vec_diff(&mut values, diff);
+        }
+
+        round(&mut input_vec.write());
+        let expected: Vec<u8> = input_vec.read().snapshot().into_iter().collect();
+
+        let poll = util::poll_all(&mut mapped);
+        let mapped_values: Vec<u16> =
+            util::get_snapshots(&poll.items).last().unwrap().iter().cloned().collect();
+        assert_eq!(
+            mapped_values,
+            expected.iter().map(|v| *v as u16 * 10).collect::<Vec<u16>>()
+        );
+
+        while let Poll::Ready(Some(diff)) = Pin::new(&mut signal_vec).poll_vec_change(&mut cx) {
+            apply_vec_diff(&mut values, diff);
+        }
+        assert_eq!(values, expected);
+    }
+}
+
+#[test]
+fn retain_removes_from_the_back() {
+    let input_vec = MutableVector::<u8>::new();
+    input_vec.write().replace(vec![1, 2, 3, 4, 5, 6].into_iter());
+    let mut signal = input_vec.as_signal();
+    util::poll_all(&mut signal);
+
+    input_vec.write().retain(|v| v % 2 == 0);
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(*util::get_snapshots(&poll.items).last().unwrap(), vector![2, 4, 6]);
+    let removed_indices: Vec<usize> = util::get_vector_diffs(&poll.items)
+        .into_iter()
+        .map(|diff| match diff {
+            VectorDiff::Remove { index, .. } => index,
+            _ => panic!("Expected only Remove diffs"),
+        })
+        .collect();
+    assert_eq!(removed_indices, vec![4, 2, 0]);
+
+    input_vec.write().retain(|_v| true);
+    assert!(util::poll_all(&mut signal).items.is_empty());
+
+    input_vec.write().retain(|_v| false);
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(util::get_vector_diffs(&poll.items), vec![VectorDiff::Clear {}]);
+}
+
+#[test]
+fn from_iter() {
+    let input_vec = MutableVector::from_iter(vec![1, 2, 3]);
+    assert_eq!(input_vec.read().snapshot(), vector![1, 2, 3]);
+
+    let collected: MutableVector<u8> = (1..4).collect();
+    let mut signal = collected.as_signal();
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(util::get_snapshots(&poll.items), vec![vector![1, 2, 3]]);
+    assert_eq!(util::get_vector_diffs(&poll.items), vec![VectorDiff::Replace {}]);
+}
+
+#[test]
+fn index_signals_track_positions() {
+    let input_vec = MutableVector::<u8>::new();
+    input_vec.write().replace(vec![1, 2, 3].into_iter());
+    let index_signals = input_vec.as_signal().index_signals();
+    let mut first = index_signals.signal_for_index(0);
+    let mut second = index_signals.signal_for_index(1);
+    let mut fourth = index_signals.signal_for_index(3);
+    let mut cx = Context::from_waker(noop_waker_ref());
+    assert_eq!(Pin::new(&mut first).poll_change(&mut cx), Poll::Ready(Some(1)));
+    assert_eq!(Pin::new(&mut second).poll_change(&mut cx), Poll::Ready(Some(2)));
+    assert_eq!(Pin::new(&mut fourth).poll_change(&mut cx), Poll::Pending);
+
+    // Only the updated index fires.
+    input_vec.write().set(1, 20);
+    assert_eq!(Pin::new(&mut first).poll_change(&mut cx), Poll::Pending);
+    assert_eq!(Pin::new(&mut second).poll_change(&mut cx), Poll::Ready(Some(20)));
+    assert_eq!(Pin::new(&mut second).poll_change(&mut cx), Poll::Pending);
+
+    // Inserting shifts new values into every later index.
+    input_vec.write().insert(1, 10);
+    assert_eq!(Pin::new(&mut first).poll_change(&mut cx), Poll::Pending);
+    assert_eq!(Pin::new(&mut second).poll_change(&mut cx), Poll::Ready(Some(10)));
+    assert_eq!(Pin::new(&mut fourth).poll_change(&mut cx), Poll::Ready(Some(3)));
+}
+
+#[test]
+fn get_out_of_bounds() {
+    let input_vec = MutableVector::from_iter(vec![1, 2]);
+    assert_eq!(input_vec.read().get(1), Some(&2));
+    assert_eq!(input_vec.read().get(2), None);
+    assert_eq!(input_vec.read()[0usize], 1);
+}
+
+#[test]
+fn diff_vectors_replays_onto_old() {
+    let cases = vec![
+        (vector![1, 2, 3], vector![0, 1, 2, 3, 4]),
+        (vector![0, 1, 2, 3, 4], vector![1, 3]),
+        (vector![1, 2, 3, 4], vector![4, 3, 2, 1]),
+        (vector![1, 2, 3], vector![1, 5, 3]),
+        (vector![], vector![1, 2]),
+        (vector![1, 2], vector![]),
+    ];
+    for (old, new) in cases {
+        let mut replayed = old.clone();
+        for diff in diff_vectors(&old, &new).iter() {
+            replayed.apply_diff(diff, &new);
+        }
+        assert_eq!(replayed, new, "Diffs from {:?} did not produce {:?}", old, new);
+    }
+
+    assert_eq!(
+        diff_vectors(&vector![1, 2, 3], &vector![1, 5, 3]),
+        vec![VectorDiff::Update { index: 1, snapshot_index: 1 }]
+    );
+    assert_eq!(
+        diff_vectors(&vector![0, 1, 2], &vector![1, 2, 3]),
+        vec![
+            VectorDiff::Remove { index: 0, snapshot_index: 0 },
+            VectorDiff::Insert { index: 2, snapshot_index: 2 },
+        ]
+    );
+}
+
+#[test]
+fn get_signal_for_index_shifts() {
+    let input_vec = MutableVector::<u8>::new();
+    input_vec.write().replace(vec![1, 2, 3].into_iter());
+    let mut second = input_vec.as_signal().get_signal_for_index(1);
+    let mut cx = Context::from_waker(noop_waker_ref());
+    assert_eq!(Pin::new(&mut second).poll_change(&mut cx), Poll::Ready(Some(Some(2))));
+
+    // Changes after the index are ignored.
+    input_vec.write().set(2, 30);
+    input_vec.write().push_back(4);
+    assert_eq!(Pin::new(&mut second).poll_change(&mut cx), Poll::Pending);
+
+    // Inserting before the index shifts a new value into it.
+    input_vec.write().insert(0, 0);
+    assert_eq!(Pin::new(&mut second).poll_change(&mut cx), Poll::Ready(Some(Some(1))));
+
+    // Swapping in an equal value does not fire.
+    input_vec.write().set(1, 1);
+    assert_eq!(Pin::new(&mut second).poll_change(&mut cx), Poll::Pending);
+
+    input_vec.write().clear();
+    assert_eq!(Pin::new(&mut second).poll_change(&mut cx), Poll::Ready(Some(None)));
+}
+
+#[test]
+fn batch_produces_one_event() {
+    let input_vec = MutableVector::<u8>::new();
+    let mut signal = input_vec.as_signal();
+    util::poll_all(&mut signal);
+
+    input_vec.batch(|| {
+        input_vec.write().push_back(1);
+        input_vec.batch(|| {
+            input_vec.write().push_back(2);
+            input_vec.write().remove(0);
+        });
+        // Polling between writes inside a batch gets nothing.
+        assert!(util::poll_all(&mut signal).items.is_empty());
+        input_vec.write().push_back(3);
+    });
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(poll.items.len(), 1);
+    assert_eq!(poll.items[0].snapshot, vector![2, 3]);
+}
+
+#[test]
+fn batch_ends_when_closure_panics() {
+    let input_vec = MutableVector::<u8>::new();
+    let mut signal = input_vec.as_signal();
+    util::poll_all(&mut signal);
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        input_vec.batch(|| {
+            input_vec.write().push_back(1);
+            panic!("failed mid-batch");
+        });
+    }));
+    assert!(result.is_err());
+
+    input_vec.write().push_back(2);
+    let poll = util::poll_all(&mut signal);
+    assert_eq!(*util::get_snapshots(&poll.items).last().unwrap(), vector![1, 2]);
+}
+
+#[test]
+fn compares_current_contents() {
+    let first = MutableVector::from_iter(vec![1, 2]);
+    let second = MutableVector::from_iter(vec![1]);
+    assert_ne!(first, second);
+    assert_eq!(first, first);
+
+    second.write().push_back(2);
+    assert_eq!(first, second);
+    assert_eq!(first, vector![1, 2]);
+}